@@ -1,23 +1,58 @@
+use crate::application::{ColumnMapping, ConflictResolution, ExportScope, ItemSettingsInput};
+use crate::domain::{CategoryId, ItemId, ValueType};
+use crate::infrastructure::IpcCommand;
+
 /// アプリケーション内で発生する操作
 #[derive(Debug, Clone)]
 pub enum Action {
     // --- モーダル表示リクエスト ---
-    ShowAddCategoryModal,                  // カテゴリ追加
-    ShowEditCategoryModal(String),         // カテゴリ名変更 (対象カテゴリ名)
-    ShowAddItemModal(String),              // 項目追加 (親カテゴリ名)
-    ShowEditItemModal(String, String),     // 項目編集 (カテゴリ名, 項目名)
-    ShowDeleteCategoryConfirm(String),     // カテゴリ削除 (対象カテゴリ名)
-    ShowDeleteItemConfirm(String, String), // 項目削除 (カテゴリ名, 項目名)
-    ShowDeleteScoreConfirm(usize),         // スコア削除 (対象インデックス)
+    ShowAddCategoryModal,                      // カテゴリ追加
+    ShowEditCategoryModal(CategoryId),         // カテゴリ名変更 (対象カテゴリ)
+    ShowAddItemModal(CategoryId),              // 項目追加 (親カテゴリ)
+    ShowEditItemModal(CategoryId, ItemId),     // 項目編集 (カテゴリ, 項目)
+    ShowDeleteCategoryConfirm(CategoryId),     // カテゴリ削除 (対象カテゴリ)
+    ShowDeleteItemConfirm(CategoryId, ItemId), // 項目削除 (カテゴリ, 項目)
+    ShowDeleteScoreConfirm(usize),             // スコア削除 (対象インデックス)
+    ShowEditScoreModal(usize),                 // スコア修正 (対象インデックス)
+    ShowEditScoreNoteModal(usize),             // メモ・タグ編集 (対象インデックス)
+    ShowRestoreBackupModal,                    // バックアップから復元
+    ShowCsvExportModal(ExportScope),           // CSV書き出し (対象範囲)
+    ShowCsvImportModal,                        // CSV取り込み
+    ShowAddProfileModal,                       // プロファイル作成
 
     // --- データ操作リクエスト ---
-    SelectItem(String, String),        // 項目選択 (カテゴリ名, 項目名)
-    AddCategory(String),               // カテゴリ追加 (名前, 減衰率)
-    RenameCategory(String, String),    // カテゴリ名変更 (旧名, 新名)
-    AddItem(String, String, String),   // 項目追加実行 (カテゴリ名, 項目名, 減衰率)
-    AddScore(String),                  // スコア追加 (スコア)
-    ExecuteDeleteCategory(String),     // カテゴリ削除実行
-    ExecuteDeleteItem(String, String), // 項目削除
-    ExecuteDeleteScore(usize),         // スコア削除実行
-    UpdateItem(String, String, String, String, String), // 項目更新
+    SelectItem(CategoryId, ItemId),        // 項目選択 (カテゴリ, 項目)
+    SelectCategory(CategoryId),            // カテゴリ選択 (カテゴリ) ※カテゴリ概要を表示
+    AddCategory(String),                   // カテゴリ追加 (名前)
+    RenameCategory(CategoryId, String),    // カテゴリ名変更 (対象カテゴリ, 新名)
+    AddScore(String, String),              // スコア追加 (スコア, 記録日時)
+    ExecuteDeleteCategory(CategoryId),     // カテゴリ削除実行
+    ExecuteDeleteItem(CategoryId, ItemId), // 項目削除
+    ExecuteDeleteScore(usize),             // スコア削除実行
+    UpdateScore(usize, String, String),    // スコア修正 (インデックス, スコア, 記録日時)
+    UpdateScoreNote(usize, String, String), // メモ・タグ更新 (インデックス, メモ, タグ)
+    Undo,                                  // 元に戻す (Ctrl+Z)
+    Redo,                                  // やり直し (Ctrl+Y)
+    RestoreBackup(String),                 // バックアップから復元 (バックアップID)
+    OpenDataFile(String),                  // セーフモード: 別のデータファイルを開く (パス)
+    StartFresh,                            // セーフモード: 空のデータで新しく始める
+    ExportCsv(ExportScope, String),        // CSV書き出し (対象範囲, パス)
+    PreviewCsvImport(String, Option<ColumnMapping>), // CSV取り込みの確認 (パス, 列の割り当て ※未指定なら推測)
+    ImportCsv(String, ColumnMapping),                // CSV取り込み実行 (パス, 列の割り当て)
+    SwitchProfile(String),                           // プロファイル切り替え (名前)
+    CreateProfile(String),                           // プロファイル作成して切り替え (名前)
+    ResolveConflict(ConflictResolution),             // 外部の変更との競合を解決 (解決方法)
+    RetryLock,                                       // 読み取り専用: 使用中ロックを取り直す
+    ApplyIpcCommand(IpcCommand), // ほかのプロセスから受け付けた操作を反映 (操作)
+
+    // 項目追加実行 (カテゴリ, 項目名, 減衰率, 値の種類)
+    AddItem(CategoryId, String, String, ValueType),
+    // 項目更新 (旧カテゴリ, 項目, 新カテゴリ, 新項目名, 設定値)
+    UpdateItem(
+        CategoryId,
+        ItemId,
+        CategoryId,
+        String,
+        Box<ItemSettingsInput>,
+    ),
 }
@@ -1,21 +1,59 @@
 use crate::action::Action;
-use crate::application::TrackerService;
-use crate::constants::DATA_FILENAME;
-use crate::infrastructure::JsonFileStore;
+use crate::application::{
+    AppError, ColumnMapping, ConflictResolution, DataStore, ExportScope, ExternalChange,
+    ItemSettingsInput, TrackerService, format_timestamp_input,
+};
+use crate::constants::{
+    DATA_FILENAME, DEFAULT_PROFILE, EXTERNAL_CHANGE_CHECK_SECS, LOCK_REFRESH_SECS,
+};
+use crate::domain::{CategoryId, ItemId, ValueType};
+use crate::infrastructure::{
+    BackupPolicy, DataLocation, DataLock, IpcCommand, IpcListener, IpcReply, IpcRequest,
+    JsonFileStore, LockState, open_store_with_backups, read_csv, write_csv,
+};
+use crate::launch::LaunchOptions;
+use crate::ui::banner;
 use crate::ui::central_panel::CentralPanel;
 use crate::ui::modals::ModalLayer;
 use crate::ui::modals::add_category::AddCategoryModal;
 use crate::ui::modals::add_item::AddItemModal;
+use crate::ui::modals::add_profile::AddProfileModal;
 use crate::ui::modals::confirm::ConfirmationModal;
+use crate::ui::modals::conflict::ConflictModal;
+use crate::ui::modals::csv_export::CsvExportModal;
+use crate::ui::modals::csv_import::CsvImportModal;
 use crate::ui::modals::edit_category::EditCategoryModal;
 use crate::ui::modals::edit_item::EditItemModal;
+use crate::ui::modals::edit_score::EditScoreModal;
+use crate::ui::modals::edit_score_note::EditScoreNoteModal;
+use crate::ui::modals::restore_backup::RestoreBackupModal;
+use crate::ui::modals::safe_mode::SafeModeModal;
 use crate::ui::side_panel::SidePanel;
 use crate::ui::state::UiState;
 use eframe::egui;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// アプリで使うサービス（ストアはデータファイルの拡張子で選ぶ）
+type Service = TrackerService<Box<dyn DataStore>>;
 
 // アプリケーション状態保存
 pub struct WeightedScoreTracker {
-    service: TrackerService<JsonFileStore>,
+    service: Service,
+    /// データファイルの使用中ロック（読み取り専用で開いている場合は None）
+    lock: Option<DataLock>,
+    /// ほかのプロセスからの操作の受付（ロックを取れなかった場合は None）
+    ipc: Option<IpcListener>,
+    /// 操作を受け付けたときに再描画を予約するためのコンテキスト
+    egui_ctx: egui::Context,
+    data_path: String,
+    location: DataLocation,
+    /// 保存場所の設定から読み込んだバックアップの保持件数
+    backup_policy: BackupPolicy,
+    profile: String,
+    profiles: Vec<String>,
+    last_external_check: Instant,
+    last_lock_refresh: Instant,
     state: UiState,
 
     side_panel: SidePanel,
@@ -24,24 +62,103 @@ pub struct WeightedScoreTracker {
 }
 
 impl WeightedScoreTracker {
-    pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
+    pub fn new(cc: &eframe::CreationContext<'_>, launch: LaunchOptions) -> Self {
         let mut state = UiState::default();
+        let legacy_dir = launch.data_dir.is_none();
+        let location = DataLocation::resolve(launch.data_dir);
+
+        // 以前のバージョンは起動したフォルダに保存していたため、既定のプロファイルへ移す
+        if legacy_dir && let Err(err) = location.adopt_legacy_file(Path::new(DATA_FILENAME)) {
+            state.error_message = Some(err.to_string());
+        }
 
-        let service = match TrackerService::new(JsonFileStore::new(DATA_FILENAME)) {
-            Ok(service) => service,
+        // 設定を読み込めない場合は既定の保持件数で続ける
+        let backup_policy = location.backup_policy().unwrap_or_else(|err| {
+            state.error_message = Some(err.to_string());
+            BackupPolicy::default()
+        });
+
+        let requested = launch.profile.unwrap_or_else(|| location.last_profile());
+        let opened = location.prepare(&requested).and_then(|path| {
+            Self::start_service(&path, backup_policy).map(|started| (path, started))
+        });
+        let (profile, path, (service, lock)) = match opened {
+            Ok((path, started)) => (requested, path, started),
             Err(err) => {
+                // 開けない場合は既定のプロファイルのファイルを読み取り専用で開く
+                // （ロックを取れていないため、ほかのインスタンスと同時に書き込まないようにする）
                 state.error_message = Some(err.to_string());
-                TrackerService::empty(JsonFileStore::new(DATA_FILENAME))
+                let path = location.data_path(DEFAULT_PROFILE);
+                let store: Box<dyn DataStore> = Box::new(JsonFileStore::new(&path));
+                let started = (TrackerService::open_read_only(store, err.to_string()), None);
+                (DEFAULT_PROFILE.to_string(), path, started)
             }
         };
+        let profiles = location.list_profiles();
 
-        Self {
+        let mut app = Self {
             service,
+            lock,
+            ipc: None,
+            egui_ctx: cc.egui_ctx.clone(),
+            data_path: path.display().to_string(),
+            location,
+            backup_policy,
+            profile,
+            profiles,
+            last_external_check: Instant::now(),
+            last_lock_refresh: Instant::now(),
             state,
 
             side_panel: SidePanel::new(),
             central_panel: CentralPanel::new(),
             modal_layer: ModalLayer::new(),
+        };
+        app.listen();
+        app
+    }
+
+    /// データファイルのロックを取ってストアを開く
+    ///
+    /// ほかのインスタンスが使用中なら読み取り専用、読み込めない場合はセーフモードで開始する。
+    fn start_service(
+        path: &Path,
+        backup_policy: BackupPolicy,
+    ) -> Result<(Service, Option<DataLock>), AppError> {
+        // ストアを開く前にロックを取る（SQLite の初回取り込みなども書き込みにあたるため）
+        let lock = DataLock::acquire(path)?;
+        let writable = matches!(lock, LockState::Acquired(_));
+        let store = open_store_with_backups(path, backup_policy, writable)?;
+        Ok(match lock {
+            LockState::Acquired(lock) => (TrackerService::open(store), Some(lock)),
+            LockState::HeldByOther(owner) => (
+                TrackerService::open_read_only(store, owner.describe()),
+                None,
+            ),
+        })
+    }
+
+    /// データファイルを開き直す（前のファイルのロックは新しいロックを取った後に解放する）
+    fn reopen(&mut self, path: PathBuf) -> Result<(), AppError> {
+        let (service, lock) = Self::start_service(&path, self.backup_policy)?;
+        self.service = service;
+        self.ipc = None;
+        self.lock = lock;
+        self.data_path = path.display().to_string();
+        self.central_panel.clear_input();
+        self.listen();
+        Ok(())
+    }
+
+    /// ロックを取れていれば、ほかのプロセスからの操作を受け付け始める
+    fn listen(&mut self) {
+        let Some(lock) = &mut self.lock else {
+            return;
+        };
+        let ctx = self.egui_ctx.clone();
+        match IpcListener::start(lock, move || ctx.request_repaint()) {
+            Ok(listener) => self.ipc = Some(listener),
+            Err(err) => self.state.error_message = Some(err.to_string()),
         }
     }
 
@@ -51,46 +168,110 @@ impl WeightedScoreTracker {
             Action::ShowAddCategoryModal => {
                 self.modal_layer.open(AddCategoryModal::new());
             }
-            Action::ShowAddItemModal(cat_name) => {
-                self.modal_layer.open(AddItemModal::new(cat_name));
-            }
-            Action::ShowEditCategoryModal(cat_name) => {
-                self.modal_layer.open(EditCategoryModal::new(cat_name));
-            }
-            Action::ShowEditItemModal(cat_name, item_name) => {
+            Action::ShowAddItemModal(cat) => match self.category_name(cat) {
+                Ok(cat_name) => self.modal_layer.open(AddItemModal::new(cat, cat_name)),
+                Err(e) => self.state.error_message = Some(e),
+            },
+            Action::ShowEditCategoryModal(cat) => match self.category_name(cat) {
+                Ok(cat_name) => self.modal_layer.open(EditCategoryModal::new(cat, cat_name)),
+                Err(e) => self.state.error_message = Some(e),
+            },
+            Action::ShowEditItemModal(cat, item) => {
                 // モデルからデータを取得してモーダルに渡す
-                match self.service.model().get_item(&cat_name, &item_name) {
-                    Ok(item) => {
-                        let decay_rate = item.decay_rate;
+                match self.service.model().get_item(cat, item) {
+                    Ok(item_data) => {
+                        let settings = ItemSettingsInput::from_item(item_data);
                         let mut categories: Vec<_> = self
                             .service
                             .model()
                             .data
                             .categories
-                            .keys()
-                            .cloned()
+                            .iter()
+                            .map(|(id, cat)| (*id, cat.name.clone()))
                             .collect();
-                        categories.sort();
+                        categories.sort_by(|a, b| a.1.cmp(&b.1));
 
                         self.modal_layer.open(EditItemModal::new(
-                            cat_name, item_name, decay_rate, categories,
+                            (cat, item),
+                            item_data.name.clone(),
+                            settings,
+                            categories,
                         ));
                     }
                     Err(e) => self.state.error_message = Some(e.to_string()),
                 }
             }
-            Action::ShowDeleteCategoryConfirm(cat_name) => {
-                self.modal_layer
-                    .open(ConfirmationModal::new_delete_category(cat_name));
-            }
-            Action::ShowDeleteItemConfirm(cat_name, item_name) => {
-                self.modal_layer
-                    .open(ConfirmationModal::new_delete_item(cat_name, item_name));
+            Action::ShowDeleteCategoryConfirm(cat) => match self.category_name(cat) {
+                Ok(cat_name) => self
+                    .modal_layer
+                    .open(ConfirmationModal::new_delete_category(cat, &cat_name)),
+                Err(e) => self.state.error_message = Some(e),
+            },
+            Action::ShowDeleteItemConfirm(cat, item) => {
+                match self.service.model().get_item(cat, item) {
+                    Ok(item_data) => {
+                        let modal = ConfirmationModal::new_delete_item(cat, item, &item_data.name);
+                        self.modal_layer.open(modal);
+                    }
+                    Err(e) => self.state.error_message = Some(e.to_string()),
+                }
             }
             Action::ShowDeleteScoreConfirm(index) => {
                 self.modal_layer
                     .open(ConfirmationModal::new_delete_score(index));
             }
+            Action::ShowEditScoreModal(index) => {
+                let item = self.service.model().selected_item();
+                let entry = item.and_then(|item| Some((item.value_type, item.scores.get(index)?)));
+                match entry {
+                    Some((value_type, entry)) => {
+                        self.modal_layer.open(EditScoreModal::new(
+                            index,
+                            value_type.format_plain(entry.score),
+                            format_timestamp_input(entry.timestamp),
+                        ));
+                    }
+                    None => {
+                        self.state.error_message =
+                            Some("編集対象のスコアが見つかりません。".to_string())
+                    }
+                }
+            }
+            Action::ShowEditScoreNoteModal(index) => {
+                let entry = self
+                    .service
+                    .model()
+                    .selected_item()
+                    .and_then(|item| item.scores.get(index));
+                match entry {
+                    Some(entry) => {
+                        self.modal_layer.open(EditScoreNoteModal::new(
+                            index,
+                            entry.note.clone().unwrap_or_default(),
+                            entry.tags.join(", "),
+                        ));
+                    }
+                    None => {
+                        self.state.error_message =
+                            Some("編集対象のスコアが見つかりません。".to_string())
+                    }
+                }
+            }
+
+            Action::ShowRestoreBackupModal => match self.service.list_backups() {
+                Ok(backups) => self.modal_layer.open(RestoreBackupModal::new(backups)),
+                Err(e) => self.state.error_message = Some(e.to_string()),
+            },
+            Action::ShowCsvExportModal(scope) => match self.export_scope_label(scope) {
+                Ok(label) => self.modal_layer.open(CsvExportModal::new(scope, label)),
+                Err(e) => self.state.error_message = Some(e),
+            },
+            Action::ShowCsvImportModal => {
+                self.modal_layer.open(CsvImportModal::new(String::new()));
+            }
+            Action::ShowAddProfileModal => {
+                self.modal_layer.open(AddProfileModal::new());
+            }
 
             // データ操作系
             Action::SelectItem(cat, item) => {
@@ -99,19 +280,67 @@ impl WeightedScoreTracker {
                 // カテゴリが変わったら入力欄をリセット
                 self.central_panel.clear_input();
             }
+            Action::SelectCategory(cat) => {
+                self.service.select_category(cat);
+                self.central_panel.clear_input();
+            }
             Action::AddCategory(name) => self.add_category(name),
-            Action::RenameCategory(old_name, new_name) => self.rename_category(old_name, new_name),
-            Action::AddItem(cat, name, decay) => self.add_item(cat, name, decay),
-            Action::AddScore(text) => self.add_score(text),
-            Action::UpdateItem(old_cat, old_item, new_cat, new_name, decay_str) => {
-                self.update_item(old_cat, old_item, new_cat, new_name, decay_str);
+            Action::RenameCategory(cat, new_name) => self.rename_category(cat, new_name),
+            Action::AddItem(cat, name, decay, value_type) => {
+                self.add_item(cat, name, decay, value_type)
+            }
+            Action::AddScore(text, timestamp) => self.add_score(text, timestamp),
+            Action::UpdateItem(old_cat, old_item, new_cat, new_name, settings) => {
+                self.update_item(old_cat, old_item, new_cat, new_name, *settings);
             }
-            Action::ExecuteDeleteCategory(name) => self.execute_delete_category(name),
+            Action::UpdateScore(idx, score, timestamp) => self.update_score(idx, score, timestamp),
+            Action::UpdateScoreNote(idx, note, tags) => self.update_score_note(idx, note, tags),
+            Action::ExecuteDeleteCategory(cat) => self.execute_delete_category(cat),
             Action::ExecuteDeleteItem(cat, item) => self.execute_delete_item(cat, item),
             Action::ExecuteDeleteScore(idx) => self.execute_delete_score(idx),
+            Action::Undo => self.undo(),
+            Action::Redo => self.redo(),
+            Action::RestoreBackup(id) => self.restore_backup(id),
+            Action::OpenDataFile(path) => self.open_data_file(path),
+            Action::StartFresh => self.start_fresh(),
+            Action::ExportCsv(scope, path) => self.export_csv(scope, path),
+            Action::PreviewCsvImport(path, mapping) => self.preview_csv_import(path, mapping),
+            Action::ImportCsv(path, mapping) => self.import_csv(path, mapping),
+            Action::SwitchProfile(name) => self.switch_profile(name),
+            Action::CreateProfile(name) => self.create_profile(name),
+            Action::ResolveConflict(resolution) => self.resolve_conflict(resolution),
+            Action::RetryLock => self.retry_lock(),
+            Action::ApplyIpcCommand(command) => self.apply_ipc_command(command),
         };
     }
 
+    /// モーダル表示用にカテゴリ名を取得
+    fn category_name(&self, cat: CategoryId) -> Result<String, String> {
+        self.service
+            .model()
+            .data
+            .get_category(cat)
+            .map(|category| category.name.clone())
+            .map_err(|e| e.to_string())
+    }
+
+    /// CSV書き出しモーダル用に対象範囲の表示名を取得
+    fn export_scope_label(&self, scope: ExportScope) -> Result<String, String> {
+        match scope {
+            ExportScope::All => Ok("すべての項目".to_string()),
+            ExportScope::Category(cat) => self.category_name(cat),
+            ExportScope::Item(cat, item) => {
+                let cat_name = self.category_name(cat)?;
+                let item_data = self
+                    .service
+                    .model()
+                    .get_item(cat, item)
+                    .map_err(|e| e.to_string())?;
+                Ok(format!("{} / {}", cat_name, item_data.name))
+            }
+        }
+    }
+
     // ======================================
     // データ操作
     // ======================================
@@ -124,22 +353,28 @@ impl WeightedScoreTracker {
     }
 
     /// カテゴリ名変更
-    fn rename_category(&mut self, old_name: String, new_name: String) {
-        if let Err(err) = self.service.rename_category(&old_name, new_name) {
+    fn rename_category(&mut self, cat: CategoryId, new_name: String) {
+        if let Err(err) = self.service.rename_category(cat, new_name) {
             self.state.error_message = Some(err.to_string());
         }
     }
 
     /// 項目追加
-    fn add_item(&mut self, cat_name: String, name: String, decay_str: String) {
-        if let Err(err) = self.service.add_item(&cat_name, name, &decay_str) {
+    fn add_item(
+        &mut self,
+        cat: CategoryId,
+        name: String,
+        decay_str: String,
+        value_type: ValueType,
+    ) {
+        if let Err(err) = self.service.add_item(cat, name, &decay_str, value_type) {
             self.state.error_message = Some(err.to_string());
         }
     }
 
     /// スコア追加
-    fn add_score(&mut self, text: String) {
-        match self.service.add_score_to_selection(&text) {
+    fn add_score(&mut self, text: String, timestamp: String) {
+        match self.service.add_score_to_selection(&text, &timestamp) {
             Ok(_) => {
                 self.central_panel.clear_input();
             }
@@ -150,30 +385,50 @@ impl WeightedScoreTracker {
     /// 項目の更新処理
     fn update_item(
         &mut self,
-        old_cat: String,
-        old_item: String,
-        new_cat: String,
-        new_item: String,
-        decay_str: String,
+        old_cat: CategoryId,
+        old_item: ItemId,
+        new_cat: CategoryId,
+        new_name: String,
+        settings: ItemSettingsInput,
     ) {
-        let old_loc = (old_cat.as_str(), old_item.as_str());
-        let new_loc = (new_cat.as_str(), new_item.as_str());
+        if let Err(err) =
+            self.service
+                .update_item((old_cat, old_item), new_cat, new_name, &settings)
+        {
+            self.state.error_message = Some(err.to_string());
+        }
+    }
 
-        if let Err(err) = self.service.update_item(old_loc, new_loc, &decay_str) {
+    /// スコア修正
+    fn update_score(&mut self, idx: usize, score: String, timestamp: String) {
+        if let Err(err) = self
+            .service
+            .update_score_on_selection(idx, &score, &timestamp)
+        {
+            self.state.error_message = Some(err.to_string());
+        }
+    }
+
+    /// スコアのメモ・タグ更新
+    fn update_score_note(&mut self, idx: usize, note: String, tags: String) {
+        if let Err(err) = self
+            .service
+            .update_score_note_on_selection(idx, note, &tags)
+        {
             self.state.error_message = Some(err.to_string());
         }
     }
 
     /// カテゴリ削除実行
-    fn execute_delete_category(&mut self, name: String) {
-        if let Err(err) = self.service.delete_category(&name) {
+    fn execute_delete_category(&mut self, cat: CategoryId) {
+        if let Err(err) = self.service.delete_category(cat) {
             self.state.error_message = Some(err.to_string());
         }
     }
 
     /// 項目削除
-    fn execute_delete_item(&mut self, cat: String, item: String) {
-        if let Err(err) = self.service.delete_item(&cat, &item) {
+    fn execute_delete_item(&mut self, cat: CategoryId, item: ItemId) {
+        if let Err(err) = self.service.delete_item(cat, item) {
             self.state.error_message = Some(err.to_string());
         }
     }
@@ -184,27 +439,298 @@ impl WeightedScoreTracker {
             self.state.error_message = Some(err.to_string());
         }
     }
+
+    /// 元に戻す
+    fn undo(&mut self) {
+        if let Err(err) = self.service.undo() {
+            self.state.error_message = Some(err.to_string());
+        }
+    }
+
+    /// やり直し
+    fn redo(&mut self) {
+        if let Err(err) = self.service.redo() {
+            self.state.error_message = Some(err.to_string());
+        }
+    }
+
+    /// バックアップから復元
+    fn restore_backup(&mut self, id: String) {
+        if let Err(err) = self.service.restore_from_backup(&id) {
+            self.state.error_message = Some(err.to_string());
+        }
+        self.central_panel.clear_input();
+    }
+
+    /// 別のデータファイルを開く（.db / .sqlite は SQLite、それ以外は JSON。読み込めなければ再びセーフモード）
+    fn open_data_file(&mut self, path: String) {
+        let path = path.trim().to_string();
+        if path.is_empty() {
+            self.state.error_message = Some("ファイルのパスを入力してください。".to_string());
+            return;
+        }
+
+        if let Err(err) = self.reopen(PathBuf::from(path)) {
+            self.state.error_message = Some(err.to_string());
+        }
+    }
+
+    /// 読み取り専用で開いているデータファイルのロックを取り直す
+    fn retry_lock(&mut self) {
+        if let Err(err) = self.reopen(PathBuf::from(&self.data_path)) {
+            self.state.error_message = Some(err.to_string());
+        }
+    }
+
+    /// 使用中ロックを一定間隔で更新する（更新が途絶えたロックはほかのインスタンスに残骸とみなされる）
+    fn refresh_lock(&mut self, ctx: &egui::Context) {
+        let Some(lock) = &self.lock else {
+            return;
+        };
+        let interval = Duration::from_secs(LOCK_REFRESH_SECS);
+        ctx.request_repaint_after(interval);
+        if self.last_lock_refresh.elapsed() < interval {
+            return;
+        }
+        self.last_lock_refresh = Instant::now();
+
+        match lock.refresh() {
+            Ok(()) => {}
+            // ロックを失った場合は、ほかのインスタンスと同時に書き込まないように読み取り専用にする
+            Err(AppError::Conflict(reason)) => {
+                self.ipc = None;
+                self.lock = None;
+                self.service.make_read_only(reason.clone());
+                self.state.error_message = Some(reason);
+            }
+            Err(err) => self.state.error_message = Some(err.to_string()),
+        }
+    }
+
+    /// ほかのプロセスから受け付けた操作を反映し、送り主に結果を返す
+    fn apply_ipc_command(&mut self, command: IpcCommand) {
+        let result = match &command.request {
+            IpcRequest::AddScore {
+                category,
+                item,
+                score,
+                timestamp,
+                note,
+                tags,
+            } => self
+                .service
+                .add_score_to_item((category, item), score, timestamp, note.clone(), tags)
+                .and_then(|entry| {
+                    let (cat, item) = self.service.find_item(category, item)?;
+                    let value_type = self.service.model().get_item(cat, item)?.value_type;
+                    Ok(IpcReply::Added { entry, value_type })
+                }),
+        };
+        command.reply(result.unwrap_or_else(IpcReply::Failed));
+    }
+
+    /// CSV書き出し
+    fn export_csv(&mut self, scope: ExportScope, path: String) {
+        let result = self
+            .service
+            .export_csv(scope)
+            .and_then(|text| write_csv(Path::new(&path), &text));
+        match result {
+            Ok(()) => self.state.notice_message = Some(format!("{} に書き出しました。", path)),
+            Err(err) => self.state.error_message = Some(err.to_string()),
+        }
+    }
+
+    /// CSVを読み込んで取り込み件数を確認する（割り当てが未指定なら見出しから推測）
+    fn preview_csv_import(&mut self, path: String, mapping: Option<ColumnMapping>) {
+        let table = match read_csv(Path::new(&path)) {
+            Ok(table) => table,
+            Err(err) => {
+                self.state.error_message = Some(err.to_string());
+                self.modal_layer.open(CsvImportModal::new(path));
+                return;
+            }
+        };
+
+        let mapping = mapping.unwrap_or_else(|| ColumnMapping::guess(&table.headers));
+        let preview = self
+            .service
+            .preview_csv_import(&table, &mapping)
+            .map_err(|e| e.to_string());
+        self.modal_layer
+            .open(CsvImportModal::new(path).with_preview(table.headers, mapping, preview));
+    }
+
+    /// CSV取り込み
+    fn import_csv(&mut self, path: String, mapping: ColumnMapping) {
+        let result =
+            read_csv(Path::new(&path)).and_then(|table| self.service.import_csv(&table, &mapping));
+        match result {
+            Ok(preview) => {
+                self.state.notice_message = Some(format!(
+                    "{} 件のスコアを取り込みました（重複 {} 件・エラー {} 件は取り込んでいません）。",
+                    preview.imported(),
+                    preview.duplicates(),
+                    preview.errors.len()
+                ));
+            }
+            Err(err) => self.state.error_message = Some(err.to_string()),
+        }
+        self.central_panel.clear_input();
+    }
+
+    /// プロファイルを切り替える（再起動せずに別のストアを開き直す）
+    fn switch_profile(&mut self, name: String) {
+        let name = name.trim().to_string();
+        let opened = self
+            .location
+            .prepare(&name)
+            .and_then(|path| self.reopen(path));
+        if let Err(err) = opened {
+            self.state.error_message = Some(err.to_string());
+            return;
+        }
+        self.profile = name;
+
+        if let Err(err) = self.location.remember_profile(&self.profile) {
+            self.state.error_message = Some(err.to_string());
+        }
+        self.profiles = self.location.list_profiles();
+    }
+
+    /// 新しいプロファイルを作成して切り替える
+    fn create_profile(&mut self, name: String) {
+        let name = name.trim().to_string();
+        if self.profiles.contains(&name) {
+            self.state.error_message = Some(format!("プロファイル「{}」は既に存在します。", name));
+            return;
+        }
+        self.switch_profile(name);
+    }
+
+    /// 外部の変更との競合を解決
+    fn resolve_conflict(&mut self, resolution: ConflictResolution) {
+        if let Err(err) = self.service.resolve_conflict(resolution) {
+            self.state.error_message = Some(err.to_string());
+        }
+        self.central_panel.clear_input();
+    }
+
+    /// ほかのプログラムによるデータファイルの変更を一定間隔で確認する
+    fn check_external_changes(&mut self, ctx: &egui::Context) {
+        let interval = Duration::from_secs(EXTERNAL_CHANGE_CHECK_SECS);
+        // 操作がなくても確認できるように再描画を予約する
+        ctx.request_repaint_after(interval);
+        if self.last_external_check.elapsed() < interval {
+            return;
+        }
+        self.last_external_check = Instant::now();
+
+        match self.service.check_external_changes() {
+            Ok(ExternalChange::Reloaded) => self.central_panel.clear_input(),
+            Ok(ExternalChange::Unchanged | ExternalChange::Conflict) => {}
+            Err(err) => self.state.error_message = Some(err.to_string()),
+        }
+    }
+
+    /// 空のデータで新しく始める
+    fn start_fresh(&mut self) {
+        if let Err(err) = self.service.start_fresh() {
+            self.state.error_message = Some(err.to_string());
+        }
+        self.central_panel.clear_input();
+    }
+
+    /// Ctrl+Z / Ctrl+Y の検出（テキスト入力中は入力欄自身の操作を優先する）
+    fn shortcut_action(ctx: &egui::Context) -> Option<Action> {
+        if ctx.wants_keyboard_input() {
+            return None;
+        }
+
+        let undo = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Z);
+        let redo = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Y);
+        ctx.input_mut(|i| {
+            if i.consume_shortcut(&undo) {
+                Some(Action::Undo)
+            } else if i.consume_shortcut(&redo) {
+                Some(Action::Redo)
+            } else {
+                None
+            }
+        })
+    }
 }
 
 impl eframe::App for WeightedScoreTracker {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.refresh_lock(ctx);
+
+        // ほかのプロセスから受け付けた操作はすぐに反映する
+        while let Some(command) = self.ipc.as_ref().and_then(IpcListener::try_recv) {
+            self.handle_action(Action::ApplyIpcCommand(command));
+        }
+
+        // セーフモード中は復元方法の選択を表示し続ける
+        if let Some(safe_mode) = self.service.safe_mode()
+            && !self.modal_layer.is_open()
+        {
+            self.modal_layer.open(SafeModeModal::new(
+                safe_mode.message.clone(),
+                self.data_path.clone(),
+            ));
+        }
+
+        // 競合中は解決方法の選択を表示し続ける
+        if self.state.error_message.is_none() && !self.modal_layer.is_open() {
+            self.check_external_changes(ctx);
+            if let Some(conflict) = self.service.conflict() {
+                self.modal_layer
+                    .open(ConflictModal::new(conflict.message.clone()));
+            }
+        }
+
         // モーダルが開いているかどうか（通常モーダル or エラーメッセージ）
         let is_modal_open = self.modal_layer.is_open() || self.state.error_message.is_some();
         let is_panel_enabled = !is_modal_open; // 開いている場合は無効化
 
-        let side_act = self
-            .side_panel
-            .show(ctx, self.service.model(), is_panel_enabled);
+        let banner_act = self
+            .service
+            .read_only()
+            .and_then(|reason| banner::show_read_only(ctx, reason, is_panel_enabled));
+
+        let history = (self.service.can_undo(), self.service.can_redo());
+        let side_act = self.side_panel.show(
+            ctx,
+            self.service.model(),
+            (&self.profile, &self.profiles),
+            history,
+            is_panel_enabled,
+        );
         let central_act = self
             .central_panel
             .show(ctx, self.service.model(), is_panel_enabled);
 
         let modal_act = self.modal_layer.show(ctx, &mut self.state);
 
-        let action = modal_act.or(side_act).or(central_act);
+        let shortcut_act = is_panel_enabled
+            .then(|| Self::shortcut_action(ctx))
+            .flatten();
+
+        let action = modal_act
+            .or(banner_act)
+            .or(side_act)
+            .or(central_act)
+            .or(shortcut_act);
 
         if let Some(act) = action {
             self.handle_action(act);
         }
+
+        // バックアップの失敗は保存の失敗とは分けて知らせる
+        if let Some(warning) = self.service.take_backup_warning()
+            && self.state.notice_message.is_none()
+        {
+            self.state.notice_message = Some(warning);
+        }
     }
 }
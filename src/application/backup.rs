@@ -0,0 +1,36 @@
+use chrono::{DateTime, Utc};
+
+/// バックアップの種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackupKind {
+    /// 直近の保存ごと
+    Recent,
+    /// 1日1件（その日の最初の保存）
+    Daily,
+    /// 1週間に1件（その週の最初の保存）
+    Weekly,
+}
+
+impl BackupKind {
+    /// 画面表示用の名称
+    pub fn label(self) -> &'static str {
+        match self {
+            BackupKind::Recent => "直近",
+            BackupKind::Daily => "日次",
+            BackupKind::Weekly => "週次",
+        }
+    }
+}
+
+/// 復元画面に表示するバックアップの概要
+#[derive(Debug, Clone, PartialEq)]
+pub struct BackupInfo {
+    /// 復元時に指定する識別子
+    pub id: String,
+    /// バックアップの種類
+    pub kind: BackupKind,
+    /// 保存日時
+    pub saved_at: DateTime<Utc>,
+    /// (カテゴリ数, 項目数)。読み込めないバックアップは None
+    pub counts: Option<(usize, usize)>,
+}
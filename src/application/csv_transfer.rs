@@ -0,0 +1,566 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone, Utc};
+
+use crate::constants::{CSV_TIMESTAMP_FORMAT, DEFAULT_DECAY_RATE, MAX_DECIMAL_PRECISION};
+use crate::domain::{AppData, CategoryId, ItemData, ItemId, ValueType};
+
+use super::AppError;
+use super::service::{parse_score, parse_tags, parse_timestamp};
+
+/// 書き出す CSV の見出し
+pub const CSV_HEADERS: [&str; 6] = ["category", "item", "timestamp", "score", "note", "tags"];
+
+/// 書き出しの対象範囲
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportScope {
+    /// すべてのカテゴリ
+    All,
+    /// 1カテゴリ
+    Category(CategoryId),
+    /// 1項目
+    Item(CategoryId, ItemId),
+}
+
+/// スコア履歴を CSV（カテゴリ, 項目, 記録日時, スコア, メモ, タグ）に書き出す
+///
+/// カテゴリ・項目は名前順、スコアは記録日時の順に並べる。
+pub fn export_csv(data: &AppData, scope: ExportScope) -> Result<String, AppError> {
+    let mut targets: Vec<(&str, &ItemData)> = match scope {
+        ExportScope::All => data
+            .categories
+            .values()
+            .flat_map(|category| {
+                category
+                    .items
+                    .values()
+                    .map(|item| (category.name.as_str(), item))
+            })
+            .collect(),
+        ExportScope::Category(cat) => {
+            let category = data.get_category(cat)?;
+            category
+                .items
+                .values()
+                .map(|item| (category.name.as_str(), item))
+                .collect()
+        }
+        ExportScope::Item(cat, item) => {
+            vec![(
+                data.get_category(cat)?.name.as_str(),
+                data.get_item(cat, item)?,
+            )]
+        }
+    };
+    targets.sort_by(|a, b| (a.0, &a.1.name).cmp(&(b.0, &b.1.name)));
+
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(CSV_HEADERS).map_err(export_error)?;
+    for (category, item) in targets {
+        for entry in &item.scores {
+            let timestamp = entry
+                .timestamp
+                .with_timezone(&Local)
+                .format(CSV_TIMESTAMP_FORMAT)
+                .to_string();
+            writer
+                .write_record([
+                    category,
+                    item.name.as_str(),
+                    timestamp.as_str(),
+                    item.value_type.format_plain(entry.score).as_str(),
+                    entry.note.as_deref().unwrap_or_default(),
+                    entry.tags.join(", ").as_str(),
+                ])
+                .map_err(export_error)?;
+        }
+    }
+
+    let bytes = writer.into_inner().map_err(export_error)?;
+    String::from_utf8(bytes).map_err(export_error)
+}
+
+fn export_error(e: impl std::fmt::Display) -> AppError {
+    AppError::Persistence(format!("CSVの書き出しに失敗しました: {}", e))
+}
+
+/// 読み込んだ CSV（見出し行と各行の値）
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CsvTable {
+    /// 見出し行
+    pub headers: Vec<String>,
+    /// 見出しを除く各行の値
+    pub rows: Vec<Vec<String>>,
+}
+
+impl CsvTable {
+    /// CSV の文字列を解釈する（表計算ソフトが付ける BOM は無視する）
+    pub fn parse(text: &str) -> Result<Self, AppError> {
+        let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+        let mut reader = csv::ReaderBuilder::new()
+            .flexible(true)
+            .trim(csv::Trim::All)
+            .from_reader(text.as_bytes());
+
+        let read_error = |e: csv::Error| AppError::Input(format!("CSVを読み込めません: {}", e));
+        let headers = reader
+            .headers()
+            .map_err(read_error)?
+            .iter()
+            .map(str::to_string)
+            .collect();
+        let rows = reader
+            .records()
+            .map(|record| record.map(|r| r.iter().map(str::to_string).collect()))
+            .collect::<Result<_, _>>()
+            .map_err(read_error)?;
+
+        Ok(Self { headers, rows })
+    }
+}
+
+/// 取り込み時の列の割り当て（列番号。未割り当ては None）
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ColumnMapping {
+    /// カテゴリ名の列（必須）
+    pub category: Option<usize>,
+    /// 項目名の列（必須）
+    pub item: Option<usize>,
+    /// 記録日時の列（必須）
+    pub timestamp: Option<usize>,
+    /// スコアの列（必須）
+    pub score: Option<usize>,
+    /// メモの列
+    pub note: Option<usize>,
+    /// タグの列（カンマ区切り）
+    pub tags: Option<usize>,
+}
+
+/// 必須の列の割り当て（カテゴリ, 項目, 記録日時, スコア）
+struct RequiredColumns {
+    category: usize,
+    item: usize,
+    timestamp: usize,
+    score: usize,
+}
+
+impl ColumnMapping {
+    /// 見出しの名前から列を推測する（書き出した CSV はそのまま割り当てられる）
+    pub fn guess(headers: &[String]) -> Self {
+        let find = |names: &[&str]| {
+            headers.iter().position(|header| {
+                names
+                    .iter()
+                    .any(|name| header.trim().eq_ignore_ascii_case(name))
+            })
+        };
+
+        Self {
+            category: find(&["category", "カテゴリ"]),
+            item: find(&["item", "項目"]),
+            timestamp: find(&["timestamp", "date", "datetime", "日時", "記録日時"]),
+            score: find(&["score", "value", "スコア"]),
+            note: find(&["note", "memo", "メモ"]),
+            tags: find(&["tags", "tag", "タグ"]),
+        }
+    }
+
+    /// 割り当て欄の一覧（表示名, 必須かどうか, 割り当て）
+    pub fn fields_mut(&mut self) -> [(&'static str, bool, &mut Option<usize>); 6] {
+        [
+            ("カテゴリ", true, &mut self.category),
+            ("項目", true, &mut self.item),
+            ("記録日時", true, &mut self.timestamp),
+            ("スコア", true, &mut self.score),
+            ("メモ", false, &mut self.note),
+            ("タグ", false, &mut self.tags),
+        ]
+    }
+
+    fn required(&self) -> Result<RequiredColumns, AppError> {
+        let column = |value: Option<usize>, label: &str| {
+            value.ok_or_else(|| AppError::Input(format!("「{}」の列を選択してください。", label)))
+        };
+
+        Ok(RequiredColumns {
+            category: column(self.category, "カテゴリ")?,
+            item: column(self.item, "項目")?,
+            timestamp: column(self.timestamp, "記録日時")?,
+            score: column(self.score, "スコア")?,
+        })
+    }
+}
+
+/// 項目ごとの取り込み件数
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemImportCount {
+    /// カテゴリ名
+    pub category: String,
+    /// 項目名
+    pub item: String,
+    /// 取り込みで新しく作る項目かどうか
+    pub new_item: bool,
+    /// 取り込む件数
+    pub imported: usize,
+    /// 重複として除いた件数
+    pub duplicates: usize,
+}
+
+/// 取り込みの結果（ドライランではプレビュー）
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportPreview {
+    /// 項目ごとの件数
+    pub items: Vec<ItemImportCount>,
+    /// 取り込めない行（"<行番号> 行目: <理由>"）
+    pub errors: Vec<String>,
+}
+
+impl ImportPreview {
+    /// 取り込む件数の合計
+    pub fn imported(&self) -> usize {
+        self.items.iter().map(|count| count.imported).sum()
+    }
+
+    /// 重複として除いた件数の合計
+    pub fn duplicates(&self) -> usize {
+        self.items.iter().map(|count| count.duplicates).sum()
+    }
+}
+
+/// 取り込み先の項目
+struct ImportTarget {
+    cat: CategoryId,
+    item: ItemId,
+    new_category: bool,
+    new_item: bool,
+}
+
+/// 取り込み先（カテゴリ名, 項目名）ごとにまとめた行（行番号, 行）
+type RowGroup<'a> = ((String, String), Vec<(usize, &'a [String])>);
+
+fn cell(row: &[String], column: usize) -> &str {
+    row.get(column).map(String::as_str).unwrap_or_default()
+}
+
+/// CSV の各行を取り込んだ後のデータと、項目ごとの件数を求める（元のデータは変更しない）
+///
+/// 存在しないカテゴリ・項目は新しく作る。
+/// 同じ項目に記録日時（秒単位）とスコアが同じ記録がある行は、重複として取り込まない。
+pub fn plan_import(
+    data: &AppData,
+    table: &CsvTable,
+    mapping: &ColumnMapping,
+) -> Result<(AppData, ImportPreview), AppError> {
+    let columns = mapping.required()?;
+    let mut planned = data.clone();
+    let mut preview = ImportPreview::default();
+
+    // 取り込み先の項目ごとに行をまとめる（CSV に現れた順）
+    let mut groups: Vec<RowGroup> = Vec::new();
+    let mut group_index: HashMap<(String, String), usize> = HashMap::new();
+    for (index, row) in table.rows.iter().enumerate() {
+        let line = index + 2; // 見出し行の次から
+        let key = (
+            cell(row, columns.category).to_string(),
+            cell(row, columns.item).to_string(),
+        );
+        if key.0.is_empty() || key.1.is_empty() {
+            preview.errors.push(format!(
+                "{} 行目: カテゴリ名と項目名を入力してください。",
+                line
+            ));
+            continue;
+        }
+
+        let position = *group_index.entry(key.clone()).or_insert_with(|| {
+            groups.push((key, Vec::new()));
+            groups.len() - 1
+        });
+        groups[position].1.push((line, row));
+    }
+
+    for ((category, item), rows) in groups {
+        let scores = rows.iter().map(|(_, row)| cell(row, columns.score));
+        let target = match resolve_target(&mut planned, &category, &item, scores) {
+            Ok(target) => target,
+            Err(err) => {
+                preview.errors.extend(
+                    rows.iter()
+                        .map(|(line, _)| format!("{} 行目: {}", line, err)),
+                );
+                continue;
+            }
+        };
+
+        let mut count = ItemImportCount {
+            category,
+            item,
+            new_item: target.new_item,
+            imported: 0,
+            duplicates: 0,
+        };
+        for (line, row) in rows {
+            match import_row(&mut planned, &target, row, &columns, mapping) {
+                Ok(true) => count.imported += 1,
+                Ok(false) => count.duplicates += 1,
+                Err(err) => preview.errors.push(format!("{} 行目: {}", line, err)),
+            }
+        }
+
+        // 1件も取り込めなかった場合は、新しく作った項目・カテゴリを残さない
+        if count.imported == 0 && target.new_item {
+            planned.remove_item(target.cat, target.item)?;
+            if target.new_category {
+                planned.remove_category(target.cat)?;
+            }
+            if count.duplicates == 0 {
+                continue;
+            }
+        }
+        preview.items.push(count);
+    }
+
+    Ok((planned, preview))
+}
+
+/// 取り込み先の項目を探す（なければカテゴリ・項目を作る）
+fn resolve_target<'a>(
+    data: &mut AppData,
+    category: &str,
+    item: &str,
+    scores: impl Iterator<Item = &'a str>,
+) -> Result<ImportTarget, AppError> {
+    let (cat, new_category) = match data.find_category_id(category) {
+        Some(cat) => (cat, false),
+        None => (data.add_category(category.to_string())?, true),
+    };
+
+    if let Some(id) = data.get_category(cat)?.find_item_id(item) {
+        return Ok(ImportTarget {
+            cat,
+            item: id,
+            new_category,
+            new_item: false,
+        });
+    }
+
+    let id = data.add_item(
+        cat,
+        item.to_string(),
+        DEFAULT_DECAY_RATE,
+        infer_value_type(scores),
+    )?;
+    Ok(ImportTarget {
+        cat,
+        item: id,
+        new_category,
+        new_item: true,
+    })
+}
+
+/// 新しく作る項目の値の種類（小数点を含む値があれば、最も多い小数点以下の桁数）
+fn infer_value_type<'a>(scores: impl Iterator<Item = &'a str>) -> ValueType {
+    scores
+        .filter_map(|score| score.trim().split_once('.'))
+        .map(|(_, fraction)| fraction.trim_end_matches('0').len())
+        .max()
+        .map_or(ValueType::Integer, |digits| {
+            ValueType::Decimal(digits.clamp(1, MAX_DECIMAL_PRECISION as usize) as u8)
+        })
+}
+
+/// 1行を取り込む（重複していれば取り込まずに false）
+fn import_row(
+    data: &mut AppData,
+    target: &ImportTarget,
+    row: &[String],
+    columns: &RequiredColumns,
+    mapping: &ColumnMapping,
+) -> Result<bool, AppError> {
+    let item = data.get_item(target.cat, target.item)?;
+    let score = parse_score(cell(row, columns.score), item.value_type)?;
+    let timestamp = parse_csv_timestamp(cell(row, columns.timestamp))?;
+
+    let rounded = item.value_type.round(score);
+    let duplicate = item.scores.iter().any(|entry| {
+        entry.score == rounded && entry.timestamp.timestamp() == timestamp.timestamp()
+    });
+    if duplicate {
+        return Ok(false);
+    }
+
+    let note = mapping.note.map(|c| cell(row, c)).unwrap_or_default();
+    let tags = mapping
+        .tags
+        .map(|c| parse_tags(cell(row, c)))
+        .unwrap_or_default();
+    data.add_score_with_note(
+        target.cat,
+        target.item,
+        score,
+        timestamp,
+        note.to_string(),
+        tags,
+    )?;
+    Ok(true)
+}
+
+/// 記録日時の解釈（RFC 3339、書き出し形式、日時入力の形式の順に試す）
+fn parse_csv_timestamp(input: &str) -> Result<DateTime<Utc>, AppError> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err(AppError::Input("記録日時が空欄です。".to_string()));
+    }
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(input) {
+        return Ok(timestamp.with_timezone(&Utc));
+    }
+    if let Ok(naive) = NaiveDateTime::parse_from_str(input, CSV_TIMESTAMP_FORMAT)
+        && let Some(local) = Local.from_local_datetime(&naive).earliest()
+    {
+        return Ok(local.with_timezone(&Utc));
+    }
+    parse_timestamp(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn seed() -> (AppData, CategoryId, ItemId) {
+        let mut data = AppData::default();
+        let cat = data.add_category("Training".to_string()).unwrap();
+        let item = data
+            .add_item(cat, "Pushups".to_string(), 0.9, ValueType::Integer)
+            .unwrap();
+        let base = Utc::now() - Duration::days(2);
+        data.add_score(cat, item, 20.0, base).unwrap();
+        data.add_score_with_note(
+            cat,
+            item,
+            25.0,
+            base + Duration::days(1),
+            "good, form".to_string(),
+            vec!["朝".to_string(), "gym".to_string()],
+        )
+        .unwrap();
+        (data, cat, item)
+    }
+
+    fn table(text: &str) -> CsvTable {
+        CsvTable::parse(text).unwrap()
+    }
+
+    #[test]
+    fn export_writes_one_row_per_score_with_notes() {
+        // 書き出しで見出し行とスコアごとの行が出力され、カンマを含むメモもそのまま復元できることを確認する。
+        let (mut data, cat, _) = seed();
+        let other = data.add_category("Reading".to_string()).unwrap();
+        let pages = data
+            .add_item(other, "Pages".to_string(), 0.9, ValueType::Integer)
+            .unwrap();
+        data.add_score(other, pages, 30.0, Utc::now()).unwrap();
+
+        let all = table(&export_csv(&data, ExportScope::All).unwrap());
+        assert_eq!(all.headers, CSV_HEADERS);
+        assert_eq!(all.rows.len(), 3);
+        assert_eq!(all.rows[0][0], "Reading");
+        assert_eq!(all.rows[2][3], "25");
+        assert_eq!(all.rows[2][4], "good, form");
+        assert_eq!(all.rows[2][5], "朝, gym");
+
+        let category = table(&export_csv(&data, ExportScope::Category(cat)).unwrap());
+        assert_eq!(category.rows.len(), 2);
+        let single = table(&export_csv(&data, ExportScope::Item(other, pages)).unwrap());
+        assert_eq!(single.rows.len(), 1);
+        assert!(export_csv(&data, ExportScope::Item(cat, pages)).is_err());
+    }
+
+    #[test]
+    fn exported_csv_is_detected_as_duplicates_on_reimport() {
+        // 書き出した CSV を同じデータへ取り込むと、列が自動で割り当てられ全行が重複として扱われることを確認する。
+        let (data, _, _) = seed();
+        let csv = table(&export_csv(&data, ExportScope::All).unwrap());
+        let mapping = ColumnMapping::guess(&csv.headers);
+        assert_eq!(mapping.note, Some(4));
+
+        let (planned, preview) = plan_import(&data, &csv, &mapping).unwrap();
+        assert_eq!(preview.imported(), 0);
+        assert_eq!(preview.duplicates(), 2);
+        assert!(preview.errors.is_empty());
+        assert_eq!(planned, data);
+    }
+
+    #[test]
+    fn plan_import_counts_rows_per_item_and_creates_missing_items() {
+        // ドライランで項目ごとの件数が求まり、未登録の項目は小数点以下の桁数から値の種類を決めて作られることを確認する。
+        let (data, cat, item) = seed();
+        let csv = table(
+            "日付,種目,グループ,記録,メモ\n\
+             2024-01-01 08:00,Pushups,Training,30,\n\
+             2024-01-02,Sprint,Running,12.25,windy\n\
+             2024-01-03 07:30:15,Sprint,Running,11.9,\n\
+             2024-01-04,Sprint,Running,abc,\n\
+             2024-01-05,,Running,1,\n",
+        );
+        let mapping = ColumnMapping {
+            category: Some(2),
+            item: Some(1),
+            timestamp: Some(0),
+            score: Some(3),
+            note: Some(4),
+            tags: None,
+        };
+
+        let (planned, preview) = plan_import(&data, &csv, &mapping).unwrap();
+        assert_eq!(preview.imported(), 3);
+        assert_eq!(preview.errors.len(), 2);
+        assert!(preview.errors[0].starts_with("6 行目"));
+        assert!(preview.errors[1].starts_with("5 行目"));
+
+        let sprint = &preview.items[1];
+        assert_eq!((sprint.item.as_str(), sprint.new_item), ("Sprint", true));
+        assert_eq!(sprint.imported, 2);
+
+        assert_eq!(planned.get_item(cat, item).unwrap().scores.len(), 3);
+        let running = planned.find_category_id("Running").unwrap();
+        let category = planned.get_category(running).unwrap();
+        let sprint = category
+            .get_item(category.find_item_id("Sprint").unwrap())
+            .unwrap();
+        assert_eq!(sprint.value_type, ValueType::Decimal(2));
+        assert_eq!(sprint.scores[0].note.as_deref(), Some("windy"));
+    }
+
+    #[test]
+    fn plan_import_detects_duplicates_within_the_file() {
+        // 同じ CSV 内で記録日時とスコアが同じ行は、2件目以降が重複として数えられることを確認する。
+        let csv = table(
+            "category,item,timestamp,score\n\
+             A,X,2024-01-01 08:00,5\n\
+             A,X,2024-01-01 08:00,5\n\
+             A,X,2024-01-01 08:00,6\n",
+        );
+        let mapping = ColumnMapping::guess(&csv.headers);
+
+        let (_, preview) = plan_import(&AppData::default(), &csv, &mapping).unwrap();
+        assert_eq!(preview.imported(), 2);
+        assert_eq!(preview.duplicates(), 1);
+    }
+
+    #[test]
+    fn plan_import_requires_mapped_columns_and_drops_empty_new_items() {
+        // 必須の列が未割り当てならエラーになり、1件も取り込めない新しい項目は作られないことを確認する。
+        let csv = table("category,item,timestamp,score\nA,X,not-a-date,5\n");
+        let mut mapping = ColumnMapping::guess(&csv.headers);
+
+        let (planned, preview) = plan_import(&AppData::default(), &csv, &mapping).unwrap();
+        assert!(planned.categories.is_empty());
+        assert!(preview.items.is_empty());
+        assert_eq!(preview.errors.len(), 1);
+
+        mapping.score = None;
+        let err = plan_import(&AppData::default(), &csv, &mapping).unwrap_err();
+        assert!(matches!(err, AppError::Input(msg) if msg.contains("スコア")));
+    }
+}
@@ -1,12 +1,20 @@
 use std::fmt;
 
+use serde::{Deserialize, Serialize};
+
 use crate::domain::DomainError;
 
-#[derive(Debug, Clone, PartialEq)]
+/// アプリケーション層のエラー（表示用のメッセージを持つ）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum AppError {
+    /// 入力値を解釈できない
     Input(String),
+    /// ドメインの規則に反する操作・見つからない対象
     Domain(String),
+    /// 読み込み・保存の失敗、または保存できない状態
     Persistence(String),
+    /// 読み込み後にほかのプログラムがデータファイルを書き換えたため、保存しなかった
+    Conflict(String),
 }
 
 impl fmt::Display for AppError {
@@ -15,6 +23,7 @@ impl fmt::Display for AppError {
             AppError::Input(msg) => write!(f, "入力エラー: {}", msg),
             AppError::Domain(msg) => write!(f, "処理エラー: {}", msg),
             AppError::Persistence(msg) => write!(f, "永続化エラー: {}", msg),
+            AppError::Conflict(msg) => write!(f, "競合エラー: {}", msg),
         }
     }
 }
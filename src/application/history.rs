@@ -0,0 +1,98 @@
+use crate::constants::MAX_UNDO_HISTORY;
+use crate::domain::AppData;
+
+/// 元に戻す／やり直し用の履歴（変更前のデータのスナップショットを保持する）
+#[derive(Default)]
+pub struct EditHistory {
+    undo_stack: Vec<AppData>,
+    redo_stack: Vec<AppData>,
+}
+
+impl EditHistory {
+    /// 変更前のデータを記録する（新しい変更を行うとやり直し履歴は破棄される）
+    pub fn record(&mut self, before: AppData) {
+        self.undo_stack.push(before);
+        if self.undo_stack.len() > MAX_UNDO_HISTORY {
+            self.undo_stack.remove(0);
+        }
+        self.redo_stack.clear();
+    }
+
+    /// 現在のデータと引き換えに、1つ前のデータを取り出す
+    pub fn undo(&mut self, current: AppData) -> Option<AppData> {
+        let previous = self.undo_stack.pop()?;
+        self.redo_stack.push(current);
+        Some(previous)
+    }
+
+    /// 現在のデータと引き換えに、元に戻す前のデータを取り出す
+    pub fn redo(&mut self, current: AppData) -> Option<AppData> {
+        let next = self.redo_stack.pop()?;
+        self.undo_stack.push(current);
+        Some(next)
+    }
+
+    /// 元に戻せる変更があるかどうか
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    /// やり直せる変更があるかどうか
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data_with(names: &[&str]) -> AppData {
+        let mut data = AppData::default();
+        for name in names {
+            data.add_category(name.to_string()).unwrap();
+        }
+        data
+    }
+
+    #[test]
+    fn undo_and_redo_swap_snapshots() {
+        // 元に戻すと記録したデータが返り、やり直すと元に戻す前のデータが返ることを確認する。
+        let mut history = EditHistory::default();
+        history.record(data_with(&[]));
+
+        let restored = history.undo(data_with(&["A"])).unwrap();
+        assert!(restored.categories.is_empty());
+        assert!(history.can_redo());
+
+        let redone = history.redo(restored).unwrap();
+        assert!(redone.find_category_id("A").is_some());
+        assert!(history.can_undo());
+        assert!(!history.can_redo());
+    }
+
+    #[test]
+    fn new_record_clears_redo_stack() {
+        // 元に戻した後に新しい変更を記録すると、やり直し履歴が破棄されることを確認する。
+        let mut history = EditHistory::default();
+        history.record(data_with(&[]));
+        history.undo(data_with(&["A"])).unwrap();
+
+        history.record(data_with(&[]));
+        assert!(!history.can_redo());
+        assert!(history.redo(data_with(&["B"])).is_none());
+    }
+
+    #[test]
+    fn history_is_capped_at_max_depth() {
+        // 履歴の件数が上限を超えた場合、古いものから破棄されることを確認する。
+        let mut history = EditHistory::default();
+        (0..MAX_UNDO_HISTORY + 5).for_each(|_| history.record(AppData::default()));
+
+        let mut count = 0;
+        while history.undo(AppData::default()).is_some() {
+            count += 1;
+        }
+        assert_eq!(count, MAX_UNDO_HISTORY);
+    }
+}
@@ -1,7 +1,16 @@
+mod backup;
+mod csv_transfer;
 mod error;
+mod history;
 mod ports;
 mod service;
 
+pub use backup::{BackupInfo, BackupKind};
+pub use csv_transfer::{ColumnMapping, CsvTable, ExportScope, ImportPreview, ItemImportCount};
 pub use error::AppError;
+pub use history::EditHistory;
 pub use ports::DataStore;
-pub use service::TrackerService;
+pub use service::{
+    ConflictResolution, DataConflict, ExternalChange, ItemSettingsInput, SafeMode, TrackerService,
+    ValueRulesInput, format_timestamp_input,
+};
@@ -1,9 +1,69 @@
 use crate::domain::AppData;
 
-use super::AppError;
+use super::{AppError, BackupInfo};
 
 /// データ系の操作を行うためのインターフェース
 pub trait DataStore {
+    /// 読み込む（データファイルがまだなければ None）
     fn load(&self) -> Result<Option<AppData>, AppError>;
+    /// 保存する（最後の読み込み・保存の後にほかのプログラムが書き換えていれば AppError::Conflict）
     fn save(&self, data: &AppData) -> Result<(), AppError>;
+
+    /// 最後の読み込み・保存の後に、ほかのプログラムがデータを書き換えたかどうか
+    fn changed_externally(&self) -> Result<bool, AppError> {
+        Ok(false)
+    }
+
+    /// ほかのプログラムによる変更を無視して上書き保存する
+    fn save_overwriting(&self, data: &AppData) -> Result<(), AppError> {
+        self.save(data)
+    }
+
+    /// 読み込めなかったデータを退避し、退避先を返す（以降の保存で上書きされないように）
+    fn quarantine(&self) -> Result<String, AppError>;
+
+    /// バックアップの一覧（新しい順）
+    fn list_backups(&self) -> Result<Vec<BackupInfo>, AppError>;
+
+    /// 指定したバックアップのデータを読み込む
+    fn load_backup(&self, id: &str) -> Result<AppData, AppError>;
+
+    /// 保存後のバックアップに失敗していればその説明を取り出す（保存自体は成功している）
+    fn take_backup_warning(&self) -> Option<String> {
+        None
+    }
+}
+
+impl<T: DataStore + ?Sized> DataStore for Box<T> {
+    fn load(&self) -> Result<Option<AppData>, AppError> {
+        (**self).load()
+    }
+
+    fn save(&self, data: &AppData) -> Result<(), AppError> {
+        (**self).save(data)
+    }
+
+    fn changed_externally(&self) -> Result<bool, AppError> {
+        (**self).changed_externally()
+    }
+
+    fn save_overwriting(&self, data: &AppData) -> Result<(), AppError> {
+        (**self).save_overwriting(data)
+    }
+
+    fn quarantine(&self) -> Result<String, AppError> {
+        (**self).quarantine()
+    }
+
+    fn list_backups(&self) -> Result<Vec<BackupInfo>, AppError> {
+        (**self).list_backups()
+    }
+
+    fn load_backup(&self, id: &str) -> Result<AppData, AppError> {
+        (**self).load_backup(id)
+    }
+
+    fn take_backup_warning(&self) -> Option<String> {
+        (**self).take_backup_warning()
+    }
 }
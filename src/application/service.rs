@@ -1,105 +1,671 @@
-use crate::domain::TrackerModel;
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
 
-use super::{AppError, DataStore};
+use crate::constants::{
+    DATE_INPUT_FORMAT, DATE_INPUT_HINT, DEFAULT_HALF_LIFE_DAYS, DEFAULT_WINDOW_SIZE,
+    TIMESTAMP_INPUT_FORMAT, TIMESTAMP_INPUT_HINT,
+};
+use crate::domain::{
+    AppData, CategoryId, ItemData, ItemId, ItemSettings, ScoreDirection, ScoreEntry, TrackerModel,
+    ValueRules, ValueType, WeightingKind, WeightingMode, normalize_tags,
+};
 
+use super::csv_transfer::{ColumnMapping, CsvTable, ExportScope, ImportPreview};
+use super::csv_transfer::{export_csv, plan_import};
+use super::{AppError, BackupInfo, DataStore, EditHistory};
+
+/// 項目の入力条件の入力値（空欄は「条件なし」）
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ValueRulesInput {
+    /// 最小値
+    pub min: String,
+    /// 最大値
+    pub max: String,
+    /// 刻み幅
+    pub step: String,
+    /// マイナス値を受け付けるかどうか
+    pub allow_negative: bool,
+}
+
+impl ValueRulesInput {
+    /// 入力条件を値の種類の桁数で入力値にする
+    pub fn from_rules(rules: &ValueRules, value_type: ValueType) -> Self {
+        let format = |v: Option<f64>| v.map(|v| value_type.format_plain(v)).unwrap_or_default();
+        Self {
+            min: format(rules.min),
+            max: format(rules.max),
+            step: format(rules.step),
+            allow_negative: rules.allow_negative,
+        }
+    }
+
+    fn parse(&self) -> Result<ValueRules, AppError> {
+        Ok(ValueRules {
+            min: parse_optional_f64(&self.min, "最小値には数値を入力してください。")?,
+            max: parse_optional_f64(&self.max, "最大値には数値を入力してください。")?,
+            step: parse_optional_f64(&self.step, "刻み幅には数値を入力してください。")?,
+            allow_negative: self.allow_negative,
+        })
+    }
+}
+
+/// 項目編集の入力値
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemSettingsInput {
+    /// 減衰率
+    pub decay: String,
+    /// 値の種類
+    pub value_type: ValueType,
+    /// 入力条件
+    pub rules: ValueRulesInput,
+    /// 評価の向き
+    pub direction: ScoreDirection,
+    /// 重み付け方式
+    pub weighting_kind: WeightingKind,
+    /// 直近N件の件数
+    pub window_size: String,
+    /// 半減期（日）
+    pub half_life_days: String,
+    /// カテゴリ集計での重要度
+    pub importance: String,
+}
+
+impl ItemSettingsInput {
+    /// 項目の現在の設定を入力値にする
+    pub fn from_item(item: &ItemData) -> Self {
+        let settings = item.settings();
+        // 選択されていない方式の入力欄は既定値で埋める
+        let window_size = match settings.weighting {
+            WeightingMode::SlidingWindow { size } => size,
+            _ => DEFAULT_WINDOW_SIZE,
+        };
+        let half_life_days = match settings.weighting {
+            WeightingMode::TimeDecay { half_life_days } => half_life_days,
+            _ => DEFAULT_HALF_LIFE_DAYS,
+        };
+        Self {
+            decay: settings.decay_rate.to_string(),
+            value_type: settings.value_type,
+            rules: ValueRulesInput::from_rules(&settings.rules, settings.value_type),
+            direction: settings.direction,
+            weighting_kind: settings.weighting.kind(),
+            window_size: window_size.to_string(),
+            half_life_days: half_life_days.to_string(),
+            importance: settings.importance.to_string(),
+        }
+    }
+
+    fn parse(&self) -> Result<ItemSettings, AppError> {
+        // 選択中の方式で使わない入力値は無視する
+        let weighting =
+            match self.weighting_kind {
+                WeightingKind::Exponential => WeightingMode::Exponential,
+                WeightingKind::LinearRamp => WeightingMode::LinearRamp,
+                WeightingKind::SlidingWindow => WeightingMode::SlidingWindow {
+                    size: self.window_size.trim().parse().map_err(|_| {
+                        AppError::Input("件数には整数を入力してください。".to_string())
+                    })?,
+                },
+                WeightingKind::Uniform => WeightingMode::Uniform,
+                WeightingKind::TimeDecay => WeightingMode::TimeDecay {
+                    half_life_days: parse_f64(
+                        &self.half_life_days,
+                        "半減期には数値を入力してください。",
+                    )?,
+                },
+            };
+
+        Ok(ItemSettings {
+            decay_rate: parse_f64(&self.decay, "有効な数値を入力してください。")?,
+            value_type: self.value_type,
+            rules: self.rules.parse()?,
+            direction: self.direction,
+            weighting,
+            importance: parse_f64(&self.importance, "重要度には数値を入力してください。")?,
+        })
+    }
+}
+
+/// データファイルを読み込めなかった場合のセーフモード（復元方法が選ばれるまで保存しない）
+#[derive(Debug, Clone, PartialEq)]
+pub struct SafeMode {
+    /// 読み込みエラーと退避結果の説明
+    pub message: String,
+}
+
+/// ほかのプログラムによるデータファイルの変更との競合（解決方法が選ばれるまで保存しない）
+#[derive(Debug, Clone, PartialEq)]
+pub struct DataConflict {
+    /// 競合の説明
+    pub message: String,
+}
+
+/// 競合の解決方法
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConflictResolution {
+    /// ファイルの内容を読み込む（こちらの変更は元に戻すで取り戻せる）
+    UseFile,
+    /// こちらの内容で上書きする
+    KeepMine,
+    /// どちらかにしかないカテゴリ・項目・スコアをすべて残して保存する
+    Merge,
+}
+
+/// ほかのプログラムによる変更の確認結果
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExternalChange {
+    /// 変更なし
+    Unchanged,
+    /// 読み込み直した（元に戻すで取り消せる）
+    Reloaded,
+    /// 未保存の変更と競合したため、解決方法の選択を待っている
+    Conflict,
+}
+
+/// カテゴリ・項目・スコアを操作するサービス
+///
+/// 変更のたびにストアへ保存し、変更前のデータを元に戻す／やり直しの履歴に記録する。
+/// 読み込めないファイルはセーフモード、ほかのプログラムによる変更は競合として扱い、
+/// 解決方法が選ばれるまで保存しない。
 pub struct TrackerService<S: DataStore> {
     model: TrackerModel,
     store: S,
+    history: EditHistory,
+    safe_mode: Option<SafeMode>,
+    conflict: Option<DataConflict>,
+    /// 読み取り専用で開いている理由（ほかのインスタンスが使用中など）
+    read_only: Option<String>,
+    /// 最後の保存に失敗し、手元にだけ残っている変更があるかどうか
+    unsaved: bool,
 }
 
 impl<S: DataStore> TrackerService<S> {
-    pub fn new(store: S) -> Result<Self, AppError> {
-        let data = store.load()?.unwrap_or_default();
-        Ok(Self {
+    /// データを読み込んで開始する（読み込めなければファイルを退避してセーフモードで開始）
+    pub fn open(store: S) -> Self {
+        let load_error = match store.load() {
+            Ok(data) => return Self::with_data(store, data.unwrap_or_default()),
+            Err(err) => err,
+        };
+
+        let message = match store.quarantine() {
+            Ok(location) => format!(
+                "{}\n読み込めなかったファイルは「{}」に退避しました。",
+                load_error, location
+            ),
+            Err(err) => format!("{}\n{}", load_error, err),
+        };
+
+        Self {
+            safe_mode: Some(SafeMode { message }),
+            ..Self::with_data(store, Default::default())
+        }
+    }
+
+    fn with_data(store: S, data: AppData) -> Self {
+        Self {
             model: TrackerModel::new(data),
             store,
-        })
+            history: EditHistory::default(),
+            safe_mode: None,
+            conflict: None,
+            read_only: None,
+            unsaved: false,
+        }
     }
 
-    pub fn empty(store: S) -> Self {
+    /// データを読み込んで開始する（読み込めなければエラーを返し、ファイルは退避しない）
+    pub fn try_open(store: S) -> Result<Self, AppError> {
+        let data = store.load()?;
+        Ok(Self::with_data(store, data.unwrap_or_default()))
+    }
+
+    /// 読み取り専用で開始する（読み込めなくてもファイルは退避しない）
+    pub fn open_read_only(store: S, reason: String) -> Self {
+        let (data, reason) = match store.load() {
+            Ok(data) => (data.unwrap_or_default(), reason),
+            Err(err) => (Default::default(), format!("{}\n{}", reason, err)),
+        };
         Self {
-            model: TrackerModel::new(Default::default()),
-            store,
+            read_only: Some(reason),
+            ..Self::with_data(store, data)
+        }
+    }
+
+    /// 以降の変更を受け付けない読み取り専用に切り替える（データファイルのロックを失った場合など）
+    pub fn make_read_only(&mut self, reason: String) {
+        self.read_only = Some(reason);
+    }
+
+    /// 読み取り専用で開いている理由（書き込み可能なら None）
+    pub fn read_only(&self) -> Option<&str> {
+        self.read_only.as_deref()
+    }
+
+    /// 保存後のバックアップに失敗していればその説明を取り出す（保存自体は成功している）
+    pub fn take_backup_warning(&self) -> Option<String> {
+        self.store.take_backup_warning()
+    }
+
+    /// セーフモード中ならその説明
+    pub fn safe_mode(&self) -> Option<&SafeMode> {
+        self.safe_mode.as_ref()
+    }
+
+    /// 解決待ちの競合
+    pub fn conflict(&self) -> Option<&DataConflict> {
+        self.conflict.as_ref()
+    }
+
+    /// ほかのプログラムによるデータファイルの変更を確認する
+    ///
+    /// 未保存の変更がなければ読み込み直し（元に戻すことも可能）、あれば競合として解決方法の選択を待つ。
+    pub fn check_external_changes(&mut self) -> Result<ExternalChange, AppError> {
+        if self.safe_mode.is_some()
+            || self.conflict.is_some()
+            || !self.store.changed_externally()?
+        {
+            return Ok(ExternalChange::Unchanged);
+        }
+
+        if self.unsaved {
+            self.conflict = Some(DataConflict {
+                message: "ほかのプログラムがデータファイルを変更しましたが、こちらにも保存されていない変更があります。".to_string(),
+            });
+            return Ok(ExternalChange::Conflict);
+        }
+
+        match self.store.load() {
+            Ok(data) => {
+                let data = data.unwrap_or_default();
+                if data == self.model.data {
+                    return Ok(ExternalChange::Unchanged);
+                }
+                self.history.record(self.model.data.clone());
+                self.model.restore_data(data);
+                Ok(ExternalChange::Reloaded)
+            }
+            Err(err) => {
+                // 読み込めない内容で書き換えられた場合は、こちらの内容で上書きするか選んでもらう
+                self.conflict = Some(DataConflict {
+                    message: format!(
+                        "ほかのプログラムが変更したデータファイルを読み込めません。\n{}",
+                        err
+                    ),
+                });
+                Ok(ExternalChange::Conflict)
+            }
+        }
+    }
+
+    /// 競合を解決して保存する
+    pub fn resolve_conflict(&mut self, resolution: ConflictResolution) -> Result<(), AppError> {
+        self.ensure_writable()?;
+        let local = self.model.data.clone();
+        match resolution {
+            ConflictResolution::UseFile => {
+                let data = self.store.load()?.unwrap_or_default();
+                self.history.record(local);
+                self.model.restore_data(data);
+                self.unsaved = false;
+                self.conflict = None;
+                Ok(())
+            }
+            ConflictResolution::KeepMine => {
+                self.store.save_overwriting(&local)?;
+                self.unsaved = false;
+                self.conflict = None;
+                Ok(())
+            }
+            ConflictResolution::Merge => {
+                let external = self.store.load()?.unwrap_or_default();
+                let mut merged = local.clone();
+                merged.merge_missing_from(&external);
+                self.history.record(local);
+                self.model.restore_data(merged);
+                self.conflict = None;
+                self.persist()
+            }
+        }
+    }
+
+    /// 復元できるバックアップの一覧
+    pub fn list_backups(&self) -> Result<Vec<BackupInfo>, AppError> {
+        self.store.list_backups()
+    }
+
+    /// バックアップから復元して保存する
+    ///
+    /// 通常時は元に戻すことができる。セーフモード中であればセーフモードを解除する。
+    pub fn restore_from_backup(&mut self, backup_id: &str) -> Result<(), AppError> {
+        self.ensure_writable()?;
+        let data = self.store.load_backup(backup_id)?;
+
+        if self.safe_mode.take().is_none() {
+            self.history.record(self.model.data.clone());
         }
+        self.model.restore_data(data);
+        self.persist()
+    }
+
+    /// 空のデータで新しく始める（セーフモードを解除して保存する）
+    pub fn start_fresh(&mut self) -> Result<(), AppError> {
+        self.ensure_writable()?;
+        self.model = TrackerModel::new(Default::default());
+        self.history = EditHistory::default();
+        self.safe_mode = None;
+        self.persist()
     }
 
+    /// 現在のデータと選択状態
     pub fn model(&self) -> &TrackerModel {
         &self.model
     }
 
-    pub fn select_item(&mut self, category: String, item: String) {
+    /// 項目を選択する
+    pub fn select_item(&mut self, category: CategoryId, item: ItemId) {
         self.model.select_item(category, item);
     }
 
+    /// カテゴリを選択する
+    pub fn select_category(&mut self, category: CategoryId) {
+        self.model.select_category(category);
+    }
+
+    /// カテゴリを追加する
     pub fn add_category(&mut self, name: String) -> Result<(), AppError> {
+        let before = self.begin()?;
         self.model.add_category(name)?;
-        self.persist()
+        self.commit(before)
     }
 
-    pub fn rename_category(&mut self, old_name: &str, new_name: String) -> Result<(), AppError> {
-        self.model.rename_category(old_name, new_name)?;
-        self.persist()
+    /// カテゴリ名を変更する
+    pub fn rename_category(
+        &mut self,
+        category: CategoryId,
+        new_name: String,
+    ) -> Result<(), AppError> {
+        let before = self.begin()?;
+        self.model.rename_category(category, new_name)?;
+        self.commit(before)
     }
 
+    /// 項目を追加する（減衰率は入力値のまま受け取る）
     pub fn add_item(
         &mut self,
-        category: &str,
+        category: CategoryId,
         item_name: String,
         decay_input: &str,
+        value_type: ValueType,
     ) -> Result<(), AppError> {
         let decay_rate = parse_f64(decay_input, "有効な数値を入力してください。")?;
-        self.model.add_item(category, item_name, decay_rate)?;
-        self.persist()
+        let before = self.begin()?;
+        self.model
+            .add_item(category, item_name, decay_rate, value_type)?;
+        self.commit(before)
     }
 
-    pub fn add_score_to_selection(&mut self, score_input: &str) -> Result<(), AppError> {
+    /// 選択中の項目にスコアを追加（日時入力が空なら現在時刻）
+    pub fn add_score_to_selection(
+        &mut self,
+        score_input: &str,
+        timestamp_input: &str,
+    ) -> Result<(), AppError> {
         let (cat, item) = self
             .selected_item_pair()
             .ok_or_else(|| AppError::Domain("項目が選択されていません。".into()))?;
 
-        let score = parse_i64(score_input, "スコアには整数値を入力してください。")?;
-        self.model.add_score(&cat, &item, score)?;
-        self.persist()
+        let value_type = self.model.get_item(cat, item)?.value_type;
+        let score = parse_score(score_input, value_type)?;
+        let timestamp = parse_timestamp(timestamp_input)?;
+        let before = self.begin()?;
+        self.model.add_score(cat, item, score, timestamp)?;
+        self.commit(before)
+    }
+
+    /// カテゴリ名と項目名から項目を探す
+    pub fn find_item(
+        &self,
+        category_name: &str,
+        item_name: &str,
+    ) -> Result<(CategoryId, ItemId), AppError> {
+        let data = &self.model.data;
+        let cat = data.find_category_id(category_name).ok_or_else(|| {
+            AppError::Domain(format!("カテゴリ「{}」が見つかりません。", category_name))
+        })?;
+        let item = data
+            .get_category(cat)?
+            .find_item_id(item_name)
+            .ok_or_else(|| {
+                AppError::Domain(format!(
+                    "カテゴリ「{}」に項目「{}」が見つかりません。",
+                    category_name, item_name
+                ))
+            })?;
+        Ok((cat, item))
+    }
+
+    /// 名前で指定した項目にメモ・タグ付きでスコアを追加し、追加したスコアを返す（日時入力が空なら現在時刻）
+    pub fn add_score_to_item(
+        &mut self,
+        (category_name, item_name): (&str, &str),
+        score_input: &str,
+        timestamp_input: &str,
+        note: String,
+        tags_input: &str,
+    ) -> Result<ScoreEntry, AppError> {
+        let (cat, item) = self.find_item(category_name, item_name)?;
+        let value_type = self.model.get_item(cat, item)?.value_type;
+        let score = parse_score(score_input, value_type)?;
+        let timestamp = parse_timestamp(timestamp_input)?;
+        let tags = parse_tags(tags_input);
+
+        let before = self.begin()?;
+        self.model
+            .add_score_with_note(cat, item, score, timestamp, note, tags)?;
+        self.commit(before)?;
+
+        let scores = &self.model.get_item(cat, item)?.scores;
+        scores
+            .iter()
+            .rev()
+            .find(|entry| entry.timestamp == timestamp)
+            .cloned()
+            .ok_or_else(|| AppError::Domain("追加したスコアが見つかりません。".to_string()))
+    }
+
+    /// 選択中の項目のスコアを修正
+    ///
+    /// 日時入力が空の場合や元の記録日時の表示と同じ場合は、秒以下を含む元の日時を維持する。
+    pub fn update_score_on_selection(
+        &mut self,
+        index: usize,
+        score_input: &str,
+        timestamp_input: &str,
+    ) -> Result<(), AppError> {
+        let (cat, item) = self
+            .selected_item_pair()
+            .ok_or_else(|| AppError::Domain("項目が選択されていません。".into()))?;
+
+        let item_data = self.model.get_item(cat, item)?;
+        let score = parse_score(score_input, item_data.value_type)?;
+        let current = item_data.scores.get(index).map(|entry| entry.timestamp);
+        let input = timestamp_input.trim();
+        let timestamp = match current {
+            // 空欄は現在時刻ではなく「変更しない」として扱う
+            Some(ts) if input.is_empty() || format_timestamp_input(ts) == input => ts,
+            _ => parse_timestamp(input)?,
+        };
+
+        let before = self.begin()?;
+        self.model
+            .update_score(cat, item, index, score, timestamp)?;
+        self.commit(before)
     }
 
+    /// 項目の名前・所属カテゴリ・設定をまとめて変更する
     pub fn update_item(
         &mut self,
-        old_loc: (&str, &str),
-        new_loc: (&str, &str),
-        decay_input: &str,
+        old_loc: (CategoryId, ItemId),
+        new_category: CategoryId,
+        new_name: String,
+        settings_input: &ItemSettingsInput,
     ) -> Result<(), AppError> {
-        let decay = parse_f64(decay_input, "有効な数値を入力してください。")?;
-        self.model.update_item(old_loc, new_loc, decay)?;
-        self.persist()
+        let settings = settings_input.parse()?;
+        let before = self.begin()?;
+        self.model
+            .update_item(old_loc, new_category, new_name, settings)?;
+        self.commit(before)
     }
 
-    pub fn delete_category(&mut self, category_name: &str) -> Result<(), AppError> {
-        self.model.remove_category(category_name)?;
-        self.persist()
+    /// カテゴリを項目ごと削除する
+    pub fn delete_category(&mut self, category: CategoryId) -> Result<(), AppError> {
+        let before = self.begin()?;
+        self.model.remove_category(category)?;
+        self.commit(before)
     }
 
-    pub fn delete_item(&mut self, category: &str, item: &str) -> Result<(), AppError> {
+    /// 項目を削除する
+    pub fn delete_item(&mut self, category: CategoryId, item: ItemId) -> Result<(), AppError> {
+        let before = self.begin()?;
         self.model.remove_item(category, item)?;
-        self.persist()
+        self.commit(before)
     }
 
+    /// 選択中の項目のスコアを削除する
     pub fn delete_score_from_selection(&mut self, index: usize) -> Result<(), AppError> {
         let (cat, item) = self
             .selected_item_pair()
             .ok_or_else(|| AppError::Domain("項目が選択されていません。".into()))?;
 
-        self.model.remove_score(&cat, &item, index)?;
-        self.persist()
+        let before = self.begin()?;
+        self.model.remove_score(cat, item, index)?;
+        self.commit(before)
     }
 
-    fn selected_item_pair(&self) -> Option<(String, String)> {
-        let category = self.model.selection.category.clone()?;
-        let item = self.model.selection.item.clone()?;
+    /// 選択中の項目のスコアのメモ・タグを変更する（タグはカンマ区切り）
+    pub fn update_score_note_on_selection(
+        &mut self,
+        index: usize,
+        note: String,
+        tags_input: &str,
+    ) -> Result<(), AppError> {
+        let (cat, item) = self
+            .selected_item_pair()
+            .ok_or_else(|| AppError::Domain("項目が選択されていません。".into()))?;
+
+        let tags = parse_tags(tags_input);
+        let before = self.begin()?;
+        self.model.update_score_note(cat, item, index, note, tags)?;
+        self.commit(before)
+    }
+
+    fn selected_item_pair(&self) -> Option<(CategoryId, ItemId)> {
+        let category = self.model.selection.category?;
+        let item = self.model.selection.item?;
         Some((category, item))
     }
 
-    fn persist(&self) -> Result<(), AppError> {
-        self.store.save(&self.model.data)
+    /// スコア履歴を CSV として書き出す
+    pub fn export_csv(&self, scope: ExportScope) -> Result<String, AppError> {
+        export_csv(&self.model.data, scope)
+    }
+
+    /// CSV 取り込みのドライラン（データは変更しない）
+    pub fn preview_csv_import(
+        &self,
+        table: &CsvTable,
+        mapping: &ColumnMapping,
+    ) -> Result<ImportPreview, AppError> {
+        plan_import(&self.model.data, table, mapping).map(|(_, preview)| preview)
+    }
+
+    /// CSV を取り込んで保存する（まとめて元に戻すことができる）
+    pub fn import_csv(
+        &mut self,
+        table: &CsvTable,
+        mapping: &ColumnMapping,
+    ) -> Result<ImportPreview, AppError> {
+        let before = self.begin()?;
+        let (data, preview) = plan_import(&before, table, mapping)?;
+        if preview.imported() == 0 {
+            return Ok(preview);
+        }
+
+        self.model.restore_data(data);
+        self.commit(before)?;
+        Ok(preview)
+    }
+
+    /// 元に戻す（戻せる変更がなければ false）
+    pub fn undo(&mut self) -> Result<bool, AppError> {
+        let current = self.begin()?;
+        let Some(previous) = self.history.undo(current) else {
+            return Ok(false);
+        };
+        self.model.restore_data(previous);
+        self.persist().map(|_| true)
+    }
+
+    /// やり直し（やり直せる変更がなければ false）
+    pub fn redo(&mut self) -> Result<bool, AppError> {
+        let current = self.begin()?;
+        let Some(next) = self.history.redo(current) else {
+            return Ok(false);
+        };
+        self.model.restore_data(next);
+        self.persist().map(|_| true)
+    }
+
+    /// 元に戻せる変更があるかどうか
+    pub fn can_undo(&self) -> bool {
+        self.history.can_undo()
+    }
+
+    /// やり直せる変更があるかどうか
+    pub fn can_redo(&self) -> bool {
+        self.history.can_redo()
+    }
+
+    /// 変更を始める前に保存可能か確認し、変更前のデータを返す
+    fn begin(&self) -> Result<AppData, AppError> {
+        self.ensure_writable()?;
+        if self.safe_mode.is_some() {
+            return Err(AppError::Persistence(
+                "セーフモード中のため変更できません。バックアップからの復元、別のファイルを開く、新しく始める、のいずれかを選択してください。"
+                    .to_string(),
+            ));
+        }
+        Ok(self.model.data.clone())
+    }
+
+    /// 読み取り専用で開いている場合は変更できない
+    fn ensure_writable(&self) -> Result<(), AppError> {
+        if self.read_only.is_some() {
+            return Err(AppError::Persistence(
+                "読み取り専用で開いているため変更できません。".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// 変更前のデータを履歴に記録してから保存する
+    fn commit(&mut self, before: AppData) -> Result<(), AppError> {
+        self.history.record(before);
+        self.persist()
+    }
+
+    fn persist(&mut self) -> Result<(), AppError> {
+        match self.store.save(&self.model.data) {
+            Err(AppError::Conflict(message)) => {
+                // 変更は手元に残し、解決方法が選ばれるまで保存しない
+                self.unsaved = true;
+                self.conflict = Some(DataConflict { message });
+                Ok(())
+            }
+            result => {
+                self.unsaved = result.is_err();
+                result
+            }
+        }
     }
 }
 
@@ -115,6 +681,74 @@ fn parse_i64(input: &str, message: &str) -> Result<i64, AppError> {
         .map_err(|_| AppError::Input(message.to_string()))
 }
 
+/// 空欄なら None として数値入力を解釈する
+fn parse_optional_f64(input: &str, message: &str) -> Result<Option<f64>, AppError> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Ok(None);
+    }
+    parse_f64(input, message).map(Some)
+}
+
+/// 項目の値の種類に応じてスコア入力を解釈する（桁数の検証はドメイン側で行う）
+pub(super) fn parse_score(input: &str, value_type: ValueType) -> Result<f64, AppError> {
+    let input = input.trim();
+    match value_type {
+        ValueType::Integer => {
+            parse_i64(input, "スコアには整数値を入力してください。").map(|v| v as f64)
+        }
+        ValueType::Decimal(_) => {
+            let value = parse_f64(input, "スコアには数値を入力してください。")?;
+            if value.is_finite() {
+                Ok(value)
+            } else {
+                Err(AppError::Input(
+                    "スコアには数値を入力してください。".to_string(),
+                ))
+            }
+        }
+    }
+}
+
+/// 記録日時を入力欄用のローカル日時文字列に変換する
+pub fn format_timestamp_input(timestamp: DateTime<Utc>) -> String {
+    timestamp
+        .with_timezone(&Local)
+        .format(TIMESTAMP_INPUT_FORMAT)
+        .to_string()
+}
+
+/// ローカル日時の入力を解釈する（空欄なら現在時刻）
+pub(super) fn parse_timestamp(input: &str) -> Result<DateTime<Utc>, AppError> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Ok(Utc::now());
+    }
+
+    let naive = NaiveDateTime::parse_from_str(input, TIMESTAMP_INPUT_FORMAT)
+        .or_else(|_| {
+            NaiveDate::parse_from_str(input, DATE_INPUT_FORMAT)
+                .map(|date| date.and_time(NaiveTime::MIN))
+        })
+        .map_err(|_| {
+            AppError::Input(format!(
+                "日時は「{}」または「{}」の形式で入力してください。",
+                TIMESTAMP_INPUT_HINT, DATE_INPUT_HINT
+            ))
+        })?;
+
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|local| local.with_timezone(&Utc))
+        .ok_or_else(|| AppError::Input("存在しない日時が指定されました。".to_string()))
+}
+
+/// カンマ（全角・半角）区切りのタグ入力を分割する
+pub(super) fn parse_tags(input: &str) -> Vec<String> {
+    normalize_tags(input.split([',', '、']))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -125,6 +759,11 @@ mod tests {
     struct MockStore {
         loaded: Option<AppData>,
         save_calls: Rc<RefCell<usize>>,
+        last_saved: Rc<RefCell<Option<AppData>>>,
+        backups: Vec<(BackupInfo, AppData)>,
+        quarantined: Rc<RefCell<bool>>,
+        /// ほかのプログラムが書き込んだデータ（次の読み込みまで保存は競合になる）
+        external: Rc<RefCell<Option<AppData>>>,
         fail_on_load: bool,
         fail_on_save: bool,
     }
@@ -134,6 +773,10 @@ mod tests {
             Self {
                 loaded,
                 save_calls: Rc::new(RefCell::new(0)),
+                last_saved: Rc::new(RefCell::new(None)),
+                backups: Vec::new(),
+                quarantined: Rc::new(RefCell::new(false)),
+                external: Rc::new(RefCell::new(None)),
                 fail_on_load: false,
                 fail_on_save: false,
             }
@@ -145,43 +788,110 @@ mod tests {
             if self.fail_on_load {
                 return Err(AppError::Persistence("load failed".to_string()));
             }
+            if let Some(external) = self.external.borrow_mut().take() {
+                return Ok(Some(external));
+            }
             Ok(self.loaded.clone())
         }
 
-        fn save(&self, _data: &AppData) -> Result<(), AppError> {
+        fn save(&self, data: &AppData) -> Result<(), AppError> {
+            if self.external.borrow().is_some() {
+                return Err(AppError::Conflict("changed externally".to_string()));
+            }
+            self.save_overwriting(data)
+        }
+
+        fn changed_externally(&self) -> Result<bool, AppError> {
+            Ok(self.external.borrow().is_some())
+        }
+
+        fn save_overwriting(&self, data: &AppData) -> Result<(), AppError> {
+            self.external.borrow_mut().take();
             *self.save_calls.borrow_mut() += 1;
+            *self.last_saved.borrow_mut() = Some(data.clone());
             if self.fail_on_save {
                 return Err(AppError::Persistence("save failed".to_string()));
             }
             Ok(())
         }
+
+        fn quarantine(&self) -> Result<String, AppError> {
+            *self.quarantined.borrow_mut() = true;
+            Ok("broken.json".to_string())
+        }
+
+        fn list_backups(&self) -> Result<Vec<BackupInfo>, AppError> {
+            Ok(self.backups.iter().map(|(info, _)| info.clone()).collect())
+        }
+
+        fn load_backup(&self, id: &str) -> Result<AppData, AppError> {
+            self.backups
+                .iter()
+                .find(|(info, _)| info.id == id)
+                .map(|(_, data)| data.clone())
+                .ok_or_else(|| AppError::Persistence("backup not found".to_string()))
+        }
     }
 
     fn seeded_data() -> AppData {
         let mut data = AppData::default();
-        data.add_category("Cat".to_string()).unwrap();
-        data.add_item("Cat", "Item".to_string(), 0.9).unwrap();
+        let cat = data.add_category("Cat".to_string()).unwrap();
+        data.add_item(cat, "Item".to_string(), 0.9, ValueType::Integer)
+            .unwrap();
         data
     }
 
+    /// カテゴリ "Cat" 内の項目のIDを名前から引く
+    fn ids(service: &TrackerService<MockStore>, item_name: &str) -> (CategoryId, ItemId) {
+        let data = &service.model().data;
+        let cat = data.find_category_id("Cat").unwrap();
+        let item = data
+            .get_category(cat)
+            .unwrap()
+            .find_item_id(item_name)
+            .unwrap();
+        (cat, item)
+    }
+
+    fn get_item<'a>(service: &'a TrackerService<MockStore>, item_name: &str) -> &'a ItemData {
+        let (cat, item) = ids(service, item_name);
+        service.model().get_item(cat, item).unwrap()
+    }
+
+    fn select(service: &mut TrackerService<MockStore>, item_name: &str) {
+        let (cat, item) = ids(service, item_name);
+        service.select_item(cat, item);
+    }
+
+    /// 場所と名前を変えずに項目設定だけを更新
+    fn update_settings(
+        service: &mut TrackerService<MockStore>,
+        settings_input: &ItemSettingsInput,
+    ) -> Result<(), AppError> {
+        let (cat, item) = ids(service, "Item");
+        service.update_item((cat, item), cat, "Item".to_string(), settings_input)
+    }
+
     #[test]
     fn new_loads_existing_data_from_store() {
         // ストアに保存済みのデータがサービス初期化時に正しく読み込まれることを確認する。
         let store = MockStore::new(Some(seeded_data()));
-        let service = TrackerService::new(store).unwrap();
+        let service = TrackerService::open(store);
 
-        assert!(service.model().data.categories.contains_key("Cat"));
-        assert!(service.model().get_item("Cat", "Item").is_ok());
+        assert!(service.model().data.find_category_id("Cat").is_some());
+        assert_eq!(get_item(&service, "Item").name, "Item");
     }
 
     #[test]
     fn add_item_returns_input_error_when_decay_is_invalid_number() {
         // 減衰率の入力が数値でない場合に入力エラーが返ることを確認する。
         let store = MockStore::new(Some(seeded_data()));
-        let mut service = TrackerService::new(store).unwrap();
+        let mut service = TrackerService::open(store);
+
+        let (cat, _) = ids(&service, "Item");
 
         let err = service
-            .add_item("Cat", "New".to_string(), "not-a-number")
+            .add_item(cat, "New".to_string(), "not-a-number", ValueType::Integer)
             .unwrap_err();
         assert!(matches!(err, AppError::Input(_)));
     }
@@ -190,9 +900,9 @@ mod tests {
     fn add_score_to_selection_requires_selected_item() {
         // 項目未選択の状態でスコア追加するとドメインエラーになることを確認する。
         let store = MockStore::new(Some(seeded_data()));
-        let mut service = TrackerService::new(store).unwrap();
+        let mut service = TrackerService::open(store);
 
-        let err = service.add_score_to_selection("10").unwrap_err();
+        let err = service.add_score_to_selection("10", "").unwrap_err();
         assert!(matches!(err, AppError::Domain(_)));
     }
 
@@ -201,21 +911,290 @@ mod tests {
         // スコア追加成功時にモデル更新と永続化処理が実行されることを確認する。
         let store = MockStore::new(Some(seeded_data()));
         let save_calls = Rc::clone(&store.save_calls);
-        let mut service = TrackerService::new(store).unwrap();
-        service.select_item("Cat".to_string(), "Item".to_string());
+        let mut service = TrackerService::open(store);
+        select(&mut service, "Item");
 
-        service.add_score_to_selection("10").unwrap();
+        service.add_score_to_selection("10", "").unwrap();
+
+        assert_eq!(get_item(&service, "Item").scores.len(), 1);
+        assert_eq!(*save_calls.borrow(), 1);
+    }
+
+    #[test]
+    fn add_score_to_item_finds_item_by_name() {
+        // 名前で指定した項目にメモ・タグ付きでスコアが追加され、存在しない名前はエラーになることを確認する。
+        let store = MockStore::new(Some(seeded_data()));
+        let saved = store.last_saved.clone();
+        let mut service = TrackerService::open(store);
 
+        let entry = service
+            .add_score_to_item(
+                ("Cat", "Item"),
+                "12",
+                "2024-01-02 03:04",
+                " memo ".to_string(),
+                "a、b",
+            )
+            .unwrap();
+        assert_eq!(entry.score, 12.0);
         assert_eq!(
-            service
-                .model()
-                .get_item("Cat", "Item")
-                .unwrap()
-                .scores
-                .len(),
-            1
+            entry.timestamp,
+            parse_timestamp("2024-01-02 03:04").unwrap()
         );
-        assert_eq!(*save_calls.borrow(), 1);
+        assert_eq!(entry.note.as_deref(), Some("memo"));
+        assert_eq!(entry.tags, vec!["a", "b"]);
+        assert_eq!(get_item(&service, "Item").scores, vec![entry]);
+        assert!(saved.borrow().is_some());
+
+        let err = service
+            .add_score_to_item(("Cat", "Missing"), "1", "", String::new(), "")
+            .unwrap_err();
+        assert!(matches!(err, AppError::Domain(_)));
+        assert!(service.find_item("Other", "Item").is_err());
+    }
+
+    #[test]
+    fn update_score_note_on_selection_splits_tag_input() {
+        // タグ入力が全角・半角カンマで分割され、メモと共に保存されることを確認する。
+        let store = MockStore::new(Some(seeded_data()));
+        let save_calls = Rc::clone(&store.save_calls);
+        let mut service = TrackerService::open(store);
+        select(&mut service, "Item");
+        service.add_score_to_selection("10", "").unwrap();
+
+        service
+            .update_score_note_on_selection(0, "memo".to_string(), "朝, 寝不足、 朝 ")
+            .unwrap();
+
+        let entry = &get_item(&service, "Item").scores[0];
+        assert_eq!(entry.note.as_deref(), Some("memo"));
+        assert_eq!(entry.tags, vec!["朝".to_string(), "寝不足".to_string()]);
+        assert_eq!(*save_calls.borrow(), 2);
+    }
+
+    #[test]
+    fn add_score_to_selection_accepts_backdated_timestamp() {
+        // 日時入力を指定した場合に、そのローカル日時でスコアが時系列順に登録されることを確認する。
+        let store = MockStore::new(Some(seeded_data()));
+        let mut service = TrackerService::open(store);
+        select(&mut service, "Item");
+
+        service.add_score_to_selection("30", "").unwrap();
+        service
+            .add_score_to_selection("10", "2024-01-02 09:30")
+            .unwrap();
+        service.add_score_to_selection("20", "2024-01-03").unwrap();
+
+        let scores = &get_item(&service, "Item").scores;
+        let values: Vec<f64> = scores.iter().map(|s| s.score).collect();
+        assert_eq!(values, vec![10.0, 20.0, 30.0]);
+
+        let expected = Local
+            .with_ymd_and_hms(2024, 1, 2, 9, 30, 0)
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(scores[0].timestamp, expected);
+    }
+
+    #[test]
+    fn add_score_to_selection_rejects_malformed_timestamp() {
+        // 日時入力の形式が不正な場合に入力エラーとなることを確認する。
+        let store = MockStore::new(Some(seeded_data()));
+        let mut service = TrackerService::open(store);
+        select(&mut service, "Item");
+
+        let err = service
+            .add_score_to_selection("10", "2024/13/40")
+            .unwrap_err();
+        assert!(matches!(err, AppError::Input(_)));
+    }
+
+    #[test]
+    fn update_score_on_selection_keeps_timestamp_when_input_unchanged() {
+        // 日時入力が元の表示のままなら、秒以下を含む元の記録日時が維持されることを確認する。
+        let store = MockStore::new(Some(seeded_data()));
+        let save_calls = Rc::clone(&store.save_calls);
+        let mut service = TrackerService::open(store);
+        select(&mut service, "Item");
+        service.add_score_to_selection("10", "").unwrap();
+
+        let original = get_item(&service, "Item").scores[0].timestamp;
+        service
+            .update_score_on_selection(0, "15", &format_timestamp_input(original))
+            .unwrap();
+
+        let entry = &get_item(&service, "Item").scores[0];
+        assert_eq!(entry.score, 15.0);
+        assert_eq!(entry.timestamp, original);
+        assert_eq!(*save_calls.borrow(), 2);
+    }
+
+    #[test]
+    fn update_score_on_selection_keeps_timestamp_when_input_is_empty() {
+        // 日時入力を空にして修正しても現在時刻にはならず、元の記録日時が維持されることを確認する。
+        let store = MockStore::new(Some(seeded_data()));
+        let mut service = TrackerService::open(store);
+        select(&mut service, "Item");
+        service
+            .add_score_to_selection("10", "2024-01-02 03:04")
+            .unwrap();
+
+        let original = get_item(&service, "Item").scores[0].timestamp;
+        service.update_score_on_selection(0, "15", "  ").unwrap();
+
+        let entry = &get_item(&service, "Item").scores[0];
+        assert_eq!(entry.score, 15.0);
+        assert_eq!(entry.timestamp, original);
+    }
+
+    #[test]
+    fn update_score_on_selection_rejects_invalid_input() {
+        // スコア修正でも追加時と同様に、整数以外と負の値が拒否されることを確認する。
+        let store = MockStore::new(Some(seeded_data()));
+        let mut service = TrackerService::open(store);
+        select(&mut service, "Item");
+        service.add_score_to_selection("10", "").unwrap();
+
+        let err = service.update_score_on_selection(0, "abc", "").unwrap_err();
+        assert!(matches!(err, AppError::Input(_)));
+        let err = service.update_score_on_selection(0, "-5", "").unwrap_err();
+        assert!(matches!(err, AppError::Domain(_)));
+    }
+
+    #[test]
+    fn add_score_to_selection_parses_decimal_for_decimal_item() {
+        // 小数型の項目では小数入力が受け付けられ、整数型の項目では入力エラーになることを確認する。
+        let mut data = seeded_data();
+        let cat = data.find_category_id("Cat").unwrap();
+        data.add_item(cat, "Time".to_string(), 0.9, ValueType::Decimal(1))
+            .unwrap();
+        let store = MockStore::new(Some(data));
+        let mut service = TrackerService::open(store);
+
+        select(&mut service, "Time");
+        service.add_score_to_selection("87.5", "").unwrap();
+        let scores = &get_item(&service, "Time").scores;
+        assert_eq!(scores[0].score, 87.5);
+
+        let err = service.add_score_to_selection("87.55", "").unwrap_err();
+        assert!(matches!(err, AppError::Domain(_)));
+
+        select(&mut service, "Item");
+        let err = service.add_score_to_selection("87.5", "").unwrap_err();
+        assert!(matches!(err, AppError::Input(_)));
+    }
+
+    #[test]
+    fn update_item_applies_value_rules_input() {
+        // 入力条件の入力値が解釈されて項目に反映され、以降のスコア追加に適用されることを確認する。
+        let store = MockStore::new(Some(seeded_data()));
+        let mut service = TrackerService::open(store);
+        let mut settings_input = ItemSettingsInput::from_item(get_item(&service, "Item"));
+        settings_input.rules = ValueRulesInput {
+            min: "0".to_string(),
+            max: "100".to_string(),
+            step: String::new(),
+            allow_negative: false,
+        };
+
+        update_settings(&mut service, &settings_input).unwrap();
+
+        let item = get_item(&service, "Item");
+        assert_eq!(item.rules.max, Some(100.0));
+        assert_eq!(item.rules.step, None);
+
+        select(&mut service, "Item");
+        let err = service.add_score_to_selection("101", "").unwrap_err();
+        assert!(matches!(err, AppError::Domain(_)));
+    }
+
+    #[test]
+    fn update_item_rejects_non_numeric_rule_input() {
+        // 入力条件に数値以外が入力された場合に入力エラーとなることを確認する。
+        let store = MockStore::new(Some(seeded_data()));
+        let mut service = TrackerService::open(store);
+        let mut settings_input = ItemSettingsInput::from_item(get_item(&service, "Item"));
+        settings_input.rules.max = "abc".to_string();
+
+        let err = update_settings(&mut service, &settings_input).unwrap_err();
+        assert!(matches!(err, AppError::Input(_)));
+    }
+
+    #[test]
+    fn update_item_changes_direction() {
+        // 項目編集の入力値で向きが変更され、自己ベストの判定に反映されることを確認する。
+        let store = MockStore::new(Some(seeded_data()));
+        let mut service = TrackerService::open(store);
+        select(&mut service, "Item");
+        service.add_score_to_selection("10", "").unwrap();
+        service.add_score_to_selection("5", "").unwrap();
+
+        let mut settings_input = ItemSettingsInput::from_item(get_item(&service, "Item"));
+        settings_input.direction = ScoreDirection::LowerIsBetter;
+        update_settings(&mut service, &settings_input).unwrap();
+
+        let item = get_item(&service, "Item");
+        assert_eq!(item.direction, ScoreDirection::LowerIsBetter);
+        assert_eq!(item.personal_best_index(), Some(1));
+    }
+
+    #[test]
+    fn update_item_switches_to_time_decay() {
+        // 経過日数モードの入力値で半減期が設定され、不正な半減期は入力エラーになることを確認する。
+        let store = MockStore::new(Some(seeded_data()));
+        let mut service = TrackerService::open(store);
+        let mut settings_input = ItemSettingsInput::from_item(get_item(&service, "Item"));
+        assert_eq!(settings_input.weighting_kind, WeightingKind::Exponential);
+
+        settings_input.weighting_kind = WeightingKind::TimeDecay;
+        settings_input.half_life_days = "abc".to_string();
+        let err = update_settings(&mut service, &settings_input).unwrap_err();
+        assert!(matches!(err, AppError::Input(_)));
+
+        settings_input.half_life_days = "14".to_string();
+        update_settings(&mut service, &settings_input).unwrap();
+        let item = get_item(&service, "Item");
+        assert_eq!(
+            item.weighting,
+            WeightingMode::TimeDecay {
+                half_life_days: 14.0
+            }
+        );
+    }
+
+    #[test]
+    fn update_item_switches_to_sliding_window() {
+        // 直近N件の方式が件数付きで設定され、整数でない件数は入力エラーになることを確認する。
+        let store = MockStore::new(Some(seeded_data()));
+        let mut service = TrackerService::open(store);
+        let mut settings_input = ItemSettingsInput::from_item(get_item(&service, "Item"));
+        settings_input.weighting_kind = WeightingKind::SlidingWindow;
+
+        settings_input.window_size = "2.5".to_string();
+        let err = update_settings(&mut service, &settings_input).unwrap_err();
+        assert!(matches!(err, AppError::Input(_)));
+
+        settings_input.window_size = "5".to_string();
+        update_settings(&mut service, &settings_input).unwrap();
+        let item = get_item(&service, "Item");
+        assert_eq!(item.weighting, WeightingMode::SlidingWindow { size: 5 });
+    }
+
+    #[test]
+    fn update_item_changes_importance() {
+        // 項目編集の入力値で重要度が変更され、数値以外は入力エラーになることを確認する。
+        let store = MockStore::new(Some(seeded_data()));
+        let mut service = TrackerService::open(store);
+        let mut settings_input = ItemSettingsInput::from_item(get_item(&service, "Item"));
+
+        settings_input.importance = "high".to_string();
+        let err = update_settings(&mut service, &settings_input).unwrap_err();
+        assert!(matches!(err, AppError::Input(_)));
+
+        settings_input.importance = "2.5".to_string();
+        update_settings(&mut service, &settings_input).unwrap();
+        let item = get_item(&service, "Item");
+        assert_eq!(item.importance, 2.5);
     }
 
     #[test]
@@ -223,9 +1202,270 @@ mod tests {
         // 永続化処理で発生したエラーがサービス層から呼び出し元へ伝播することを確認する。
         let mut store = MockStore::new(None);
         store.fail_on_save = true;
-        let mut service = TrackerService::new(store).unwrap();
+        let mut service = TrackerService::open(store);
+
+        let err = service.add_category("Cat".to_string()).unwrap_err();
+        assert!(matches!(err, AppError::Persistence(_)));
+    }
+
+    #[test]
+    fn undo_and_redo_restore_data_and_persist() {
+        // 元に戻す／やり直しでデータが切り替わり、そのたびに切り替え後のデータが保存されることを確認する。
+        let store = MockStore::new(Some(seeded_data()));
+        let last_saved = Rc::clone(&store.last_saved);
+        let mut service = TrackerService::open(store);
+        select(&mut service, "Item");
+        service.add_score_to_selection("10", "").unwrap();
+        service.add_score_to_selection("20", "").unwrap();
+
+        assert!(service.undo().unwrap());
+        assert_eq!(get_item(&service, "Item").scores.len(), 1);
+        let saved = last_saved.borrow().clone().unwrap();
+        let (cat, item) = ids(&service, "Item");
+        assert_eq!(saved.get_item(cat, item).unwrap().scores.len(), 1);
+
+        assert!(service.redo().unwrap());
+        assert_eq!(get_item(&service, "Item").scores.len(), 2);
+        assert!(!service.can_redo());
+    }
+
+    #[test]
+    fn undo_restores_deleted_item_and_returns_false_when_empty() {
+        // 削除した項目を元に戻せること、戻せる変更がない場合は false が返ることを確認する。
+        let store = MockStore::new(Some(seeded_data()));
+        let mut service = TrackerService::open(store);
+        assert!(!service.undo().unwrap());
+
+        let (cat, item) = ids(&service, "Item");
+        service.delete_item(cat, item).unwrap();
+        assert!(service.model().get_item(cat, item).is_err());
+
+        assert!(service.undo().unwrap());
+        assert!(service.model().get_item(cat, item).is_ok());
+        assert!(!service.can_undo());
+    }
+
+    #[test]
+    fn failed_mutation_is_not_recorded_in_history() {
+        // 入力エラーなどで失敗した操作は履歴に記録されないことを確認する。
+        let store = MockStore::new(Some(seeded_data()));
+        let mut service = TrackerService::open(store);
+
+        let (cat, _) = ids(&service, "Item");
+        service
+            .add_item(cat, "Item".to_string(), "0.5", ValueType::Integer)
+            .unwrap_err();
+        assert!(!service.can_undo());
+    }
+
+    #[test]
+    fn load_failure_quarantines_file_and_enters_safe_mode() {
+        // 読み込み失敗時にファイルが退避され、セーフモード中は変更も保存も行われないことを確認する。
+        let mut store = MockStore::new(None);
+        store.fail_on_load = true;
+        let quarantined = Rc::clone(&store.quarantined);
+        let save_calls = Rc::clone(&store.save_calls);
+        let mut service = TrackerService::open(store);
+
+        assert!(*quarantined.borrow());
+        let safe_mode = service.safe_mode().unwrap();
+        assert!(safe_mode.message.contains("broken.json"));
 
         let err = service.add_category("Cat".to_string()).unwrap_err();
         assert!(matches!(err, AppError::Persistence(_)));
+        assert!(service.model().data.categories.is_empty());
+        assert_eq!(*save_calls.borrow(), 0);
+    }
+
+    fn backup_of(data: AppData) -> (BackupInfo, AppData) {
+        let info = BackupInfo {
+            id: "backup-1".to_string(),
+            kind: crate::application::BackupKind::Recent,
+            saved_at: Utc::now(),
+            counts: Some((data.categories.len(), 0)),
+        };
+        (info, data)
+    }
+
+    #[test]
+    fn restore_from_backup_leaves_safe_mode() {
+        // セーフモード中にバックアップから復元するとデータが読み込まれて保存され、セーフモードが解除されることを確認する。
+        let mut store = MockStore::new(None);
+        store.fail_on_load = true;
+        store.backups.push(backup_of(seeded_data()));
+        let last_saved = Rc::clone(&store.last_saved);
+        let mut service = TrackerService::open(store);
+
+        service.restore_from_backup("backup-1").unwrap();
+
+        assert!(service.safe_mode().is_none());
+        assert_eq!(get_item(&service, "Item").name, "Item");
+        assert!(last_saved.borrow().is_some());
+        assert!(!service.can_undo());
+    }
+
+    #[test]
+    fn restore_from_backup_can_be_undone_in_normal_mode() {
+        // 通常時のバックアップ復元は元に戻すことができることを確認する。
+        let mut store = MockStore::new(Some(seeded_data()));
+        store.backups.push(backup_of(AppData::default()));
+        let mut service = TrackerService::open(store);
+        select(&mut service, "Item");
+
+        service.restore_from_backup("backup-1").unwrap();
+        assert!(service.model().data.categories.is_empty());
+        assert!(service.model().selected_item().is_none());
+
+        service.undo().unwrap();
+        assert_eq!(get_item(&service, "Item").name, "Item");
+    }
+
+    #[test]
+    fn restore_from_backup_fails_for_unknown_backup() {
+        // 存在しないバックアップを指定すると失敗し、セーフモードが維持されることを確認する。
+        let mut store = MockStore::new(None);
+        store.fail_on_load = true;
+        let mut service = TrackerService::open(store);
+
+        assert!(service.restore_from_backup("missing").is_err());
+        assert!(service.safe_mode().is_some());
+    }
+
+    #[test]
+    fn start_fresh_leaves_safe_mode_and_saves_empty_data() {
+        // 新しく始めると空のデータが保存され、セーフモードが解除されることを確認する。
+        let mut store = MockStore::new(None);
+        store.fail_on_load = true;
+        let last_saved = Rc::clone(&store.last_saved);
+        let mut service = TrackerService::open(store);
+
+        service.start_fresh().unwrap();
+
+        assert!(service.safe_mode().is_none());
+        let saved = last_saved.borrow().clone().unwrap();
+        assert!(saved.categories.is_empty());
+    }
+
+    /// カテゴリ "Cat" に項目を追加したデータ（ほかのプログラムによる変更の代わり）
+    fn with_item(data: &AppData, item_name: &str) -> AppData {
+        let mut data = data.clone();
+        let cat = data.find_category_id("Cat").unwrap();
+        data.add_item(cat, item_name.to_string(), 0.9, ValueType::Integer)
+            .unwrap();
+        data
+    }
+
+    #[test]
+    fn external_change_is_reloaded_when_nothing_is_unsaved() {
+        // 未保存の変更がなければ外部の変更を読み込み直し、元に戻すこともできることを確認する。
+        let store = MockStore::new(Some(seeded_data()));
+        let external = Rc::clone(&store.external);
+        let mut service = TrackerService::open(store);
+        assert_eq!(
+            service.check_external_changes().unwrap(),
+            ExternalChange::Unchanged
+        );
+
+        *external.borrow_mut() = Some(with_item(&seeded_data(), "External"));
+        assert_eq!(
+            service.check_external_changes().unwrap(),
+            ExternalChange::Reloaded
+        );
+        assert_eq!(get_item(&service, "External").name, "External");
+        assert!(service.conflict().is_none());
+        assert!(service.can_undo());
+    }
+
+    #[test]
+    fn conflicting_save_keeps_local_change_until_resolved() {
+        // 外部の変更後の保存は競合として保留され、統合すると両方の変更が保存されることを確認する。
+        let store = MockStore::new(Some(seeded_data()));
+        let external = Rc::clone(&store.external);
+        let last_saved = Rc::clone(&store.last_saved);
+        let mut service = TrackerService::open(store);
+
+        *external.borrow_mut() = Some(with_item(&seeded_data(), "External"));
+        let (cat, _) = ids(&service, "Item");
+        service
+            .add_item(cat, "Local".to_string(), "0.9", ValueType::Integer)
+            .unwrap();
+        assert!(service.conflict().is_some());
+        assert!(last_saved.borrow().is_none());
+        assert_eq!(
+            service.check_external_changes().unwrap(),
+            ExternalChange::Unchanged
+        );
+
+        service.resolve_conflict(ConflictResolution::Merge).unwrap();
+        assert!(service.conflict().is_none());
+        let saved = last_saved.borrow().clone().unwrap();
+        assert_eq!(saved, service.model().data);
+        assert_eq!(get_item(&service, "Local").name, "Local");
+        assert_eq!(get_item(&service, "External").name, "External");
+    }
+
+    #[test]
+    fn conflict_can_be_resolved_by_either_side() {
+        // 競合はファイルの内容を読み込むか、こちらの内容で上書きするかで解決できることを確認する。
+        let store = MockStore::new(Some(seeded_data()));
+        let external = Rc::clone(&store.external);
+        let last_saved = Rc::clone(&store.last_saved);
+        let mut service = TrackerService::open(store);
+        let (cat, _) = ids(&service, "Item");
+
+        let external_data = with_item(&service.model().data, "External");
+        *external.borrow_mut() = Some(external_data.clone());
+        service
+            .add_item(cat, "Local".to_string(), "0.9", ValueType::Integer)
+            .unwrap();
+        service
+            .resolve_conflict(ConflictResolution::UseFile)
+            .unwrap();
+        assert_eq!(service.model().data, external_data);
+
+        *external.borrow_mut() = Some(with_item(&external_data, "Other"));
+        service
+            .add_item(cat, "Mine".to_string(), "0.9", ValueType::Integer)
+            .unwrap();
+        service
+            .resolve_conflict(ConflictResolution::KeepMine)
+            .unwrap();
+        assert_eq!(last_saved.borrow().clone().unwrap(), service.model().data);
+        assert_eq!(get_item(&service, "Mine").name, "Mine");
+    }
+
+    #[test]
+    fn read_only_service_rejects_changes_but_follows_external_changes() {
+        // 読み取り専用では変更も保存も行わず、ほかのインスタンスによる変更は読み込み直すことを確認する。
+        let store = MockStore::new(Some(seeded_data()));
+        let save_calls = Rc::clone(&store.save_calls);
+        let external = Rc::clone(&store.external);
+        let mut service = TrackerService::open_read_only(store, "in use".to_string());
+        assert_eq!(service.read_only(), Some("in use"));
+
+        let err = service.add_category("New".to_string()).unwrap_err();
+        assert!(matches!(err, AppError::Persistence(_)));
+        assert!(service.start_fresh().is_err());
+        assert_eq!(*save_calls.borrow(), 0);
+
+        *external.borrow_mut() = Some(with_item(&service.model().data, "External"));
+        assert_eq!(
+            service.check_external_changes().unwrap(),
+            ExternalChange::Reloaded
+        );
+        assert_eq!(get_item(&service, "External").name, "External");
+    }
+
+    #[test]
+    fn read_only_open_does_not_quarantine_unreadable_file() {
+        // 読み取り専用で開いたときは読み込めなくてもファイルを退避せず、理由に読み込みエラーを含めることを確認する。
+        let mut store = MockStore::new(None);
+        store.fail_on_load = true;
+        let quarantined = Rc::clone(&store.quarantined);
+        let service = TrackerService::open_read_only(store, "in use".to_string());
+
+        assert!(!*quarantined.borrow());
+        assert!(service.safe_mode().is_none());
+        assert!(service.read_only().unwrap().contains("load failed"));
     }
 }
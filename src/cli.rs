@@ -0,0 +1,627 @@
+use serde::Serialize;
+
+use crate::application::{AppError, DataStore, TrackerService, format_timestamp_input};
+use crate::constants::SERVER_DEFAULT_PORT;
+use crate::domain::{ItemData, ScoreEntry, TrackerModel, ValueType};
+use crate::infrastructure::{
+    DataLocation, DataLock, IpcReply, IpcRequest, LockOwner, LockState, open_store_with_backups,
+    send_request,
+};
+use crate::launch::USAGE;
+use crate::logic::{calculate_stats, scheme_for, summarize_category};
+use crate::utils::comma_display::CommaDisplay;
+
+/// コマンドラインのサブコマンド
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// スコアを記録する（日時が空なら現在時刻）
+    Add {
+        category: String,
+        item: String,
+        score: String,
+        at: String,
+        note: String,
+        tags: String,
+    },
+    /// カテゴリと項目の一覧
+    List,
+    /// 項目のスコア履歴
+    History { category: String, item: String },
+    /// 項目の統計値
+    Stats { category: String, item: String },
+    /// HTTP API を待ち受ける（終了するまで戻らない）
+    Serve { port: u16 },
+    /// 使い方を表示する
+    Help,
+}
+
+/// サブコマンドと出力形式
+#[derive(Debug, Clone, PartialEq)]
+pub struct CliRequest {
+    pub command: Command,
+    /// JSON で出力するかどうか（既定は人が読む形式）
+    pub json: bool,
+}
+
+impl CliRequest {
+    /// サブコマンド以降の引数（--data・--profile を除く）を解釈する
+    pub fn parse(args: Vec<String>) -> Result<Self, String> {
+        let mut json = false;
+        let mut at = None;
+        let mut note = None;
+        let mut tags = None;
+        let mut port = None;
+        let mut positional = Vec::new();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            // 負の数（マイナスのスコアなど）はフラグではなく引数として扱う
+            if !arg.starts_with('-') || arg.parse::<f64>().is_ok() {
+                positional.push(arg);
+                continue;
+            }
+            // -- 以降はすべて引数として扱う（- で始まる名前を指定する場合）
+            if arg == "--" {
+                positional.extend(args.by_ref());
+                break;
+            }
+            let (flag, inline) = match arg.split_once('=') {
+                Some((flag, value)) => (flag.to_string(), Some(value.to_string())),
+                None => (arg, None),
+            };
+            let mut value = || {
+                inline
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| format!("{} には値を指定してください。\n{}", flag, USAGE))
+            };
+
+            match flag.as_str() {
+                "--json" => json = true,
+                "--at" => at = Some(value()?),
+                "--note" => note = Some(value()?),
+                "--tags" => tags = Some(value()?),
+                "--port" => port = Some(value()?),
+                "--help" | "-h" => positional.insert(0, "help".to_string()),
+                _ => return Err(format!("不明な引数です: {}\n{}", flag, USAGE)),
+            }
+        }
+
+        let mut positional = positional.into_iter();
+        let name = positional.next().unwrap_or_default();
+        let operands: Vec<String> = positional.collect();
+        let expect = |count: usize| {
+            if operands.len() == count {
+                Ok(operands.clone())
+            } else {
+                Err(format!(
+                    "{} の引数の数が正しくありません。\n{}",
+                    name, USAGE
+                ))
+            }
+        };
+
+        let command = match name.as_str() {
+            "add" => {
+                let [category, item, score] = <[String; 3]>::try_from(expect(3)?).unwrap();
+                Command::Add {
+                    category,
+                    item,
+                    score,
+                    at: at.take().unwrap_or_default(),
+                    note: note.take().unwrap_or_default(),
+                    tags: tags.take().unwrap_or_default(),
+                }
+            }
+            "list" => {
+                expect(0)?;
+                Command::List
+            }
+            "history" | "stats" => {
+                let [category, item] = <[String; 2]>::try_from(expect(2)?).unwrap();
+                if name == "history" {
+                    Command::History { category, item }
+                } else {
+                    Command::Stats { category, item }
+                }
+            }
+            "serve" => {
+                expect(0)?;
+                let port = match port.take() {
+                    Some(port) => port.parse().ok().filter(|&port| port != 0).ok_or_else(|| {
+                        format!(
+                            "--port には 1 ～ 65535 の番号を指定してください。\n{}",
+                            USAGE
+                        )
+                    })?,
+                    None => SERVER_DEFAULT_PORT,
+                };
+                Command::Serve { port }
+            }
+            "help" => Command::Help,
+            _ => return Err(format!("不明なコマンドです: {}\n{}", name, USAGE)),
+        };
+
+        if at.is_some() || note.is_some() || tags.is_some() {
+            return Err(format!(
+                "--at・--note・--tags は add でのみ指定できます。\n{}",
+                USAGE
+            ));
+        }
+        if port.is_some() {
+            return Err(format!("--port は serve でのみ指定できます。\n{}", USAGE));
+        }
+        Ok(Self { command, json })
+    }
+}
+
+/// サブコマンドを実行し、出力する文字列を返す
+///
+/// スコアの記録と HTTP API はデータファイルのロックを取ってから行い、画面などほかのインスタンスが
+/// 使用中なら実行しない（起動中の画面が転送を受け付けていれば、スコアの記録は画面に反映してもらう）。
+/// 参照だけのコマンドは使用中でも実行できる。
+pub fn run(
+    location: &DataLocation,
+    profile: Option<&str>,
+    request: &CliRequest,
+) -> Result<String, AppError> {
+    if request.command == Command::Help {
+        return Ok(USAGE.to_string());
+    }
+
+    let profile = profile
+        .map(str::to_string)
+        .unwrap_or_else(|| location.last_profile());
+    let path = location.prepare(&profile)?;
+
+    let adds = matches!(request.command, Command::Add { .. });
+    let writes = adds || matches!(request.command, Command::Serve { .. });
+    let lock = match DataLock::acquire(&path)? {
+        LockState::Acquired(lock) => Some(lock),
+        // 画面が使用中なら記録を画面に転送して反映してもらう
+        LockState::HeldByOther(owner) if adds && owner.ipc_port.is_some() => {
+            return forward_to_window(&owner, request);
+        }
+        LockState::HeldByOther(owner) if writes => {
+            return Err(AppError::Persistence(format!(
+                "{}\n画面を閉じてからもう一度実行してください。",
+                owner.describe()
+            )));
+        }
+        LockState::HeldByOther(_) => None,
+    };
+    let store = open_store_with_backups(&path, location.backup_policy()?, lock.is_some())?;
+    let mut service = TrackerService::try_open(store)?;
+    if let Command::Serve { port } = request.command {
+        return serve(service, lock, port);
+    }
+    let output = execute(&mut service, request);
+    if let Some(warning) = service.take_backup_warning() {
+        eprintln!("{}", warning);
+    }
+    output
+}
+
+#[cfg(feature = "server")]
+fn serve<S: DataStore>(
+    service: TrackerService<S>,
+    lock: Option<DataLock>,
+    port: u16,
+) -> Result<String, AppError> {
+    crate::server::serve(service, lock, port).map(|()| String::new())
+}
+
+#[cfg(not(feature = "server"))]
+fn serve<S: DataStore>(
+    _service: TrackerService<S>,
+    _lock: Option<DataLock>,
+    _port: u16,
+) -> Result<String, AppError> {
+    Err(AppError::Input(
+        "server 機能を無効にしてビルドされているため、serve は使用できません。".to_string(),
+    ))
+}
+
+/// 使用中の画面にスコアの記録を送って反映してもらう
+fn forward_to_window(owner: &LockOwner, request: &CliRequest) -> Result<String, AppError> {
+    let Command::Add {
+        category,
+        item,
+        score,
+        at,
+        note,
+        tags,
+    } = &request.command
+    else {
+        return Err(AppError::Input(
+            "画面に転送できるのはスコアの記録のみです。".to_string(),
+        ));
+    };
+    let ipc_request = IpcRequest::AddScore {
+        category: category.clone(),
+        item: item.clone(),
+        score: score.clone(),
+        timestamp: at.clone(),
+        note: note.clone(),
+        tags: tags.clone(),
+    };
+    match send_request(owner, ipc_request)? {
+        IpcReply::Added { entry, value_type } => {
+            describe_added((category, item), &entry, value_type, request.json)
+        }
+        IpcReply::Failed(err) => Err(err),
+    }
+}
+
+/// 記録したスコアの出力
+fn describe_added(
+    (category, item): (&str, &str),
+    entry: &ScoreEntry,
+    value_type: ValueType,
+    json: bool,
+) -> Result<String, AppError> {
+    if json {
+        return to_json(&AddedScore {
+            category,
+            item,
+            entry,
+        });
+    }
+    Ok(format!(
+        "{} / {} にスコア {} を記録しました（{}）。",
+        category,
+        item,
+        value_type.format(entry.score),
+        format_timestamp_input(entry.timestamp)
+    ))
+}
+
+/// 開いたサービスに対してサブコマンドを実行する
+fn execute<S: DataStore>(
+    service: &mut TrackerService<S>,
+    request: &CliRequest,
+) -> Result<String, AppError> {
+    match &request.command {
+        Command::Add {
+            category,
+            item,
+            score,
+            at,
+            note,
+            tags,
+        } => {
+            let entry =
+                service.add_score_to_item((category, item), score, at, note.clone(), tags)?;
+            let value_type = find_item(service, category, item)?.value_type;
+            describe_added((category, item), &entry, value_type, request.json)
+        }
+        Command::List => list(service, request.json),
+        Command::History { category, item } => {
+            let item_data = find_item(service, category, item)?;
+            if request.json {
+                return to_json(&item_data.scores);
+            }
+            Ok(history_lines(item_data))
+        }
+        Command::Stats { category, item } => {
+            let item_data = find_item(service, category, item)?;
+            let stats = ItemStats::of(category, item_data);
+            if request.json {
+                return to_json(&stats);
+            }
+            Ok(stats.describe(item_data))
+        }
+        Command::Serve { .. } | Command::Help => Ok(USAGE.to_string()),
+    }
+}
+
+/// カテゴリ名と項目名から項目を探す
+pub fn find_item<'a, S: DataStore>(
+    service: &'a TrackerService<S>,
+    category: &str,
+    item: &str,
+) -> Result<&'a ItemData, AppError> {
+    let (cat, item) = service.find_item(category, item)?;
+    Ok(service.model().get_item(cat, item)?)
+}
+
+/// 整形した JSON に変換する
+pub fn to_json<T: Serialize + ?Sized>(value: &T) -> Result<String, AppError> {
+    serde_json::to_string_pretty(value)
+        .map_err(|e| AppError::Persistence(format!("JSON に変換できません: {}", e)))
+}
+
+/// add の JSON 出力
+#[derive(Serialize)]
+pub struct AddedScore<'a> {
+    pub category: &'a str,
+    pub item: &'a str,
+    #[serde(flatten)]
+    pub entry: &'a ScoreEntry,
+}
+
+/// list の JSON 出力（カテゴリごと）
+#[derive(Serialize)]
+pub struct CategoryListing {
+    category: String,
+    /// 各項目の換算値（0〜100）を重要度で加重平均した値
+    aggregate: Option<f64>,
+    items: Vec<ItemListing>,
+}
+
+#[derive(Serialize)]
+struct ItemListing {
+    name: String,
+    count: usize,
+    average: Option<f64>,
+    /// 加重平均をスコアの範囲で 0〜100 に換算した値
+    normalized: Option<f64>,
+}
+
+/// カテゴリと項目の一覧（カテゴリ・項目とも名前順）
+pub fn category_listing(model: &TrackerModel) -> Vec<CategoryListing> {
+    let mut categories: Vec<_> = model.data.categories.values().collect();
+    categories.sort_by(|a, b| a.name.cmp(&b.name));
+    categories
+        .into_iter()
+        .map(|category| {
+            let summary = summarize_category(category);
+            CategoryListing {
+                category: category.name.clone(),
+                aggregate: summary.aggregate,
+                items: summary
+                    .items
+                    .into_iter()
+                    .map(|item| ItemListing {
+                        name: item.name,
+                        count: item.count,
+                        average: item.average,
+                        normalized: item.normalized,
+                    })
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+/// カテゴリと項目の一覧
+fn list<S: DataStore>(service: &TrackerService<S>, json: bool) -> Result<String, AppError> {
+    if json {
+        return to_json(&category_listing(service.model()));
+    }
+
+    let mut categories: Vec<_> = service.model().data.categories.values().collect();
+    if categories.is_empty() {
+        return Ok("カテゴリがありません。".to_string());
+    }
+    categories.sort_by(|a, b| a.name.cmp(&b.name));
+    let lines: Vec<String> = categories
+        .iter()
+        .flat_map(|category| {
+            let summary = summarize_category(category);
+            let items = summary.items.into_iter().map(|item| {
+                let average = item
+                    .average
+                    .map(|avg| {
+                        format!(
+                            "  加重平均 {}",
+                            avg.to_comma_fmt(item.value_type.stats_precision())
+                        )
+                    })
+                    .unwrap_or_default();
+                format!("  {}  {} 件{}", item.name, item.count, average)
+            });
+            std::iter::once(category.name.clone()).chain(items)
+        })
+        .collect();
+    Ok(lines.join("\n"))
+}
+
+/// 記録日時・スコア・メモ・タグを1件1行で並べる（古い順）
+fn history_lines(item: &ItemData) -> String {
+    if item.scores.is_empty() {
+        return "スコアがありません。".to_string();
+    }
+    item.scores
+        .iter()
+        .map(|entry| {
+            let mut line = format!(
+                "{}  {}",
+                format_timestamp_input(entry.timestamp),
+                item.value_type.format(entry.score)
+            );
+            if let Some(note) = &entry.note {
+                line.push_str(&format!("  {}", note));
+            }
+            if !entry.tags.is_empty() {
+                line.push_str(&format!("  [{}]", entry.tags.join(", ")));
+            }
+            line
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// stats の出力（スコアがなければ平均・標準偏差は None）
+#[derive(Serialize)]
+pub struct ItemStats<'a> {
+    category: &'a str,
+    item: &'a str,
+    count: usize,
+    mean: Option<f64>,
+    std: Option<f64>,
+    weighting: String,
+}
+
+impl<'a> ItemStats<'a> {
+    pub fn of(category: &'a str, item: &'a ItemData) -> Self {
+        let (mean, std, count, _) = calculate_stats(&item.scores, scheme_for(item).as_ref());
+        Self {
+            category,
+            item: &item.name,
+            count,
+            mean: (count > 0).then_some(mean),
+            std: (count > 0).then_some(std),
+            weighting: item.weighting.label(),
+        }
+    }
+
+    fn describe(&self, item: &ItemData) -> String {
+        let precision = item.value_type.stats_precision();
+        let format = |value: Option<f64>| {
+            value
+                .map(|v| v.to_comma_fmt(precision))
+                .unwrap_or_else(|| "-".to_string())
+        };
+        format!(
+            "{} / {}\n件数: {}\n加重平均: {}\n標準偏差: {}\n重み付け: {}",
+            self.category,
+            self.item,
+            self.count,
+            format(self.mean),
+            format(self.std),
+            self.weighting
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::JsonFileStore;
+    use std::path::PathBuf;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn request(args: &[&str]) -> CliRequest {
+        CliRequest::parse(args.iter().map(|arg| arg.to_string()).collect()).unwrap()
+    }
+
+    /// カテゴリ "Cat" に項目 "Item" を作ったデータファイルを開く
+    fn seeded_service(name: &str) -> (TrackerService<JsonFileStore>, PathBuf) {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time went backwards")
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("{name}-{unique}"));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut service =
+            TrackerService::try_open(JsonFileStore::new(dir.join("data.json"))).unwrap();
+        service.add_category("Cat".to_string()).unwrap();
+        let cat = service.model().data.find_category_id("Cat").unwrap();
+        service
+            .add_item(cat, "Item".to_string(), "0.9", Default::default())
+            .unwrap();
+        (service, dir)
+    }
+
+    #[test]
+    fn parse_reads_subcommand_and_options() {
+        // サブコマンドの引数と --at・--note・--tags・--json がどの位置でも解釈され、負の数と -- 以降は引数として扱われることを確認する。
+        let parsed = request(&[
+            "add",
+            "Cat",
+            "--at=2024-01-02",
+            "Item",
+            "10",
+            "--json",
+            "--tags",
+            "a,b",
+        ]);
+        assert_eq!(
+            parsed,
+            CliRequest {
+                command: Command::Add {
+                    category: "Cat".to_string(),
+                    item: "Item".to_string(),
+                    score: "10".to_string(),
+                    at: "2024-01-02".to_string(),
+                    note: String::new(),
+                    tags: "a,b".to_string(),
+                },
+                json: true,
+            }
+        );
+        assert_eq!(request(&["--help"]).command, Command::Help);
+
+        let negative = request(&["add", "Golf", "Diff", "-2.5"]);
+        assert!(matches!(negative.command, Command::Add { ref score, .. } if score == "-2.5"));
+        let separated = request(&["add", "--json", "--", "-Cat", "--note", "-3"]);
+        assert!(separated.json);
+        assert!(matches!(
+            separated.command,
+            Command::Add { ref category, ref item, ref score, ref note, .. }
+                if category == "-Cat" && item == "--note" && score == "-3" && note.is_empty()
+        ));
+        assert_eq!(
+            request(&["serve"]).command,
+            Command::Serve {
+                port: SERVER_DEFAULT_PORT
+            }
+        );
+        assert_eq!(
+            request(&["serve", "--port=9000"]).command,
+            Command::Serve { port: 9000 }
+        );
+    }
+
+    #[test]
+    fn parse_rejects_wrong_arguments() {
+        // 不明なコマンド・引数の数の誤り・add 以外での --note・serve 以外での --port・範囲外のポートはエラーになることを確認する。
+        let parse = |args: &[&str]| CliRequest::parse(args.iter().map(|a| a.to_string()).collect());
+        assert!(parse(&["remove"]).is_err());
+        assert!(parse(&["add", "Cat", "Item"]).is_err());
+        assert!(parse(&["stats", "Cat"]).is_err());
+        assert!(parse(&["list", "--note", "memo"]).is_err());
+        assert!(parse(&["list", "--verbose"]).is_err());
+        assert!(parse(&["list", "--port", "9000"]).is_err());
+        assert!(parse(&["serve", "--port", "0"]).is_err());
+    }
+
+    #[test]
+    fn add_then_query_history_and_stats() {
+        // 記録したスコアが保存され、履歴と統計値が人が読む形式と JSON の両方で出力されることを確認する。
+        let (mut service, dir) = seeded_service("cli-add");
+
+        let added = execute(
+            &mut service,
+            &request(&[
+                "add",
+                "Cat",
+                "Item",
+                "10",
+                "--at",
+                "2024-01-02 03:04",
+                "--note",
+                "memo",
+            ]),
+        )
+        .unwrap();
+        assert_eq!(
+            added,
+            "Cat / Item にスコア 10 を記録しました（2024-01-02 03:04）。"
+        );
+        execute(
+            &mut service,
+            &request(&["add", "Cat", "Item", "20", "--at", "2024-01-03"]),
+        )
+        .unwrap();
+
+        let reopened =
+            &mut TrackerService::try_open(JsonFileStore::new(dir.join("data.json"))).unwrap();
+        let history = execute(reopened, &request(&["history", "Cat", "Item"])).unwrap();
+        assert_eq!(history, "2024-01-02 03:04  10  memo\n2024-01-03 00:00  20");
+
+        let stats = execute(reopened, &request(&["stats", "Cat", "Item", "--json"])).unwrap();
+        let stats: serde_json::Value = serde_json::from_str(&stats).unwrap();
+        assert_eq!(stats["count"], 2);
+        assert!(stats["mean"].as_f64().unwrap() > 10.0);
+
+        let listing = execute(reopened, &request(&["list", "--json"])).unwrap();
+        let listing: serde_json::Value = serde_json::from_str(&listing).unwrap();
+        assert_eq!(listing[0]["category"], "Cat");
+        assert_eq!(listing[0]["items"][0]["count"], 2);
+
+        assert!(execute(reopened, &request(&["stats", "Cat", "Missing"])).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
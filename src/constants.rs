@@ -1,27 +1,125 @@
-use eframe::egui;
+//! 保存形式・入力範囲などの定数
 
 // --- ファイル保存 ---
+/// データファイル名
 pub const DATA_FILENAME: &str = "scores_data.json";
+/// ユーザーごとのデータフォルダ内に作るアプリのフォルダ名（Linux では $XDG_DATA_HOME/<名前>）
+pub const APP_DIR_NAME: &str = "WeightedScoreTracker";
+/// プロファイルごとのフォルダをまとめるフォルダ名
+pub const PROFILES_DIR_NAME: &str = "profiles";
+/// 最後に使ったプロファイル名を記録するファイル名
+pub const ACTIVE_PROFILE_FILENAME: &str = "active_profile";
+/// 既定のプロファイル名
+pub const DEFAULT_PROFILE: &str = "default";
+/// プロファイル名の最大文字数
+pub const MAX_PROFILE_NAME_LENGTH: usize = 64;
+/// この拡張子のデータファイルは SQLite データベースとして扱う
+pub const SQLITE_EXTENSIONS: &[&str] = &["db", "sqlite", "sqlite3"];
+/// この拡張子のデータファイルは変更を追記するジャーナルとして扱う
+pub const JOURNAL_EXTENSION: &str = "journal";
+/// ジャーナルをスナップショットへ圧縮するまでの追記件数
+pub const JOURNAL_COMPACT_THRESHOLD: usize = 500;
+/// ほかのプログラムによるデータファイルの変更を確認する間隔（秒）
+pub const EXTERNAL_CHANGE_CHECK_SECS: u64 = 2;
+/// 使用中ロックファイルの拡張子（"<データファイル名>.lock"）
+pub const LOCK_EXTENSION: &str = "lock";
+/// 使用中ロックを更新する間隔（秒）
+pub const LOCK_REFRESH_SECS: u64 = 30;
+/// 更新が途絶えたロックを残骸とみなすまでの時間（秒）
+pub const LOCK_STALE_SECS: u64 = 120;
+/// 読み込めなかったファイルの退避名（"<ファイル名>.broken-<日時>"）の接尾辞
+pub const QUARANTINE_SUFFIX: &str = "broken";
+/// 退避名に付ける日時の形式
+pub const QUARANTINE_TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+// --- バックアップ ---
+/// データファイルと同じ場所に作成するバックアップフォルダ名
+pub const BACKUP_DIR_NAME: &str = "backups";
+/// バックアップファイルの拡張子
+pub const BACKUP_EXTENSION: &str = "bak";
+/// 保持する直近の保存のバックアップ件数
+pub const BACKUP_KEEP_RECENT: usize = 10;
+/// 保持する日次バックアップの件数
+pub const BACKUP_KEEP_DAILY: usize = 7;
+/// 保持する週次バックアップの件数
+pub const BACKUP_KEEP_WEEKLY: usize = 4;
+/// 保存場所に置くバックアップ保持件数の設定ファイル名
+pub const BACKUP_SETTINGS_FILENAME: &str = "backup.json";
+
+// --- 元に戻す／やり直し ---
+/// 保持する履歴の最大件数
+pub const MAX_UNDO_HISTORY: usize = 100;
 
 // --- 減衰率 (Decay Rate) ---
+/// 減衰率の最小値
 pub const MIN_DECAY_RATE: f64 = 0.01;
+/// 減衰率の最大値
 pub const MAX_DECAY_RATE: f64 = 1.00;
+/// 減衰率の既定値
 pub const DEFAULT_DECAY_RATE: f64 = 0.90;
 
-// --- ウィンドウ設定 ---
-pub const WINDOW_WIDTH: f32 = 1000.0;
-pub const WINDOW_HEIGHT: f32 = 600.0;
+// --- 経過日数による減衰 (Half-life) ---
+/// 半減期（日）の最小値
+pub const MIN_HALF_LIFE_DAYS: f64 = 0.1;
+/// 半減期（日）の最大値
+pub const MAX_HALF_LIFE_DAYS: f64 = 3650.0;
+/// 半減期（日）の既定値
+pub const DEFAULT_HALF_LIFE_DAYS: f64 = 30.0;
+
+// --- カテゴリ集計における項目の重要度 (Importance) ---
+/// 重要度の最小値
+pub const MIN_IMPORTANCE: f64 = 0.0;
+/// 重要度の最大値
+pub const MAX_IMPORTANCE: f64 = 100.0;
+/// 重要度の既定値
+pub const DEFAULT_IMPORTANCE: f64 = 1.0;
+/// カテゴリ集計値の表示桁数
+pub const CATEGORY_AGGREGATE_PRECISION: usize = 2;
+
+// --- 直近N件の重み付け (Sliding window) ---
+/// 対象件数の最小値
+pub const MIN_WINDOW_SIZE: usize = 1;
+/// 対象件数の最大値
+pub const MAX_WINDOW_SIZE: usize = 1000;
+/// 対象件数の既定値
+pub const DEFAULT_WINDOW_SIZE: usize = 10;
+
+// --- スコアの値 ---
+/// 小数型の項目で指定できる小数点以下の最大桁数
+pub const MAX_DECIMAL_PRECISION: u8 = 4;
+
+// --- 日時入力 ---
+/// 日時入力の形式（ローカル時刻）
+pub const TIMESTAMP_INPUT_FORMAT: &str = "%Y-%m-%d %H:%M";
+/// 日時入力の形式の表示用
+pub const TIMESTAMP_INPUT_HINT: &str = "YYYY-MM-DD HH:MM";
+/// 日付だけの入力の形式（その日の 0:00 とみなす）
+pub const DATE_INPUT_FORMAT: &str = "%Y-%m-%d";
+/// 日付だけの入力の形式の表示用
+pub const DATE_INPUT_HINT: &str = "YYYY-MM-DD";
+
+// --- CSV ---
+/// 書き出す記録日時の形式（ローカル時刻。取り込みでは RFC 3339 と日時入力の形式も受け付ける）
+pub const CSV_TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+/// CSV 書き出しの既定のファイル名
+pub const CSV_EXPORT_FILENAME: &str = "scores_export.csv";
+
+// --- HTTP API ---
+/// serve コマンドの既定の待ち受けポート（127.0.0.1 のみ）
+pub const SERVER_DEFAULT_PORT: u16 = 8765;
+/// 受け付けるリクエスト本文の最大バイト数
+pub const SERVER_MAX_BODY_BYTES: usize = 64 * 1024;
 
-// --- フォント設定 ---
-pub const FONT_SCALE: f32 = 1.2;
-// 優先順位順のフォントパス
-pub const FONT_PATHS: &[&str] = &[
-    "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc",
-    "/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc",
-    "C:\\Windows\\Fonts\\meiryo.ttc",
-];
+// --- 起動中の画面への転送 ---
+/// 接続を待つ時間（秒）
+pub const IPC_CONNECT_TIMEOUT_SECS: u64 = 2;
+/// 画面が操作を反映して応答するまで待つ時間（秒）
+pub const IPC_REPLY_TIMEOUT_SECS: u64 = 10;
+/// 1件のメッセージの最大バイト数
+pub const IPC_MAX_MESSAGE_BYTES: usize = 64 * 1024;
 
 // --- 計算・プロットロジック ---
-// グラフから非表示する重みの閾値
+/// グラフから非表示する重みの閾値
 pub const PLOT_WEIGHT_THRESHOLD: f64 = 0.1;
-pub const BAR_BASE_COLOR: egui::Color32 = egui::Color32::from_rgb(65, 105, 225);
+/// 傾きが標準誤差の何倍を超えたら改善・悪化とみなすか（以内なら横ばい）
+pub const TREND_SIGNIFICANCE: f64 = 2.0;
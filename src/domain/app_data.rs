@@ -1,25 +1,23 @@
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-use super::{CategoryData, DomainError, ItemData};
+use super::{CategoryData, CategoryId, DomainError, ItemData, ItemId, ItemSettings, ValueType};
 
-#[derive(Serialize, Deserialize, Default, Clone)]
+/// 保存対象のデータ全体
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
 pub struct AppData {
-    pub categories: HashMap<String, CategoryData>,
+    /// カテゴリ（IDごと）
+    pub categories: HashMap<CategoryId, CategoryData>,
 }
 
 impl AppData {
-    fn category_not_found(cat_name: &str) -> DomainError {
-        DomainError::NotFound(format!("カテゴリ「{}」が見つかりません。", cat_name))
-    }
-
-    fn item_not_found(item_name: &str) -> DomainError {
-        DomainError::NotFound(format!("項目「{}」が見つかりません。", item_name))
+    fn category_not_found() -> DomainError {
+        DomainError::NotFound("カテゴリが見つかりません。".to_string())
     }
 
     fn ensure_category_name_available(&self, category_name: &str) -> Result<(), DomainError> {
-        if self.categories.contains_key(category_name) {
+        if self.find_category_id(category_name).is_some() {
             return Err(DomainError::AlreadyExists(format!(
                 "カテゴリ「{}」は既に使用されています。",
                 category_name
@@ -30,44 +28,46 @@ impl AppData {
 
     // ヘルパー関数
 
+    /// 名前からカテゴリIDを検索
+    pub fn find_category_id(&self, cat_name: &str) -> Option<CategoryId> {
+        self.categories
+            .iter()
+            .find(|(_, cat)| cat.name == cat_name)
+            .map(|(id, _)| *id)
+    }
+
     /// カテゴリを検索、参照を返す
-    pub fn get_category(&self, cat_name: &str) -> Result<&CategoryData, DomainError> {
+    pub fn get_category(&self, cat: CategoryId) -> Result<&CategoryData, DomainError> {
         self.categories
-            .get(cat_name)
-            .ok_or_else(|| Self::category_not_found(cat_name))
+            .get(&cat)
+            .ok_or_else(Self::category_not_found)
     }
 
     /// カテゴリを検索、可変参照を返す
-    fn get_category_mut(&mut self, cat_name: &str) -> Result<&mut CategoryData, DomainError> {
+    fn get_category_mut(&mut self, cat: CategoryId) -> Result<&mut CategoryData, DomainError> {
         self.categories
-            .get_mut(cat_name)
-            .ok_or_else(|| Self::category_not_found(cat_name))
+            .get_mut(&cat)
+            .ok_or_else(Self::category_not_found)
     }
 
     /// 項目を検索、参照を返す
-    pub fn get_item(&self, cat_name: &str, item_name: &str) -> Result<&ItemData, DomainError> {
-        self.get_category(cat_name)?
-            .items
-            .get(item_name)
-            .ok_or_else(|| Self::item_not_found(item_name))
+    pub fn get_item(&self, cat: CategoryId, item: ItemId) -> Result<&ItemData, DomainError> {
+        self.get_category(cat)?.get_item(item)
     }
 
     /// 項目を検索、可変参照を返す
     fn get_item_mut(
         &mut self,
-        cat_name: &str,
-        item_name: &str,
+        cat: CategoryId,
+        item: ItemId,
     ) -> Result<&mut ItemData, DomainError> {
-        self.get_category_mut(cat_name)?
-            .items
-            .get_mut(item_name)
-            .ok_or_else(|| Self::item_not_found(item_name))
+        self.get_category_mut(cat)?.get_item_mut(item)
     }
 
     // =======================================================================================
 
     /// 新しいカテゴリを追加
-    pub fn add_category(&mut self, name: String) -> Result<(), DomainError> {
+    pub fn add_category(&mut self, name: String) -> Result<CategoryId, DomainError> {
         let name = name.trim().to_string();
         if name.is_empty() {
             return Err(DomainError::Validation(
@@ -76,26 +76,26 @@ impl AppData {
         }
         self.ensure_category_name_available(&name)?;
 
-        let cat = CategoryData {
-            items: HashMap::new(),
-            created_at: Utc::now(),
-        };
-
-        self.categories.insert(name, cat);
-        Ok(())
+        let id = CategoryId::generate();
+        self.categories.insert(id, CategoryData::new(name));
+        Ok(id)
     }
 
     /// カテゴリを削除
-    pub fn remove_category(&mut self, name: &str) -> Result<CategoryData, DomainError> {
-        self.categories.remove(name).ok_or_else(|| {
-            DomainError::NotFound(format!("削除対象のカテゴリ「{}」が見つかりません。", name))
+    pub fn remove_category(&mut self, cat: CategoryId) -> Result<CategoryData, DomainError> {
+        self.categories.remove(&cat).ok_or_else(|| {
+            DomainError::NotFound("削除対象のカテゴリが見つかりません。".to_string())
         })
     }
 
-    /// カテゴリ名変更
-    pub fn rename_category(&mut self, old_name: &str, new_name: String) -> Result<(), DomainError> {
+    /// カテゴリ名変更（IDは変わらない）
+    pub fn rename_category(
+        &mut self,
+        cat: CategoryId,
+        new_name: String,
+    ) -> Result<(), DomainError> {
         let new_name = new_name.trim().to_string();
-        if old_name == new_name {
+        if self.get_category(cat)?.name == new_name {
             return Ok(()); // 更新なし
         }
 
@@ -106,12 +106,7 @@ impl AppData {
         }
         self.ensure_category_name_available(&new_name)?;
 
-        let cat = self
-            .categories
-            .remove(old_name)
-            .ok_or_else(|| Self::category_not_found(old_name))?;
-
-        self.categories.insert(new_name, cat);
+        self.get_category_mut(cat)?.name = new_name;
         Ok(())
     }
 
@@ -120,89 +115,205 @@ impl AppData {
     /// 項目の追加
     pub fn add_item(
         &mut self,
-        cat: &str,
+        cat: CategoryId,
         name: String,
         decay_rate: f64,
-    ) -> Result<(), DomainError> {
-        self.get_category_mut(cat)?.add_item(name, decay_rate)
+        value_type: ValueType,
+    ) -> Result<ItemId, DomainError> {
+        self.get_category_mut(cat)?
+            .add_item(name, decay_rate, value_type)
     }
 
     /// 項目の削除
-    pub fn remove_item(&mut self, cat: &str, item: &str) -> Result<(), DomainError> {
+    pub fn remove_item(&mut self, cat: CategoryId, item: ItemId) -> Result<(), DomainError> {
         self.get_category_mut(cat)?.remove_item(item).map(|_| ())
     }
 
     /// 項目名の変更
     pub fn rename_item(
         &mut self,
-        cat: &str,
-        old_name: &str,
+        cat: CategoryId,
+        item: ItemId,
         new_name: String,
     ) -> Result<(), DomainError> {
-        self.get_category_mut(cat)?.rename_item(old_name, new_name)
+        self.get_category_mut(cat)?.rename_item(item, new_name)
     }
 
-    /// 減衰率を変更
-    pub fn update_decay(&mut self, cat: &str, item: &str, decay: f64) -> Result<(), DomainError> {
-        self.get_item_mut(cat, item)?.update_decay_rate(decay)
+    /// 項目の設定を一括変更
+    pub fn update_item_settings(
+        &mut self,
+        cat: CategoryId,
+        item: ItemId,
+        settings: ItemSettings,
+    ) -> Result<(), DomainError> {
+        self.get_item_mut(cat, item)?.update_settings(settings)
     }
 
-    /// 項目のカテゴリを変更
+    /// 項目のカテゴリを変更（IDは変わらない）
     pub fn move_item(
         &mut self,
-        old_cat: &str,
-        new_cat: &str,
-        item: &str,
+        old_cat: CategoryId,
+        new_cat: CategoryId,
+        item: ItemId,
     ) -> Result<(), DomainError> {
         if old_cat == new_cat {
             return Ok(());
         }
 
-        let item_data = self.get_category_mut(old_cat)?.remove_item(item)?;
-
-        let target_cat = self.get_category_mut(new_cat)?;
-        if target_cat.item_exists(item) {
+        // 移動先の存在と同名重複を先に確認（移動元から取り出した後に失敗しないように）
+        let item_name = &self.get_item(old_cat, item)?.name;
+        if self.get_category(new_cat)?.item_exists(item_name) {
             return Err(DomainError::AlreadyExists(format!(
                 "移動先に同名の項目が存在します: {}",
-                item
+                item_name
             )));
         }
-
-        target_cat.items.insert(item.to_string(), item_data);
-        Ok(())
+        let item_data = self.get_category_mut(old_cat)?.remove_item(item)?;
+        self.get_category_mut(new_cat)?.insert_item(item, item_data)
     }
 
     // =======================
 
-    /// スコアを追加
-    pub fn add_score(&mut self, cat: &str, item: &str, score: i64) -> Result<(), DomainError> {
-        self.get_item_mut(cat, item)?.add_score(score)
+    /// スコアを追加（記録日時の順に挿入）
+    pub fn add_score(
+        &mut self,
+        cat: CategoryId,
+        item: ItemId,
+        score: f64,
+        timestamp: DateTime<Utc>,
+    ) -> Result<(), DomainError> {
+        self.get_item_mut(cat, item)?.add_score(score, timestamp)
+    }
+
+    /// メモ・タグ付きでスコアを追加（記録日時の順に挿入）
+    pub fn add_score_with_note(
+        &mut self,
+        cat: CategoryId,
+        item: ItemId,
+        score: f64,
+        timestamp: DateTime<Utc>,
+        note: String,
+        tags: Vec<String>,
+    ) -> Result<(), DomainError> {
+        self.get_item_mut(cat, item)?
+            .add_score_with_note(score, timestamp, note, tags)
+    }
+
+    /// スコアの値・記録日時を更新
+    pub fn update_score(
+        &mut self,
+        cat: CategoryId,
+        item: ItemId,
+        index: usize,
+        score: f64,
+        timestamp: DateTime<Utc>,
+    ) -> Result<(), DomainError> {
+        self.get_item_mut(cat, item)?
+            .update_score(index, score, timestamp)
     }
 
     /// スコアを削除
-    pub fn remove_score(&mut self, cat: &str, item: &str, index: usize) -> Result<(), DomainError> {
+    pub fn remove_score(
+        &mut self,
+        cat: CategoryId,
+        item: ItemId,
+        index: usize,
+    ) -> Result<(), DomainError> {
         self.get_item_mut(cat, item)?.remove_score(index)
     }
+
+    /// スコアのメモ・タグを更新
+    pub fn update_score_note(
+        &mut self,
+        cat: CategoryId,
+        item: ItemId,
+        index: usize,
+        note: String,
+        tags: Vec<String>,
+    ) -> Result<(), DomainError> {
+        self.get_item_mut(cat, item)?
+            .update_score_note(index, note, tags)
+    }
+
+    // =======================
+
+    /// 別のデータにしかないカテゴリ・項目・スコアを取り込む（外部で変更されたデータとの統合用）
+    ///
+    /// ID か名前が一致するカテゴリ・項目は同じものとみなし、名前や設定はこちらを優先する。
+    /// 別のデータ側の削除は反映しない。
+    pub fn merge_missing_from(&mut self, other: &AppData) {
+        for (other_cat_id, other_cat) in &other.categories {
+            let cat_id = if self.categories.contains_key(other_cat_id) {
+                *other_cat_id
+            } else if let Some(id) = self.find_category_id(&other_cat.name) {
+                id
+            } else {
+                self.categories.insert(*other_cat_id, other_cat.clone());
+                continue;
+            };
+
+            for (other_item_id, other_item) in &other_cat.items {
+                // 別のカテゴリへ移動した項目も ID で見つける
+                let existing = self
+                    .categories
+                    .iter()
+                    .find(|(_, category)| category.items.contains_key(other_item_id))
+                    .map(|(id, _)| (*id, *other_item_id))
+                    .or_else(|| {
+                        self.categories[&cat_id]
+                            .find_item_id(&other_item.name)
+                            .map(|item| (cat_id, item))
+                    });
+
+                match existing {
+                    Some((cat, item)) => {
+                        if let Ok(item_data) = self.get_item_mut(cat, item) {
+                            item_data.merge_scores_from(other_item);
+                        }
+                    }
+                    None => {
+                        if let Some(category) = self.categories.get_mut(&cat_id) {
+                            category.items.insert(*other_item_id, other_item.clone());
+                        }
+                    }
+                }
+            }
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    fn seed_data() -> AppData {
+    struct Seed {
+        data: AppData,
+        cat_a: CategoryId,
+        cat_b: CategoryId,
+        item1: ItemId,
+    }
+
+    fn seed_data() -> Seed {
         let mut data = AppData::default();
-        data.add_category("CatA".to_string()).unwrap();
-        data.add_category("CatB".to_string()).unwrap();
-        data.add_item("CatA", "Item1".to_string(), 0.9).unwrap();
-        data
+        let cat_a = data.add_category("CatA".to_string()).unwrap();
+        let cat_b = data.add_category("CatB".to_string()).unwrap();
+        let item1 = data
+            .add_item(cat_a, "Item1".to_string(), 0.9, ValueType::Integer)
+            .unwrap();
+        Seed {
+            data,
+            cat_a,
+            cat_b,
+            item1,
+        }
     }
 
     #[test]
     fn add_category_trims_name_and_rejects_invalid_or_duplicate() {
         // カテゴリ名の前後空白が除去され、空名と重複名が拒否されることを確認する。
         let mut data = AppData::default();
-        data.add_category("  Work  ".to_string()).unwrap();
-        assert!(data.categories.contains_key("Work"));
+        let id = data.add_category("  Work  ".to_string()).unwrap();
+        assert_eq!(data.find_category_id("Work"), Some(id));
 
         let err = data.add_category("   ".to_string()).unwrap_err();
         assert!(matches!(err, DomainError::Validation(_)));
@@ -212,62 +323,129 @@ mod tests {
     }
 
     #[test]
-    fn rename_category_validates_and_moves_data() {
-        // カテゴリ名変更時にデータが移動し、不正入力と不存在カテゴリがエラーになることを確認する。
-        let mut data = seed_data();
-
-        data.rename_category("CatA", "  Focus  ".to_string())
+    fn rename_category_validates_and_keeps_id() {
+        // カテゴリ名変更で ID と中身が保たれ、不正入力と不存在カテゴリがエラーになることを確認する。
+        let Seed {
+            mut data,
+            cat_a,
+            cat_b,
+            item1,
+        } = seed_data();
+
+        data.rename_category(cat_a, "  Focus  ".to_string())
             .unwrap();
-        assert!(data.categories.contains_key("Focus"));
-        assert!(!data.categories.contains_key("CatA"));
-        assert!(data.get_item("Focus", "Item1").is_ok());
+        assert_eq!(data.get_category(cat_a).unwrap().name, "Focus");
+        assert_eq!(data.find_category_id("CatA"), None);
+        assert!(data.get_item(cat_a, item1).is_ok());
 
-        let err = data
-            .rename_category("Focus", "   ".to_string())
-            .unwrap_err();
+        let err = data.rename_category(cat_a, "   ".to_string()).unwrap_err();
         assert!(matches!(err, DomainError::Validation(_)));
 
+        let err = data.rename_category(cat_a, "CatB".to_string()).unwrap_err();
+        assert!(matches!(err, DomainError::AlreadyExists(_)));
+        assert!(data.get_category(cat_b).is_ok());
+
         let err = data
-            .rename_category("Unknown", "X".to_string())
+            .rename_category(CategoryId::generate(), "X".to_string())
             .unwrap_err();
         assert!(matches!(err, DomainError::NotFound(_)));
     }
 
     #[test]
-    fn move_item_moves_between_categories_and_checks_duplicates() {
-        // 項目のカテゴリ移動が成功し、移動先で同名重複がある場合はエラーになることを確認する。
-        let mut data = seed_data();
-        data.add_item("CatB", "Item2".to_string(), 0.8).unwrap();
-
-        data.move_item("CatA", "CatB", "Item1").unwrap();
-        assert!(data.get_item("CatB", "Item1").is_ok());
-        assert!(data.get_item("CatA", "Item1").is_err());
-
-        data.add_item("CatA", "Shared".to_string(), 0.7).unwrap();
-        data.add_item("CatB", "Shared".to_string(), 0.7).unwrap();
-        let err = data.move_item("CatA", "CatB", "Shared").unwrap_err();
+    fn move_item_keeps_id_and_checks_duplicates() {
+        // 項目のカテゴリ移動で ID が保たれ、移動先で同名重複がある場合はエラーになることを確認する。
+        let Seed {
+            mut data,
+            cat_a,
+            cat_b,
+            item1,
+        } = seed_data();
+
+        data.move_item(cat_a, cat_b, item1).unwrap();
+        assert!(data.get_item(cat_b, item1).is_ok());
+        assert!(data.get_item(cat_a, item1).is_err());
+
+        let shared = data
+            .add_item(cat_a, "Shared".to_string(), 0.7, ValueType::Integer)
+            .unwrap();
+        data.add_item(cat_b, "Shared".to_string(), 0.7, ValueType::Integer)
+            .unwrap();
+        let err = data.move_item(cat_a, cat_b, shared).unwrap_err();
         assert!(matches!(err, DomainError::AlreadyExists(_)));
+
+        // 失敗時は移動元から失われない
+        assert!(data.get_item(cat_a, shared).is_ok());
+        let err = data
+            .move_item(cat_a, CategoryId::generate(), shared)
+            .unwrap_err();
+        assert!(matches!(err, DomainError::NotFound(_)));
+        assert!(data.get_item(cat_a, shared).is_ok());
     }
 
     #[test]
     fn remove_item_propagates_not_found_error() {
         // 存在しない項目の削除時に NotFound エラーが呼び出し元へ返ることを確認する。
-        let mut data = seed_data();
-
-        data.remove_item("CatA", "Item1").unwrap();
-        let err = data.remove_item("CatA", "Item1").unwrap_err();
+        let Seed {
+            mut data,
+            cat_a,
+            item1,
+            ..
+        } = seed_data();
+
+        data.remove_item(cat_a, item1).unwrap();
+        let err = data.remove_item(cat_a, item1).unwrap_err();
         assert!(matches!(err, DomainError::NotFound(_)));
     }
 
     #[test]
     fn add_and_remove_score_through_app_data() {
         // AppData 経由でスコア追加と削除を行ったときに件数が正しく変化することを確認する。
-        let mut data = seed_data();
-
-        data.add_score("CatA", "Item1", 10).unwrap();
-        assert_eq!(data.get_item("CatA", "Item1").unwrap().scores.len(), 1);
+        let Seed {
+            mut data,
+            cat_a,
+            item1,
+            ..
+        } = seed_data();
+
+        data.add_score(cat_a, item1, 10.0, Utc::now()).unwrap();
+        assert_eq!(data.get_item(cat_a, item1).unwrap().scores.len(), 1);
+
+        data.remove_score(cat_a, item1, 0).unwrap();
+        assert_eq!(data.get_item(cat_a, item1).unwrap().scores.len(), 0);
+    }
 
-        data.remove_score("CatA", "Item1", 0).unwrap();
-        assert_eq!(data.get_item("CatA", "Item1").unwrap().scores.len(), 0);
+    #[test]
+    fn merge_missing_from_keeps_both_sides() {
+        // 統合すると相手にしかない項目・スコアが追加され、こちらの名前変更と共通のスコアは重複しないことを確認する。
+        let Seed {
+            mut data,
+            cat_a,
+            cat_b,
+            item1,
+        } = seed_data();
+        let shared_at = Utc::now() - chrono::Duration::days(1);
+        data.add_score(cat_a, item1, 5.0, shared_at).unwrap();
+
+        let mut other = data.clone();
+        other.add_score(cat_a, item1, 7.0, Utc::now()).unwrap();
+        let added = other
+            .add_item(cat_b, "Item2".to_string(), 0.9, ValueType::Integer)
+            .unwrap();
+        let new_cat = other.add_category("CatC".to_string()).unwrap();
+
+        data.rename_category(cat_a, "Renamed".to_string()).unwrap();
+        data.merge_missing_from(&other);
+
+        assert_eq!(data.get_category(cat_a).unwrap().name, "Renamed");
+        let scores: Vec<f64> = data
+            .get_item(cat_a, item1)
+            .unwrap()
+            .scores
+            .iter()
+            .map(|entry| entry.score)
+            .collect();
+        assert_eq!(scores, vec![5.0, 7.0]);
+        assert!(data.get_item(cat_b, added).is_ok());
+        assert!(data.get_category(new_cat).is_ok());
     }
 }
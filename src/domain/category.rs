@@ -2,19 +2,37 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-use super::{DomainError, ItemData, default_created_at};
+use super::{DomainError, ItemData, ItemId, ValueType, default_created_at};
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+/// カテゴリ（項目をまとめる）
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct CategoryData {
-    pub items: HashMap<String, ItemData>,
+    /// カテゴリ名
+    pub name: String,
+    /// 項目（IDごと）
+    pub items: HashMap<ItemId, ItemData>,
 
+    /// 作成日時
     #[serde(default = "default_created_at")]
     pub created_at: DateTime<Utc>,
 }
 
 impl CategoryData {
+    /// 項目のないカテゴリを作る
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            items: HashMap::new(),
+            created_at: Utc::now(),
+        }
+    }
+
+    fn item_not_found() -> DomainError {
+        DomainError::NotFound("項目が見つかりません。".to_string())
+    }
+
     fn ensure_item_name_available(&self, item_name: &str) -> Result<(), DomainError> {
-        if self.items.contains_key(item_name) {
+        if self.item_exists(item_name) {
             return Err(DomainError::AlreadyExists(format!(
                 "項目「{}」は既に存在します。",
                 item_name
@@ -23,28 +41,52 @@ impl CategoryData {
         Ok(())
     }
 
-    pub fn item_exists(&self, item: &str) -> bool {
-        self.items.contains_key(item)
+    /// 同じ名前の項目があるかどうか
+    pub fn item_exists(&self, item_name: &str) -> bool {
+        self.find_item_id(item_name).is_some()
+    }
+
+    /// 名前から項目IDを検索
+    pub fn find_item_id(&self, item_name: &str) -> Option<ItemId> {
+        self.items
+            .iter()
+            .find(|(_, item)| item.name == item_name)
+            .map(|(id, _)| *id)
+    }
+
+    /// 項目を検索、参照を返す
+    pub fn get_item(&self, item_id: ItemId) -> Result<&ItemData, DomainError> {
+        self.items.get(&item_id).ok_or_else(Self::item_not_found)
+    }
+
+    /// 項目を検索、可変参照を返す
+    pub fn get_item_mut(&mut self, item_id: ItemId) -> Result<&mut ItemData, DomainError> {
+        self.items
+            .get_mut(&item_id)
+            .ok_or_else(Self::item_not_found)
     }
 
-    pub fn add_item(&mut self, name: String, decay_rate: f64) -> Result<(), DomainError> {
+    /// 項目を追加する（名前の重複は不可）
+    pub fn add_item(
+        &mut self,
+        name: String,
+        decay_rate: f64,
+        value_type: ValueType,
+    ) -> Result<ItemId, DomainError> {
         let name = name.trim().to_string();
         self.ensure_item_name_available(&name)?;
+        value_type.validate()?;
 
-        let now = Utc::now();
-        let item = ItemData {
-            scores: Vec::new(),
-            decay_rate,
-            updated_at: now,
-        };
-
-        self.items.insert(name, item);
-        Ok(())
+        let id = ItemId::generate();
+        self.items
+            .insert(id, ItemData::new(name, decay_rate, value_type));
+        Ok(id)
     }
 
-    pub fn rename_item(&mut self, old_name: &str, new_name: String) -> Result<(), DomainError> {
+    /// 項目名を変更する（名前の重複は不可）
+    pub fn rename_item(&mut self, item_id: ItemId, new_name: String) -> Result<(), DomainError> {
         let new_name = new_name.trim().to_string();
-        if old_name == new_name {
+        if self.get_item(item_id)?.name == new_name {
             return Ok(()); // 更新なし
         }
 
@@ -55,18 +97,27 @@ impl CategoryData {
         }
         self.ensure_item_name_available(&new_name)?;
 
-        let item = self
-            .items
-            .remove(old_name)
-            .ok_or_else(|| DomainError::NotFound("変更元の項目が見つかりません。".to_string()))?;
+        self.get_item_mut(item_id)?.name = new_name;
+        Ok(())
+    }
+
+    /// 他カテゴリから移動してきた項目を、IDを保ったまま受け入れる
+    pub fn insert_item(&mut self, item_id: ItemId, item: ItemData) -> Result<(), DomainError> {
+        if self.item_exists(&item.name) {
+            return Err(DomainError::AlreadyExists(format!(
+                "移動先に同名の項目が存在します: {}",
+                item.name
+            )));
+        }
 
-        self.items.insert(new_name, item);
+        self.items.insert(item_id, item);
         Ok(())
     }
 
-    pub fn remove_item(&mut self, item_name: &str) -> Result<ItemData, DomainError> {
+    /// 項目を取り除いて返す
+    pub fn remove_item(&mut self, item_id: ItemId) -> Result<ItemData, DomainError> {
         self.items
-            .remove(item_name)
+            .remove(&item_id)
             .ok_or_else(|| DomainError::NotFound("削除対象の項目が見つかりません。".to_string()))
     }
 }
@@ -76,38 +127,42 @@ mod tests {
     use super::*;
 
     fn empty_category() -> CategoryData {
-        CategoryData {
-            items: HashMap::new(),
-            created_at: Utc::now(),
-        }
+        CategoryData::new("Cat".to_string())
     }
 
     #[test]
     fn add_item_trims_name_and_rejects_duplicate() {
         // 項目名の前後空白が除去され、同名項目の追加が拒否されることを確認する。
         let mut category = empty_category();
-        category.add_item("  A  ".to_string(), 0.9).unwrap();
+        let id = category
+            .add_item("  A  ".to_string(), 0.9, ValueType::Integer)
+            .unwrap();
         assert!(category.item_exists("A"));
+        assert_eq!(category.find_item_id("A"), Some(id));
 
-        let err = category.add_item("A".to_string(), 0.9).unwrap_err();
+        let err = category
+            .add_item("A".to_string(), 0.9, ValueType::Integer)
+            .unwrap_err();
         assert!(matches!(err, DomainError::AlreadyExists(_)));
     }
 
     #[test]
-    fn rename_item_validates_and_moves_entry() {
-        // 項目名変更でエントリが移動し、空名と不存在項目がエラーになることを確認する。
+    fn rename_item_validates_and_keeps_id() {
+        // 項目名変更で ID が保たれたまま名前だけが変わり、空名と不存在項目がエラーになることを確認する。
         let mut category = empty_category();
-        category.add_item("Old".to_string(), 0.9).unwrap();
+        let id = category
+            .add_item("Old".to_string(), 0.9, ValueType::Integer)
+            .unwrap();
 
-        category.rename_item("Old", "  New  ".to_string()).unwrap();
-        assert!(category.item_exists("New"));
+        category.rename_item(id, "  New  ".to_string()).unwrap();
+        assert_eq!(category.get_item(id).unwrap().name, "New");
         assert!(!category.item_exists("Old"));
 
-        let err = category.rename_item("New", "   ".to_string()).unwrap_err();
+        let err = category.rename_item(id, "   ".to_string()).unwrap_err();
         assert!(matches!(err, DomainError::Validation(_)));
 
         let err = category
-            .rename_item("Missing", "X".to_string())
+            .rename_item(ItemId::generate(), "X".to_string())
             .unwrap_err();
         assert!(matches!(err, DomainError::NotFound(_)));
     }
@@ -116,7 +171,7 @@ mod tests {
     fn remove_item_returns_error_when_missing() {
         // 存在しない項目を削除しようとした場合に NotFound エラーになることを確認する。
         let mut category = empty_category();
-        let err = category.remove_item("Nope").unwrap_err();
+        let err = category.remove_item(ItemId::generate()).unwrap_err();
         assert!(matches!(err, DomainError::NotFound(_)));
     }
 }
@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// スコアの良し悪しの向き
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ScoreDirection {
+    /// 高いほど良い（得点など）
+    #[default]
+    HigherIsBetter,
+    /// 低いほど良い（タイム・ミス数など）
+    LowerIsBetter,
+}
+
+impl ScoreDirection {
+    /// a が b より良いスコアなら Ordering::Less（良い順ソート用）
+    pub fn compare(&self, a: f64, b: f64) -> Ordering {
+        match self {
+            ScoreDirection::HigherIsBetter => b.total_cmp(&a),
+            ScoreDirection::LowerIsBetter => a.total_cmp(&b),
+        }
+    }
+
+    /// a が b より厳密に良いスコアかどうか
+    pub fn is_better(&self, a: f64, b: f64) -> bool {
+        self.compare(a, b) == Ordering::Less
+    }
+
+    /// 画面表示用の名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            ScoreDirection::HigherIsBetter => "高いほど良い",
+            ScoreDirection::LowerIsBetter => "低いほど良い",
+        }
+    }
+
+    /// 自己ベストの表記
+    pub fn best_label(&self) -> &'static str {
+        match self {
+            ScoreDirection::HigherIsBetter => "最高スコア",
+            ScoreDirection::LowerIsBetter => "最小スコア",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compare_orders_better_scores_first() {
+        // 向きに応じて良いスコアが先頭に来る順序になることを確認する。
+        let mut values = vec![3.0, 1.0, 2.0];
+        values.sort_by(|a, b| ScoreDirection::HigherIsBetter.compare(*a, *b));
+        assert_eq!(values, vec![3.0, 2.0, 1.0]);
+
+        values.sort_by(|a, b| ScoreDirection::LowerIsBetter.compare(*a, *b));
+        assert_eq!(values, vec![1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn is_better_is_strict() {
+        // 同じ値は「より良い」と判定されないことを確認する。
+        assert!(ScoreDirection::LowerIsBetter.is_better(1.0, 2.0));
+        assert!(!ScoreDirection::LowerIsBetter.is_better(2.0, 2.0));
+        assert!(ScoreDirection::HigherIsBetter.is_better(2.0, 1.0));
+    }
+}
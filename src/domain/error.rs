@@ -1,9 +1,13 @@
 use std::fmt;
 
+/// ドメインの規則に反する操作のエラー
 #[derive(Debug, Clone, PartialEq)]
 pub enum DomainError {
+    /// 値が条件を満たさない
     Validation(String),
+    /// 対象が見つからない
     NotFound(String),
+    /// 名前が既に使われている
     AlreadyExists(String),
 }
 
@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use uuid::Uuid;
+
+/// カテゴリの永続ID（名称変更・移動の影響を受けない）
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(transparent)]
+pub struct CategoryId(Uuid);
+
+/// 項目の永続ID（名称変更・カテゴリ移動の影響を受けない）
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(transparent)]
+pub struct ItemId(Uuid);
+
+impl CategoryId {
+    /// 新しい ID を発行
+    pub fn generate() -> Self {
+        Self(Uuid::new_v4())
+    }
+}
+
+impl ItemId {
+    /// 新しい ID を発行
+    pub fn generate() -> Self {
+        Self(Uuid::new_v4())
+    }
+}
+
+impl FromStr for CategoryId {
+    type Err = uuid::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Uuid::parse_str(s).map(Self)
+    }
+}
+
+impl FromStr for ItemId {
+    type Err = uuid::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Uuid::parse_str(s).map(Self)
+    }
+}
+
+impl fmt::Display for CategoryId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl fmt::Display for ItemId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_ids_are_unique() {
+        // 新規発行した ID が毎回異なる値になることを確認する。
+        assert_ne!(CategoryId::generate(), CategoryId::generate());
+        assert_ne!(ItemId::generate(), ItemId::generate());
+    }
+
+    #[test]
+    fn ids_serialize_as_plain_uuid_strings() {
+        // ID が UUID 文字列そのものとして保存・復元されることを確認する。
+        let id = ItemId::generate();
+        let json = serde_json::to_string(&id).unwrap();
+        assert_eq!(json, format!("\"{}\"", id));
+
+        let restored: ItemId = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, id);
+    }
+}
@@ -1,8 +1,12 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use super::{DomainError, default_created_at};
-use crate::constants::{MAX_DECAY_RATE, MIN_DECAY_RATE};
+use super::{
+    DomainError, ScoreDirection, ValueRules, ValueType, WeightingMode, default_created_at,
+};
+use crate::constants::{
+    DEFAULT_IMPORTANCE, MAX_DECAY_RATE, MAX_IMPORTANCE, MIN_DECAY_RATE, MIN_IMPORTANCE,
+};
 
 // バリデーションヘルパー関数
 pub fn validate_decay_rate_range(rate: f64) -> Result<(), DomainError> {
@@ -16,43 +20,292 @@ pub fn validate_decay_rate_range(rate: f64) -> Result<(), DomainError> {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+pub fn validate_importance_range(importance: f64) -> Result<(), DomainError> {
+    if (MIN_IMPORTANCE..=MAX_IMPORTANCE).contains(&importance) {
+        Ok(())
+    } else {
+        Err(DomainError::Validation(format!(
+            "重要度は {} ～ {} の範囲で指定してください。",
+            MIN_IMPORTANCE, MAX_IMPORTANCE
+        )))
+    }
+}
+
+fn default_importance() -> f64 {
+    DEFAULT_IMPORTANCE
+}
+
+/// タグ入力の正規化（前後空白除去・空要素除外・重複除外）
+pub fn normalize_tags<I, S>(tags: I) -> Vec<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.as_ref().trim();
+        if !tag.is_empty() && !normalized.iter().any(|t| t == tag) {
+            normalized.push(tag.to_string());
+        }
+    }
+    normalized
+}
+
+/// スコア値と記録日時の検証
+pub fn validate_score_entry(
+    score: f64,
+    timestamp: DateTime<Utc>,
+    value_type: ValueType,
+    rules: &ValueRules,
+) -> Result<(), DomainError> {
+    if !value_type.accepts(score) {
+        return Err(DomainError::Validation(format!(
+            "この項目のスコアは{}で入力してください。",
+            value_type.label()
+        )));
+    }
+    rules.check(score, value_type)?;
+    if timestamp > Utc::now() {
+        return Err(DomainError::Validation(
+            "未来の日時は指定できません。".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// 1回分のスコアの記録
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct ScoreEntry {
-    pub score: i64,
+    /// スコア
+    pub score: f64,
+    /// 記録日時
     pub timestamp: DateTime<Utc>,
+
+    /// メモ
+    // 古いJSONファイルには存在しないため、未設定なら空で埋める
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// タグ（重複なし）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+impl ScoreEntry {
+    /// タグが付いているかどうか
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+/// 項目編集で一括変更する設定値
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ItemSettings {
+    /// 減衰率
+    pub decay_rate: f64,
+    /// 値の種類
+    pub value_type: ValueType,
+    /// 入力条件
+    pub rules: ValueRules,
+    /// 評価の向き
+    pub direction: ScoreDirection,
+    /// 重み付け方式
+    pub weighting: WeightingMode,
+    /// カテゴリ集計での重要度
+    pub importance: f64,
+}
+
+/// 項目（スコアの記録と集計の設定）
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct ItemData {
+    /// 項目名
+    pub name: String,
+    /// スコア（記録日時の古い順）
     pub scores: Vec<ScoreEntry>,
+    /// 減衰率
     pub decay_rate: f64,
 
-    // 古いJSONファイルの "created_at" も読み込む
-    #[serde(alias = "created_at", default = "default_created_at")]
-    // 未設定の場合、現在時刻で埋める
+    /// 値の種類
+    // 古いJSONファイルには存在しないため、未設定なら整数として扱う
+    #[serde(default)]
+    pub value_type: ValueType,
+
+    /// 入力条件
+    // 未設定なら「マイナス不可」のみの条件
+    #[serde(default)]
+    pub rules: ValueRules,
+
+    /// 評価の向き
+    // 未設定なら「高いほど良い」
+    #[serde(default)]
+    pub direction: ScoreDirection,
+
+    /// 重み付け方式
+    // 未設定なら記録順による減衰
+    #[serde(default)]
+    pub weighting: WeightingMode,
+
+    /// カテゴリ集計での重要度（未設定なら 1.0）
+    #[serde(default = "default_importance")]
+    pub importance: f64,
+
+    /// 最後に変更した日時（並び順に使う）
+    // 未設定の場合、現在時刻で埋める（旧名称 "created_at" は読み込み時に変換済み）
+    #[serde(default = "default_created_at")]
     pub updated_at: DateTime<Utc>,
 }
 
 impl ItemData {
-    pub fn add_score(&mut self, score: i64) -> Result<(), DomainError> {
-        if score < 0 {
-            return Err(DomainError::Validation(
-                "スコアにマイナスの値は入力できません。".to_string(),
-            ));
+    /// スコアのない項目を作る（その他の設定は既定値）
+    pub fn new(name: String, decay_rate: f64, value_type: ValueType) -> Self {
+        Self {
+            name,
+            scores: Vec::new(),
+            decay_rate,
+            value_type,
+            rules: ValueRules::default(),
+            direction: ScoreDirection::default(),
+            weighting: WeightingMode::default(),
+            importance: DEFAULT_IMPORTANCE,
+            updated_at: Utc::now(),
         }
+    }
 
-        let now = Utc::now();
+    /// 現在の設定値
+    pub fn settings(&self) -> ItemSettings {
+        ItemSettings {
+            decay_rate: self.decay_rate,
+            value_type: self.value_type,
+            rules: self.rules,
+            direction: self.direction,
+            weighting: self.weighting,
+            importance: self.importance,
+        }
+    }
+
+    /// 設定値を一括変更（検証に失敗した場合は何も変更しない）
+    pub fn update_settings(&mut self, settings: ItemSettings) -> Result<(), DomainError> {
+        let mut updated = self.clone();
+        updated.update_decay_rate(settings.decay_rate)?;
+        updated.update_value_settings(settings.value_type, settings.rules)?;
+        settings.weighting.validate()?;
+        validate_importance_range(settings.importance)?;
+        updated.direction = settings.direction;
+        updated.weighting = settings.weighting;
+        updated.importance = settings.importance;
+
+        *self = updated;
+        Ok(())
+    }
+
+    /// 自己ベストのインデックス（同値の場合は最初に記録したもの）
+    pub fn personal_best_index(&self) -> Option<usize> {
+        self.scores
+            .iter()
+            .enumerate()
+            .fold(None, |best, (i, entry)| match best {
+                Some(b) if !self.direction.is_better(entry.score, self.scores[b].score) => Some(b),
+                _ => Some(i),
+            })
+    }
+
+    /// 成績の良い順に並べたスコアのインデックス（同値の場合は記録順）
+    pub fn ranked_indices(&self) -> Vec<usize> {
+        let mut indices: Vec<usize> = (0..self.scores.len()).collect();
+        indices.sort_by(|&a, &b| {
+            self.direction
+                .compare(self.scores[a].score, self.scores[b].score)
+        });
+        indices
+    }
+
+    /// スコアを追加（記録日時の順序を保って挿入）
+    pub fn add_score(&mut self, score: f64, timestamp: DateTime<Utc>) -> Result<(), DomainError> {
+        self.add_score_with_note(score, timestamp, String::new(), Vec::new())
+    }
+
+    /// メモ・タグ付きでスコアを追加（CSV取り込みなど）
+    pub fn add_score_with_note(
+        &mut self,
+        score: f64,
+        timestamp: DateTime<Utc>,
+        note: String,
+        tags: Vec<String>,
+    ) -> Result<(), DomainError> {
+        validate_score_entry(score, timestamp, self.value_type, &self.rules)?;
+
+        let note = note.trim().to_string();
         let score_entry = ScoreEntry {
-            score,
-            timestamp: now,
+            score: self.value_type.round(score),
+            timestamp,
+            note: (!note.is_empty()).then_some(note),
+            tags: normalize_tags(tags),
         };
 
-        self.scores.push(score_entry);
-        self.updated_at = now;
+        self.insert_chronologically(score_entry);
+        self.updated_at = Utc::now(); // 記録日時ではなく更新操作の日時
+
+        Ok(())
+    }
+
+    /// スコアの値と記録日時を更新（メモ・タグは保持したまま時系列順に並べ直す）
+    pub fn update_score(
+        &mut self,
+        index: usize,
+        score: f64,
+        timestamp: DateTime<Utc>,
+    ) -> Result<(), DomainError> {
+        if index >= self.scores.len() {
+            return Err(DomainError::Validation(
+                "指定されたスコアのインデックスが範囲外です。".to_string(),
+            ));
+        }
+        validate_score_entry(score, timestamp, self.value_type, &self.rules)?;
+
+        let mut entry = self.scores.remove(index);
+        entry.score = self.value_type.round(score);
+        entry.timestamp = timestamp;
+
+        self.insert_chronologically(entry);
+        self.updated_at = Utc::now();
 
         Ok(())
     }
 
+    /// 別の記録にしかないスコアを取り込み、取り込んだ件数を返す
+    ///
+    /// 記録日時とスコアが同じものは同じ記録とみなし、メモ・タグはこちらを優先する。
+    pub fn merge_scores_from(&mut self, other: &ItemData) -> usize {
+        let missing: Vec<ScoreEntry> = other
+            .scores
+            .iter()
+            .filter(|entry| {
+                !self
+                    .scores
+                    .iter()
+                    .any(|own| own.timestamp == entry.timestamp && own.score == entry.score)
+            })
+            .cloned()
+            .collect();
+
+        let count = missing.len();
+        missing
+            .into_iter()
+            .for_each(|entry| self.insert_chronologically(entry));
+        if count > 0 {
+            self.updated_at = self.updated_at.max(other.updated_at);
+        }
+        count
+    }
+
+    // 同時刻のスコアがある場合は、その後ろに挿入
+    fn insert_chronologically(&mut self, entry: ScoreEntry) {
+        let position = self
+            .scores
+            .partition_point(|s| s.timestamp <= entry.timestamp);
+        self.scores.insert(position, entry);
+    }
+
+    /// スコアを削除する
     pub fn remove_score(&mut self, index: usize) -> Result<(), DomainError> {
         if index >= self.scores.len() {
             return Err(DomainError::Validation(
@@ -66,25 +319,85 @@ impl ItemData {
         Ok(())
     }
 
+    /// スコアのメモとタグを更新
+    pub fn update_score_note(
+        &mut self,
+        index: usize,
+        note: String,
+        tags: Vec<String>,
+    ) -> Result<(), DomainError> {
+        let entry = self.scores.get_mut(index).ok_or_else(|| {
+            DomainError::Validation("指定されたスコアのインデックスが範囲外です。".to_string())
+        })?;
+
+        let note = note.trim().to_string();
+        entry.note = (!note.is_empty()).then_some(note);
+        entry.tags = normalize_tags(tags);
+
+        Ok(())
+    }
+
+    /// 項目内で使用されているタグ一覧（重複なし、名前順）
+    pub fn all_tags(&self) -> Vec<String> {
+        let mut tags = normalize_tags(self.scores.iter().flat_map(|s| s.tags.iter()));
+        tags.sort();
+        tags
+    }
+
+    /// 減衰率を変更する
     pub fn update_decay_rate(&mut self, new_rate: f64) -> Result<(), DomainError> {
         validate_decay_rate_range(new_rate)?;
 
         self.decay_rate = new_rate;
         Ok(())
     }
+
+    /// 値の種類と入力条件を変更（既存スコアが新しい設定を満たさない場合はエラー）
+    pub fn update_value_settings(
+        &mut self,
+        value_type: ValueType,
+        rules: ValueRules,
+    ) -> Result<(), DomainError> {
+        value_type.validate()?;
+        rules.validate(value_type)?;
+
+        if !self.scores.iter().all(|s| value_type.accepts(s.score)) {
+            return Err(DomainError::Validation(format!(
+                "既存のスコアを{}で表現できないため、値の種類を変更できません。",
+                value_type.label()
+            )));
+        }
+
+        if let Some((index, err)) = self
+            .scores
+            .iter()
+            .enumerate()
+            .find_map(|(i, s)| rules.check(s.score, value_type).err().map(|e| (i, e)))
+        {
+            let DomainError::Validation(msg) = err else {
+                return Err(err);
+            };
+            return Err(DomainError::Validation(format!(
+                "{}回目のスコアが新しい条件を満たしません（{}）",
+                index + 1,
+                msg
+            )));
+        }
+
+        self.value_type = value_type;
+        self.rules = rules;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::constants::{MAX_DECAY_RATE, MIN_DECAY_RATE};
+    use chrono::Duration;
 
     fn sample_item() -> ItemData {
-        ItemData {
-            scores: Vec::new(),
-            decay_rate: 0.9,
-            updated_at: Utc::now(),
-        }
+        ItemData::new("Item".to_string(), 0.9, ValueType::Integer)
     }
 
     #[test]
@@ -106,22 +419,321 @@ mod tests {
     fn add_score_rejects_negative_value() {
         // 負のスコアを追加しようとするとエラーになり履歴が増えないことを確認する。
         let mut item = sample_item();
-        let err = item.add_score(-1).unwrap_err();
+        let err = item.add_score(-1.0, Utc::now()).unwrap_err();
         assert!(matches!(err, DomainError::Validation(_)));
         assert!(item.scores.is_empty());
     }
 
+    #[test]
+    fn add_score_inserts_backdated_entry_in_chronological_order() {
+        // 過去日時のスコアが時系列順の位置に挿入され、更新日時は現在時刻になることを確認する。
+        let mut item = sample_item();
+        let now = Utc::now();
+        item.add_score(1.0, now - Duration::days(10)).unwrap();
+        item.add_score(3.0, now - Duration::days(1)).unwrap();
+
+        item.add_score(2.0, now - Duration::days(5)).unwrap();
+
+        let scores: Vec<f64> = item.scores.iter().map(|s| s.score).collect();
+        assert_eq!(scores, vec![1.0, 2.0, 3.0]);
+        assert!(item.updated_at >= now);
+    }
+
+    #[test]
+    fn add_score_rejects_future_timestamp() {
+        // 未来日時のスコアを追加しようとするとエラーになることを確認する。
+        let mut item = sample_item();
+        let err = item
+            .add_score(10.0, Utc::now() + Duration::days(1))
+            .unwrap_err();
+        assert!(matches!(err, DomainError::Validation(_)));
+        assert!(item.scores.is_empty());
+    }
+
+    #[test]
+    fn update_score_reorders_entry_and_keeps_note() {
+        // スコア更新で値と日時が変わり、時系列順に並べ直され、メモは保持されることを確認する。
+        let mut item = sample_item();
+        let now = Utc::now();
+        item.add_score(1.0, now - Duration::days(3)).unwrap();
+        item.add_score(2.0, now - Duration::days(2)).unwrap();
+        item.update_score_note(1, "memo".to_string(), Vec::new())
+            .unwrap();
+
+        item.update_score(1, 5.0, now - Duration::days(4)).unwrap();
+
+        assert_eq!(item.scores[0].score, 5.0);
+        assert_eq!(item.scores[0].timestamp, now - Duration::days(4));
+        assert_eq!(item.scores[0].note.as_deref(), Some("memo"));
+        assert_eq!(item.scores[1].score, 1.0);
+    }
+
+    #[test]
+    fn update_score_validates_like_add_score() {
+        // スコア更新でも負の値・未来日時・範囲外インデックスが拒否され、履歴が変わらないことを確認する。
+        let mut item = sample_item();
+        item.add_score(10.0, Utc::now()).unwrap();
+
+        let err = item.update_score(0, -1.0, Utc::now()).unwrap_err();
+        assert!(matches!(err, DomainError::Validation(_)));
+        let err = item
+            .update_score(0, 1.0, Utc::now() + Duration::days(1))
+            .unwrap_err();
+        assert!(matches!(err, DomainError::Validation(_)));
+        let err = item.update_score(1, 1.0, Utc::now()).unwrap_err();
+        assert!(matches!(err, DomainError::Validation(_)));
+
+        assert_eq!(item.scores[0].score, 10.0);
+    }
+
     #[test]
     fn remove_score_rejects_out_of_bounds_index() {
         // 範囲外インデックスの削除がエラーとなり既存スコアが保持されることを確認する。
         let mut item = sample_item();
-        item.add_score(42).unwrap();
+        item.add_score(42.0, Utc::now()).unwrap();
 
         let err = item.remove_score(1).unwrap_err();
         assert!(matches!(err, DomainError::Validation(_)));
         assert_eq!(item.scores.len(), 1);
     }
 
+    #[test]
+    fn update_score_note_trims_note_and_normalizes_tags() {
+        // メモの前後空白が除去され、タグの空要素と重複が取り除かれることを確認する。
+        let mut item = sample_item();
+        item.add_score(42.0, Utc::now()).unwrap();
+
+        item.update_score_note(
+            0,
+            "  調子が悪い  ".to_string(),
+            vec![" 寝不足 ".to_string(), "".to_string(), "寝不足".to_string()],
+        )
+        .unwrap();
+
+        let entry = &item.scores[0];
+        assert_eq!(entry.note.as_deref(), Some("調子が悪い"));
+        assert_eq!(entry.tags, vec!["寝不足".to_string()]);
+        assert!(entry.has_tag("寝不足"));
+
+        // 空白のみのメモは未設定として扱う
+        item.update_score_note(0, "   ".to_string(), Vec::new())
+            .unwrap();
+        assert!(item.scores[0].note.is_none());
+        assert!(item.scores[0].tags.is_empty());
+    }
+
+    #[test]
+    fn update_score_note_rejects_out_of_bounds_index() {
+        // 範囲外インデックスのメモ更新がエラーになることを確認する。
+        let mut item = sample_item();
+        let err = item
+            .update_score_note(0, "memo".to_string(), Vec::new())
+            .unwrap_err();
+        assert!(matches!(err, DomainError::Validation(_)));
+    }
+
+    #[test]
+    fn all_tags_returns_sorted_unique_tags() {
+        // 項目内のタグ一覧が重複なしの名前順で返ることを確認する。
+        let mut item = sample_item();
+        item.add_score(1.0, Utc::now()).unwrap();
+        item.add_score(2.0, Utc::now()).unwrap();
+        item.update_score_note(0, String::new(), vec!["b".to_string(), "a".to_string()])
+            .unwrap();
+        item.update_score_note(1, String::new(), vec!["a".to_string()])
+            .unwrap();
+
+        assert_eq!(item.all_tags(), vec!["a".to_string(), "b".to_string()]);
+    }
+
+    #[test]
+    fn score_entry_without_note_and_tags_deserializes() {
+        // メモ・タグを持たない旧形式のスコアも読み込めることを確認する。
+        let entry: ScoreEntry =
+            serde_json::from_str(r#"{"score":10,"timestamp":"2024-01-01T00:00:00Z"}"#).unwrap();
+        assert!(entry.note.is_none());
+        assert!(entry.tags.is_empty());
+    }
+
+    #[test]
+    fn add_score_rejects_value_not_matching_value_type() {
+        // 整数型の項目には小数を追加できず、小数型の項目には指定桁数まで追加できることを確認する。
+        let mut item = sample_item();
+        let err = item.add_score(87.5, Utc::now()).unwrap_err();
+        assert!(matches!(err, DomainError::Validation(_)));
+
+        let mut item = ItemData::new("Item".to_string(), 0.9, ValueType::Decimal(1));
+        item.add_score(87.5, Utc::now()).unwrap();
+        assert_eq!(item.scores[0].score, 87.5);
+        assert!(item.add_score(87.55, Utc::now()).is_err());
+    }
+
+    #[test]
+    fn update_value_settings_rejects_type_that_cannot_hold_existing_scores() {
+        // 既存スコアを表現できない値の種類への変更が拒否され、表現できる場合は変更されることを確認する。
+        let mut item = ItemData::new("Item".to_string(), 0.9, ValueType::Decimal(2));
+        item.add_score(1.25, Utc::now()).unwrap();
+
+        let err = item
+            .update_value_settings(ValueType::Integer, ValueRules::default())
+            .unwrap_err();
+        assert!(matches!(err, DomainError::Validation(_)));
+        assert_eq!(item.value_type, ValueType::Decimal(2));
+
+        item.update_value_settings(ValueType::Decimal(3), ValueRules::default())
+            .unwrap();
+        assert_eq!(item.value_type, ValueType::Decimal(3));
+    }
+
+    #[test]
+    fn add_score_enforces_item_rules() {
+        // 項目の入力条件に従って、マイナス許可時は負の値を受け付け、範囲外は拒否することを確認する。
+        let mut item = ItemData::new("Item".to_string(), 0.9, ValueType::Decimal(1));
+        item.update_value_settings(
+            ValueType::Decimal(1),
+            ValueRules {
+                min: Some(-5.0),
+                max: Some(54.0),
+                step: None,
+                allow_negative: true,
+            },
+        )
+        .unwrap();
+
+        item.add_score(-2.4, Utc::now()).unwrap();
+        let err = item.add_score(54.1, Utc::now()).unwrap_err();
+        assert!(matches!(err, DomainError::Validation(_)));
+        assert_eq!(item.scores.len(), 1);
+    }
+
+    #[test]
+    fn update_value_settings_rejects_rules_violated_by_existing_scores() {
+        // 既存スコアが新しい条件を満たさない場合に条件変更が拒否され、元の条件が保持されることを確認する。
+        let mut item = sample_item();
+        item.add_score(120.0, Utc::now()).unwrap();
+
+        let err = item
+            .update_value_settings(
+                ValueType::Integer,
+                ValueRules {
+                    max: Some(100.0),
+                    ..Default::default()
+                },
+            )
+            .unwrap_err();
+        assert!(matches!(err, DomainError::Validation(_)));
+        assert_eq!(item.rules, ValueRules::default());
+    }
+
+    #[test]
+    fn update_value_settings_validates_rules_against_new_type() {
+        // 値の種類と刻み幅を同時に変更した場合、新しい値の種類に対して刻み幅が検証されることを確認する。
+        let mut item = ItemData::new("Item".to_string(), 0.9, ValueType::Decimal(1));
+        let half_step = ValueRules {
+            step: Some(0.5),
+            ..Default::default()
+        };
+        item.update_value_settings(ValueType::Decimal(1), half_step)
+            .unwrap();
+
+        // 整数型への変更と同時に刻み幅を解除できる
+        item.update_value_settings(ValueType::Integer, ValueRules::default())
+            .unwrap();
+        assert!(
+            item.update_value_settings(ValueType::Integer, half_step)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn personal_best_follows_direction() {
+        // 自己ベストと成績順が、項目の向き（高いほど良い／低いほど良い）に従うことを確認する。
+        let mut item = sample_item();
+        for score in [30.0, 10.0, 50.0, 10.0] {
+            item.add_score(score, Utc::now()).unwrap();
+        }
+
+        assert_eq!(item.personal_best_index(), Some(2));
+        assert_eq!(item.ranked_indices(), vec![2, 0, 1, 3]);
+
+        item.direction = ScoreDirection::LowerIsBetter;
+        assert_eq!(item.personal_best_index(), Some(1));
+        assert_eq!(item.ranked_indices(), vec![1, 3, 0, 2]);
+
+        assert_eq!(sample_item().personal_best_index(), None);
+    }
+
+    #[test]
+    fn update_settings_is_atomic_on_validation_error() {
+        // 設定の一括変更で検証エラーが出た場合に、どの設定も変更されないことを確認する。
+        let mut item = sample_item();
+        item.add_score(5.0, Utc::now()).unwrap();
+        let before = item.settings();
+
+        let err = item
+            .update_settings(ItemSettings {
+                decay_rate: 0.5,
+                value_type: ValueType::Integer,
+                rules: ValueRules {
+                    min: Some(10.0),
+                    ..Default::default()
+                },
+                direction: ScoreDirection::LowerIsBetter,
+                weighting: WeightingMode::TimeDecay {
+                    half_life_days: 7.0,
+                },
+                importance: 2.0,
+            })
+            .unwrap_err();
+        assert!(matches!(err, DomainError::Validation(_)));
+        assert_eq!(item.settings(), before);
+
+        let err = item
+            .update_settings(ItemSettings {
+                decay_rate: 0.5,
+                weighting: WeightingMode::TimeDecay {
+                    half_life_days: 0.0,
+                },
+                ..before
+            })
+            .unwrap_err();
+        assert!(matches!(err, DomainError::Validation(_)));
+        assert_eq!(item.settings(), before);
+
+        let err = item
+            .update_settings(ItemSettings {
+                importance: -1.0,
+                ..before
+            })
+            .unwrap_err();
+        assert!(matches!(err, DomainError::Validation(_)));
+        assert_eq!(item.settings(), before);
+
+        let valid = ItemSettings {
+            decay_rate: 0.5,
+            importance: 3.0,
+            direction: ScoreDirection::LowerIsBetter,
+            ..before
+        };
+        item.update_settings(valid).unwrap();
+        assert_eq!(item.settings(), valid);
+    }
+
+    #[test]
+    fn item_without_value_type_deserializes_as_integer() {
+        // 値の種類を持たない旧形式の項目が整数型として読み込まれ、整数スコアも保持されることを確認する。
+        let item: ItemData = serde_json::from_str(
+            r#"{"name":"Item","scores":[{"score":42,"timestamp":"2024-01-01T00:00:00Z"}],"decay_rate":0.9}"#,
+        )
+        .unwrap();
+        assert_eq!(item.value_type, ValueType::Integer);
+        assert_eq!(item.rules, ValueRules::default());
+        assert_eq!(item.direction, ScoreDirection::HigherIsBetter);
+        assert_eq!(item.weighting, WeightingMode::Exponential);
+        assert_eq!(item.importance, DEFAULT_IMPORTANCE);
+        assert_eq!(item.scores[0].score, 42.0);
+    }
+
     #[test]
     fn update_decay_rate_changes_value_on_success() {
         // 有効な減衰率で更新した場合に値が正しく反映されることを確認する。
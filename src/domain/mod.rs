@@ -1,18 +1,29 @@
 use chrono::{DateTime, Utc};
+/// 日時が未設定の古いデータを読み込む際の既定値（現在時刻）
 pub fn default_created_at() -> DateTime<Utc> {
     Utc::now()
 }
 
 mod app_data;
 mod category;
+mod direction;
 mod error;
+mod id;
 mod item;
 mod model;
 mod selection;
+mod value_rules;
+mod value_type;
+mod weighting;
 
 pub use app_data::AppData;
 pub use category::CategoryData;
+pub use direction::ScoreDirection;
 pub use error::DomainError;
-pub use item::{ItemData, ScoreEntry};
+pub use id::{CategoryId, ItemId};
+pub use item::{ItemData, ItemSettings, ScoreEntry, normalize_tags};
 pub use model::TrackerModel;
 pub use selection::SelectionState;
+pub use value_rules::ValueRules;
+pub use value_type::ValueType;
+pub use weighting::{WeightingKind, WeightingMode};
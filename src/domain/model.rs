@@ -1,14 +1,19 @@
+use chrono::{DateTime, Utc};
+
 use crate::domain::error::DomainError;
 
-use super::{AppData, ItemData, SelectionState};
+use super::{AppData, CategoryId, ItemData, ItemId, ItemSettings, SelectionState, ValueType};
 
 /// アプリケーションのドメインロジックと状態を一元管理するモデル
 pub struct TrackerModel {
+    /// 保存対象のデータ
     pub data: AppData,
+    /// 画面での選択状態（保存しない）
     pub selection: SelectionState,
 }
 
 impl TrackerModel {
+    /// 何も選択していない状態で作る
     pub fn new(data: AppData) -> Self {
         Self {
             data,
@@ -18,156 +23,275 @@ impl TrackerModel {
 
     // --- 参照系ヘルパー ---
 
-    pub fn get_item(&self, cat: &str, item: &str) -> Result<&ItemData, DomainError> {
+    /// 項目を検索、参照を返す
+    pub fn get_item(&self, cat: CategoryId, item: ItemId) -> Result<&ItemData, DomainError> {
         self.data.get_item(cat, item)
     }
 
+    /// 選択中の項目データ
+    pub fn selected_item(&self) -> Option<&ItemData> {
+        let (Some(cat), Some(item)) = (self.selection.category, self.selection.item) else {
+            return None;
+        };
+        self.data.get_item(cat, item).ok()
+    }
+
     // --- 操作系ロジック ---
 
-    pub fn add_category(&mut self, name: String) -> Result<(), DomainError> {
+    /// カテゴリを追加する
+    pub fn add_category(&mut self, name: String) -> Result<CategoryId, DomainError> {
         self.data.add_category(name)
     }
 
-    pub fn rename_category(&mut self, old_name: &str, new_name: String) -> Result<(), DomainError> {
-        let normalized_name = new_name.trim().to_string();
-        self.data
-            .rename_category(old_name, normalized_name.clone())?;
-
-        // 選択状態の自動追従
-        if self.selection.category.as_deref() == Some(old_name) {
-            self.selection.category = Some(normalized_name);
-        }
-
-        Ok(())
+    /// カテゴリ名変更（IDで選択しているため選択状態はそのまま）
+    pub fn rename_category(
+        &mut self,
+        cat: CategoryId,
+        new_name: String,
+    ) -> Result<(), DomainError> {
+        self.data.rename_category(cat, new_name)
     }
 
-    pub fn remove_category(&mut self, name: &str) -> Result<(), DomainError> {
-        self.data.remove_category(name)?;
+    /// カテゴリを削除する（選択中なら選択を解除）
+    pub fn remove_category(&mut self, cat: CategoryId) -> Result<(), DomainError> {
+        self.data.remove_category(cat)?;
 
         // 選択中のカテゴリが削除されたら選択解除
-        if self.selection.category.as_deref() == Some(name) {
+        if self.selection.category == Some(cat) {
             self.selection.clear();
         }
 
         Ok(())
     }
 
+    /// 項目を追加する
     pub fn add_item(
         &mut self,
-        cat_name: &str,
+        cat: CategoryId,
         item_name: String,
         decay: f64,
-    ) -> Result<(), DomainError> {
-        self.data.add_item(cat_name, item_name, decay)
+        value_type: ValueType,
+    ) -> Result<ItemId, DomainError> {
+        self.data.add_item(cat, item_name, decay, value_type)
     }
 
-    pub fn remove_item(&mut self, cat_name: &str, item_name: &str) -> Result<(), DomainError> {
-        self.data.remove_item(cat_name, item_name)?;
+    /// 項目を削除する（選択中なら選択を解除）
+    pub fn remove_item(&mut self, cat: CategoryId, item: ItemId) -> Result<(), DomainError> {
+        self.data.remove_item(cat, item)?;
 
-        if self.selection.category.as_deref() == Some(cat_name)
-            && self.selection.item.as_deref() == Some(item_name)
-        {
+        if self.selection.category == Some(cat) && self.selection.item == Some(item) {
             self.selection.clear();
         }
 
         Ok(())
     }
 
+    /// 項目の移動・名称変更・設定変更を一括で行う（失敗時は何も変更しない）
     pub fn update_item(
         &mut self,
-        old_loc: (&str, &str),
-        new_loc: (&str, &str),
-        decay: f64,
+        old_loc: (CategoryId, ItemId),
+        new_cat: CategoryId,
+        new_name: String,
+        settings: ItemSettings,
     ) -> Result<(), DomainError> {
-        let (old_cat, old_item) = old_loc;
-        let (new_cat, new_item) = new_loc;
+        let (old_cat, item) = old_loc;
 
         let mut temp_data = self.data.clone();
 
-        temp_data.move_item(old_cat, new_cat, old_item)?;
-        temp_data.rename_item(new_cat, old_item, new_item.to_string())?;
-        temp_data.update_decay(new_cat, new_item, decay)?;
+        temp_data.move_item(old_cat, new_cat, item)?;
+        temp_data.rename_item(new_cat, item, new_name)?;
+        temp_data.update_item_settings(new_cat, item, settings)?;
 
         // エラーが発生しなければ、上書き
         self.data = temp_data;
 
-        // 選択更新
-        if self.selection.category.as_deref() == Some(old_cat)
-            && self.selection.item.as_deref() == Some(old_item)
-        {
-            self.select_item(new_cat.to_string(), new_item.to_string());
+        // 選択更新（項目IDは変わらないため、カテゴリのみ追従）
+        if self.selection.category == Some(old_cat) && self.selection.item == Some(item) {
+            self.selection.category = Some(new_cat);
         }
 
         Ok(())
     }
 
+    /// スコアを追加
     pub fn add_score(
         &mut self,
-        cat_name: &str,
-        item_name: &str,
-        score: i64,
+        cat: CategoryId,
+        item: ItemId,
+        score: f64,
+        timestamp: DateTime<Utc>,
+    ) -> Result<(), DomainError> {
+        self.data.add_score(cat, item, score, timestamp)?;
+        // 挿入位置によってインデックスがずれるため選択解除
+        self.selection.history_index = None;
+        Ok(())
+    }
+
+    /// メモ・タグ付きでスコアを追加
+    pub fn add_score_with_note(
+        &mut self,
+        cat: CategoryId,
+        item: ItemId,
+        score: f64,
+        timestamp: DateTime<Utc>,
+        note: String,
+        tags: Vec<String>,
+    ) -> Result<(), DomainError> {
+        self.data
+            .add_score_with_note(cat, item, score, timestamp, note, tags)?;
+        self.selection.history_index = None;
+        Ok(())
+    }
+
+    /// スコアの値・記録日時を更新
+    pub fn update_score(
+        &mut self,
+        cat: CategoryId,
+        item: ItemId,
+        index: usize,
+        score: f64,
+        timestamp: DateTime<Utc>,
     ) -> Result<(), DomainError> {
-        self.data.add_score(cat_name, item_name, score)
+        self.data.update_score(cat, item, index, score, timestamp)?;
+        // 並べ替えによってインデックスがずれるため選択解除
+        self.selection.history_index = None;
+        Ok(())
     }
 
+    /// スコアを削除
     pub fn remove_score(
         &mut self,
-        cat_name: &str,
-        item_name: &str,
+        cat: CategoryId,
+        item: ItemId,
         index: usize,
     ) -> Result<(), DomainError> {
-        self.data.remove_score(cat_name, item_name, index)?;
+        self.data.remove_score(cat, item, index)?;
         self.selection.history_index = None;
         Ok(())
     }
 
+    /// スコアのメモ・タグを更新
+    pub fn update_score_note(
+        &mut self,
+        cat: CategoryId,
+        item: ItemId,
+        index: usize,
+        note: String,
+        tags: Vec<String>,
+    ) -> Result<(), DomainError> {
+        self.data.update_score_note(cat, item, index, note, tags)
+    }
+
+    /// データ全体を差し替える（元に戻す／やり直し・復元・取り込み用）
+    ///
+    /// 選択中の項目が別カテゴリに存在すればカテゴリを追従させ、存在しなければ選択を解除する。
+    pub fn restore_data(&mut self, data: AppData) {
+        self.data = data;
+        self.selection.history_index = None;
+
+        match (self.selection.category, self.selection.item) {
+            (_, Some(item)) => {
+                let owner = self
+                    .data
+                    .categories
+                    .iter()
+                    .find(|(_, category)| category.items.contains_key(&item))
+                    .map(|(id, _)| *id);
+                match owner {
+                    Some(cat) => self.selection.category = Some(cat),
+                    None => self.selection.clear(),
+                }
+            }
+            (Some(cat), None) if !self.data.categories.contains_key(&cat) => {
+                self.selection.clear();
+            }
+            _ => {}
+        }
+    }
+
     // 選択操作
-    pub fn select_item(&mut self, cat: String, item: String) {
+    /// 項目を選択する
+    pub fn select_item(&mut self, cat: CategoryId, item: ItemId) {
         self.selection.category = Some(cat);
         self.selection.item = Some(item);
         self.selection.history_index = None;
     }
+
+    /// カテゴリのみを選択（カテゴリ概要の表示）
+    pub fn select_category(&mut self, cat: CategoryId) {
+        self.selection.category = Some(cat);
+        self.selection.item = None;
+        self.selection.history_index = None;
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    fn seed_model() -> TrackerModel {
-        let mut model = TrackerModel::new(AppData::default());
-        model.add_category("A".to_string()).unwrap();
-        model.add_category("B".to_string()).unwrap();
-        model.add_item("A", "item1".to_string(), 0.9).unwrap();
-        model
+    fn settings_with_decay(decay_rate: f64) -> ItemSettings {
+        ItemSettings {
+            decay_rate,
+            value_type: ValueType::Integer,
+            rules: Default::default(),
+            direction: Default::default(),
+            weighting: Default::default(),
+            importance: 1.0,
+        }
     }
 
-    #[test]
-    fn rename_category_updates_selection() {
-        // 選択中カテゴリをリネームした際に選択状態も新しいカテゴリ名へ追従することを確認する。
-        let mut model = TrackerModel::new(AppData::default());
-        model.add_category("Old".to_string()).unwrap();
-
-        // 選択する
-        model.select_item("Old".to_string(), "Item".to_string());
-
-        // リネーム実行
-        model.rename_category("Old", "New".to_string()).unwrap();
+    struct Seed {
+        model: TrackerModel,
+        cat_a: CategoryId,
+        cat_b: CategoryId,
+        item1: ItemId,
+    }
 
-        // データが更新されているか
-        assert!(model.data.categories.contains_key("New"));
-        assert!(!model.data.categories.contains_key("Old"));
+    fn seed_model() -> Seed {
+        let mut model = TrackerModel::new(AppData::default());
+        let cat_a = model.add_category("A".to_string()).unwrap();
+        let cat_b = model.add_category("B".to_string()).unwrap();
+        let item1 = model
+            .add_item(cat_a, "item1".to_string(), 0.9, ValueType::Integer)
+            .unwrap();
+        Seed {
+            model,
+            cat_a,
+            cat_b,
+            item1,
+        }
+    }
 
-        // ★選択状態も自動で更新されているか確認★
-        assert_eq!(model.selection.category.as_deref(), Some("New"));
+    #[test]
+    fn rename_category_keeps_selection() {
+        // 選択中カテゴリをリネームしても ID による選択状態がそのまま有効であることを確認する。
+        let Seed {
+            mut model,
+            cat_a,
+            item1,
+            ..
+        } = seed_model();
+        model.select_item(cat_a, item1);
+
+        model.rename_category(cat_a, "New".to_string()).unwrap();
+
+        assert_eq!(model.data.get_category(cat_a).unwrap().name, "New");
+        assert_eq!(model.selection.category, Some(cat_a));
+        assert!(model.selected_item().is_some());
     }
 
     #[test]
     fn remove_category_clears_selection_when_target_selected() {
         // 選択中のカテゴリを削除したときに選択状態が初期化されることを確認する。
-        let mut model = seed_model();
-        model.select_item("A".to_string(), "item1".to_string());
+        let Seed {
+            mut model,
+            cat_a,
+            item1,
+            ..
+        } = seed_model();
+        model.select_item(cat_a, item1);
 
-        model.remove_category("A").unwrap();
+        model.remove_category(cat_a).unwrap();
 
         assert_eq!(model.selection, SelectionState::default());
     }
@@ -175,54 +299,153 @@ mod tests {
     #[test]
     fn remove_item_clears_selection_when_target_selected() {
         // 選択中の項目を削除したときに選択状態が初期化されることを確認する。
-        let mut model = seed_model();
-        model.select_item("A".to_string(), "item1".to_string());
+        let Seed {
+            mut model,
+            cat_a,
+            item1,
+            ..
+        } = seed_model();
+        model.select_item(cat_a, item1);
 
-        model.remove_item("A", "item1").unwrap();
+        model.remove_item(cat_a, item1).unwrap();
 
         assert_eq!(model.selection, SelectionState::default());
     }
 
     #[test]
     fn update_item_moves_renames_and_updates_selection() {
-        // 項目更新で移動・名称変更・減衰率更新が行われ、選択状態も更新されることを確認する。
-        let mut model = seed_model();
-        model.select_item("A".to_string(), "item1".to_string());
+        // 項目更新で移動・名称変更・減衰率更新が行われ、選択状態も移動先カテゴリへ追従することを確認する。
+        let Seed {
+            mut model,
+            cat_a,
+            cat_b,
+            item1,
+        } = seed_model();
+        model.select_item(cat_a, item1);
 
         model
-            .update_item(("A", "item1"), ("B", "item2"), 0.6)
+            .update_item(
+                (cat_a, item1),
+                cat_b,
+                "item2".to_string(),
+                settings_with_decay(0.6),
+            )
             .unwrap();
 
-        assert!(model.get_item("A", "item1").is_err());
-        let moved = model.get_item("B", "item2").unwrap();
+        assert!(model.get_item(cat_a, item1).is_err());
+        let moved = model.get_item(cat_b, item1).unwrap();
+        assert_eq!(moved.name, "item2");
         assert_eq!(moved.decay_rate, 0.6);
-        assert_eq!(model.selection.category.as_deref(), Some("B"));
-        assert_eq!(model.selection.item.as_deref(), Some("item2"));
+        assert_eq!(model.selection.category, Some(cat_b));
+        assert_eq!(model.selection.item, Some(item1));
     }
 
     #[test]
     fn update_item_is_atomic_when_destination_is_missing() {
         // 項目更新が失敗した場合に元データが破壊されず一貫性が保たれることを確認する。
-        let mut model = seed_model();
+        let Seed {
+            mut model,
+            cat_a,
+            item1,
+            ..
+        } = seed_model();
 
         let err = model
-            .update_item(("A", "item1"), ("MissingCat", "item2"), 0.5)
+            .update_item(
+                (cat_a, item1),
+                CategoryId::generate(),
+                "item2".to_string(),
+                settings_with_decay(0.5),
+            )
             .unwrap_err();
         assert!(matches!(err, DomainError::NotFound(_)));
 
-        assert!(model.get_item("A", "item1").is_ok());
-        assert!(model.get_item("MissingCat", "item2").is_err());
+        let item = model.get_item(cat_a, item1).unwrap();
+        assert_eq!(item.name, "item1");
     }
 
     #[test]
     fn remove_score_resets_history_selection() {
         // スコア削除後に履歴選択インデックスが解除されることを確認する。
-        let mut model = seed_model();
-        model.add_score("A", "item1", 100).unwrap();
+        let Seed {
+            mut model,
+            cat_a,
+            item1,
+            ..
+        } = seed_model();
+        model.add_score(cat_a, item1, 100.0, Utc::now()).unwrap();
         model.selection.history_index = Some(0);
 
-        model.remove_score("A", "item1", 0).unwrap();
+        model.remove_score(cat_a, item1, 0).unwrap();
 
         assert_eq!(model.selection.history_index, None);
     }
+
+    #[test]
+    fn select_category_clears_item_selection() {
+        // カテゴリのみの選択で項目選択と履歴選択が解除されることを確認する。
+        let Seed {
+            mut model,
+            cat_a,
+            cat_b,
+            item1,
+        } = seed_model();
+        model.select_item(cat_a, item1);
+        model.selection.history_index = Some(0);
+
+        model.select_category(cat_b);
+
+        assert_eq!(model.selection.category, Some(cat_b));
+        assert_eq!(model.selection.item, None);
+        assert_eq!(model.selection.history_index, None);
+        assert!(model.selected_item().is_none());
+    }
+
+    #[test]
+    fn restore_data_follows_item_to_its_category() {
+        // データ差し替え後、選択中の項目が存在するカテゴリに選択が追従することを確認する。
+        let Seed {
+            mut model,
+            cat_a,
+            cat_b,
+            item1,
+        } = seed_model();
+        let before = model.data.clone();
+        model
+            .update_item(
+                (cat_a, item1),
+                cat_b,
+                "item1".to_string(),
+                settings_with_decay(0.9),
+            )
+            .unwrap();
+        assert_eq!(model.selection.category, None);
+
+        model.select_item(cat_b, item1);
+        model.restore_data(before);
+
+        assert_eq!(model.selection.category, Some(cat_a));
+        assert!(model.selected_item().is_some());
+    }
+
+    #[test]
+    fn restore_data_clears_selection_when_target_missing() {
+        // データ差し替え後、選択中の項目やカテゴリが存在しなければ選択が解除されることを確認する。
+        let Seed {
+            mut model,
+            cat_a,
+            item1,
+            ..
+        } = seed_model();
+        model.select_item(cat_a, item1);
+        model.restore_data(AppData::default());
+        assert_eq!(model.selection, SelectionState::default());
+
+        let Seed {
+            mut model, cat_b, ..
+        } = seed_model();
+        model.select_category(cat_b);
+        model.restore_data(AppData::default());
+        assert_eq!(model.selection, SelectionState::default());
+    }
 }
@@ -1,11 +1,18 @@
+use super::{CategoryId, ItemId};
+
+/// 画面での選択状態
 #[derive(Default, Debug, Clone, PartialEq)]
 pub struct SelectionState {
-    pub category: Option<String>,
-    pub item: Option<String>,
+    /// 選択中のカテゴリ
+    pub category: Option<CategoryId>,
+    /// 選択中の項目
+    pub item: Option<ItemId>,
+    /// 選択中のスコア（履歴での位置）
     pub history_index: Option<usize>,
 }
 
 impl SelectionState {
+    /// 選択を解除する
     pub fn clear(&mut self) {
         self.category = None;
         self.item = None;
@@ -0,0 +1,179 @@
+use serde::{Deserialize, Serialize};
+
+use super::{DomainError, ValueType};
+
+/// 項目ごとのスコアの入力条件
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+pub struct ValueRules {
+    /// 最小値
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+    /// 最大値
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+    /// 刻み幅（最小値、未設定なら 0 からの倍数のみ受け付ける）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub step: Option<f64>,
+    /// マイナス値を受け付けるかどうか
+    #[serde(default)]
+    pub allow_negative: bool,
+}
+
+impl ValueRules {
+    /// 条件の設定そのものを検証
+    pub fn validate(&self, value_type: ValueType) -> Result<(), DomainError> {
+        let bounds = [self.min, self.max, self.step];
+        if bounds.iter().flatten().any(|v| !v.is_finite()) {
+            return Err(DomainError::Validation(
+                "最小値・最大値・刻み幅には有効な数値を指定してください。".to_string(),
+            ));
+        }
+
+        if let (Some(min), Some(max)) = (self.min, self.max)
+            && min > max
+        {
+            return Err(DomainError::Validation(
+                "最小値は最大値以下で指定してください。".to_string(),
+            ));
+        }
+
+        if !self.allow_negative && self.min.is_some_and(|min| min < 0.0) {
+            return Err(DomainError::Validation(
+                "最小値に負の値を指定する場合は、マイナスの値を許可してください。".to_string(),
+            ));
+        }
+
+        if let Some(step) = self.step {
+            if step <= 0.0 {
+                return Err(DomainError::Validation(
+                    "刻み幅には正の値を指定してください。".to_string(),
+                ));
+            }
+            if !value_type.accepts(step) {
+                return Err(DomainError::Validation(format!(
+                    "刻み幅は{}で指定してください。",
+                    value_type.label()
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// スコアが条件を満たすか検証
+    pub fn check(&self, score: f64, value_type: ValueType) -> Result<(), DomainError> {
+        if !self.allow_negative && score < 0.0 {
+            return Err(DomainError::Validation(
+                "スコアにマイナスの値は入力できません。".to_string(),
+            ));
+        }
+
+        if let Some(min) = self.min
+            && score < min
+        {
+            return Err(DomainError::Validation(format!(
+                "スコアは {} 以上で入力してください。",
+                value_type.format(min)
+            )));
+        }
+
+        if let Some(max) = self.max
+            && score > max
+        {
+            return Err(DomainError::Validation(format!(
+                "スコアは {} 以下で入力してください。",
+                value_type.format(max)
+            )));
+        }
+
+        if let Some(step) = self.step {
+            // 最小値（未設定なら0）を基準とした刻み
+            let steps = (score - self.min.unwrap_or(0.0)) / step;
+            if (steps - steps.round()).abs() > 1e-6 {
+                return Err(DomainError::Validation(format!(
+                    "スコアは {} 刻みで入力してください。",
+                    value_type.format(step)
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_rules_reject_only_negative_values() {
+        // 既定の条件では負の値のみが拒否されることを確認する。
+        let rules = ValueRules::default();
+        assert!(rules.check(0.0, ValueType::Integer).is_ok());
+        assert!(rules.check(1_000_000.0, ValueType::Integer).is_ok());
+        assert!(matches!(
+            rules.check(-1.0, ValueType::Integer),
+            Err(DomainError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn check_enforces_bounds_and_step() {
+        // 最小値・最大値・刻み幅の条件を満たさないスコアが拒否されることを確認する。
+        let rules = ValueRules {
+            min: Some(0.0),
+            max: Some(100.0),
+            step: Some(0.5),
+            allow_negative: false,
+        };
+        let value_type = ValueType::Decimal(1);
+
+        assert!(rules.check(0.0, value_type).is_ok());
+        assert!(rules.check(99.5, value_type).is_ok());
+        assert!(rules.check(100.0, value_type).is_ok());
+        assert!(rules.check(100.5, value_type).is_err());
+        assert!(rules.check(42.3, value_type).is_err());
+    }
+
+    #[test]
+    fn check_allows_negative_values_when_permitted() {
+        // マイナスの値を許可した場合は、最小値までの負の値が受け付けられることを確認する。
+        let rules = ValueRules {
+            min: Some(-10.0),
+            allow_negative: true,
+            ..Default::default()
+        };
+        assert!(rules.check(-3.2, ValueType::Decimal(1)).is_ok());
+        assert!(rules.check(-10.5, ValueType::Decimal(1)).is_err());
+    }
+
+    #[test]
+    fn validate_rejects_inconsistent_configuration() {
+        // 最小値 > 最大値、負の最小値の不許可、不正な刻み幅が設定エラーになることを確認する。
+        let inverted = ValueRules {
+            min: Some(10.0),
+            max: Some(0.0),
+            ..Default::default()
+        };
+        assert!(inverted.validate(ValueType::Integer).is_err());
+
+        let negative_min = ValueRules {
+            min: Some(-1.0),
+            ..Default::default()
+        };
+        assert!(negative_min.validate(ValueType::Integer).is_err());
+
+        let zero_step = ValueRules {
+            step: Some(0.0),
+            ..Default::default()
+        };
+        assert!(zero_step.validate(ValueType::Integer).is_err());
+
+        let fractional_step = ValueRules {
+            step: Some(0.5),
+            ..Default::default()
+        };
+        assert!(fractional_step.validate(ValueType::Integer).is_err());
+        assert!(fractional_step.validate(ValueType::Decimal(1)).is_ok());
+    }
+}
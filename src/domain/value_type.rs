@@ -0,0 +1,129 @@
+use serde::{Deserialize, Serialize};
+
+use super::DomainError;
+use crate::constants::MAX_DECIMAL_PRECISION;
+use crate::utils::comma_display::CommaDisplay;
+
+/// 項目ごとのスコアの値の種類
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ValueType {
+    /// 整数
+    #[default]
+    Integer,
+    /// 小数（小数点以下の桁数）
+    Decimal(u8),
+}
+
+impl ValueType {
+    /// 小数点以下の表示桁数
+    pub fn precision(&self) -> usize {
+        match self {
+            ValueType::Integer => 0,
+            ValueType::Decimal(digits) => *digits as usize,
+        }
+    }
+
+    /// 統計値（加重平均など）の表示桁数
+    pub fn stats_precision(&self) -> usize {
+        self.precision() + 2
+    }
+
+    /// 値の種類の設定を検証
+    pub fn validate(&self) -> Result<(), DomainError> {
+        match self {
+            ValueType::Decimal(digits) if !(1..=MAX_DECIMAL_PRECISION).contains(digits) => {
+                Err(DomainError::Validation(format!(
+                    "小数点以下の桁数は 1 ～ {} で指定してください。",
+                    MAX_DECIMAL_PRECISION
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// 値がこの種類で表現できるかどうか
+    pub fn accepts(&self, value: f64) -> bool {
+        if !value.is_finite() {
+            return false;
+        }
+        let scale = 10_f64.powi(self.precision() as i32);
+        let scaled = value * scale;
+        (scaled - scaled.round()).abs() < 1e-6
+    }
+
+    /// 値の種類に合わせた丸め
+    pub fn round(&self, value: f64) -> f64 {
+        let scale = 10_f64.powi(self.precision() as i32);
+        (value * scale).round() / scale
+    }
+
+    /// カンマ区切りの表示文字列
+    pub fn format(&self, value: f64) -> String {
+        value.to_comma_fmt(self.precision())
+    }
+
+    /// カンマなしの文字列（コピー・入力欄用）
+    pub fn format_plain(&self, value: f64) -> String {
+        format!("{:.1$}", value, self.precision())
+    }
+
+    /// 画面表示用の名称
+    pub fn label(&self) -> String {
+        match self {
+            ValueType::Integer => "整数".to_string(),
+            ValueType::Decimal(digits) => format!("小数 ({}桁)", digits),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_checks_digits_for_each_type() {
+        // 整数型は小数を拒否し、小数型は指定桁数までの値のみを受け付けることを確認する。
+        assert!(ValueType::Integer.accepts(10.0));
+        assert!(!ValueType::Integer.accepts(10.5));
+        assert!(ValueType::Decimal(1).accepts(87.5));
+        assert!(!ValueType::Decimal(1).accepts(87.55));
+        assert!(ValueType::Decimal(2).accepts(87.55));
+        assert!(!ValueType::Decimal(2).accepts(f64::NAN));
+    }
+
+    #[test]
+    fn validate_rejects_out_of_range_precision() {
+        // 小数点以下の桁数が範囲外の場合にバリデーションエラーとなることを確認する。
+        assert!(ValueType::Integer.validate().is_ok());
+        assert!(ValueType::Decimal(1).validate().is_ok());
+        assert!(ValueType::Decimal(MAX_DECIMAL_PRECISION).validate().is_ok());
+        assert!(ValueType::Decimal(0).validate().is_err());
+        assert!(
+            ValueType::Decimal(MAX_DECIMAL_PRECISION + 1)
+                .validate()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn format_uses_precision_of_type() {
+        // 表示文字列が値の種類の桁数に合わせて整形されることを確認する。
+        assert_eq!(ValueType::Integer.format(12345.0), "12,345");
+        assert_eq!(ValueType::Decimal(2).format(12345.5), "12,345.50");
+        assert_eq!(ValueType::Decimal(1).format_plain(1234.24), "1234.2");
+    }
+
+    #[test]
+    fn value_type_serializes_in_snake_case() {
+        // JSON 上の表現が整数は文字列、小数は桁数付きのオブジェクトになることを確認する。
+        assert_eq!(
+            serde_json::to_string(&ValueType::Integer).unwrap(),
+            r#""integer""#
+        );
+        assert_eq!(
+            serde_json::to_string(&ValueType::Decimal(2)).unwrap(),
+            r#"{"decimal":2}"#
+        );
+    }
+}
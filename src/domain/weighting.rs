@@ -0,0 +1,169 @@
+use serde::{Deserialize, Serialize};
+
+use super::DomainError;
+use crate::constants::{MAX_HALF_LIFE_DAYS, MAX_WINDOW_SIZE, MIN_HALF_LIFE_DAYS, MIN_WINDOW_SIZE};
+
+/// スコアの重み付け方式
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum WeightingMode {
+    /// 記録順に減衰率を掛けて減衰させる
+    #[default]
+    Exponential,
+    /// 記録順に直線的に重みを下げる（最新が 1.0、最古が 1/N）
+    LinearRamp,
+    /// 直近N件のみを等しい重みで扱う
+    SlidingWindow {
+        /// 対象にする件数
+        size: usize,
+    },
+    /// すべてのスコアを等しい重みで扱う
+    Uniform,
+    /// 記録日時からの経過日数で減衰させる（半減期を日数で指定）
+    TimeDecay {
+        /// 半減期（日）
+        half_life_days: f64,
+    },
+}
+
+/// 重み付け方式の種類（設定画面の選択肢）
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WeightingKind {
+    /// 指数減衰
+    Exponential,
+    /// 直線減衰
+    LinearRamp,
+    /// 直近N件
+    SlidingWindow,
+    /// 均等
+    Uniform,
+    /// 経過日数による半減期
+    TimeDecay,
+}
+
+impl WeightingKind {
+    /// 選択肢の表示順
+    pub const ALL: [WeightingKind; 5] = [
+        WeightingKind::Exponential,
+        WeightingKind::LinearRamp,
+        WeightingKind::SlidingWindow,
+        WeightingKind::Uniform,
+        WeightingKind::TimeDecay,
+    ];
+
+    /// 画面表示用の名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            WeightingKind::Exponential => "指数減衰",
+            WeightingKind::LinearRamp => "直線減衰",
+            WeightingKind::SlidingWindow => "直近N件",
+            WeightingKind::Uniform => "均等",
+            WeightingKind::TimeDecay => "経過日数",
+        }
+    }
+}
+
+impl WeightingMode {
+    /// 方式の種類
+    pub fn kind(&self) -> WeightingKind {
+        match self {
+            WeightingMode::Exponential => WeightingKind::Exponential,
+            WeightingMode::LinearRamp => WeightingKind::LinearRamp,
+            WeightingMode::SlidingWindow { .. } => WeightingKind::SlidingWindow,
+            WeightingMode::Uniform => WeightingKind::Uniform,
+            WeightingMode::TimeDecay { .. } => WeightingKind::TimeDecay,
+        }
+    }
+
+    /// 設定値を検証
+    pub fn validate(&self) -> Result<(), DomainError> {
+        match self {
+            WeightingMode::SlidingWindow { size } => {
+                if (MIN_WINDOW_SIZE..=MAX_WINDOW_SIZE).contains(size) {
+                    Ok(())
+                } else {
+                    Err(DomainError::Validation(format!(
+                        "件数は {} ～ {} の範囲で指定してください。",
+                        MIN_WINDOW_SIZE, MAX_WINDOW_SIZE
+                    )))
+                }
+            }
+            WeightingMode::TimeDecay { half_life_days } => {
+                if (MIN_HALF_LIFE_DAYS..=MAX_HALF_LIFE_DAYS).contains(half_life_days) {
+                    Ok(())
+                } else {
+                    Err(DomainError::Validation(format!(
+                        "半減期は {} ～ {} 日の範囲で指定してください。",
+                        MIN_HALF_LIFE_DAYS, MAX_HALF_LIFE_DAYS
+                    )))
+                }
+            }
+            WeightingMode::Exponential | WeightingMode::LinearRamp | WeightingMode::Uniform => {
+                Ok(())
+            }
+        }
+    }
+
+    /// 画面表示用の名称
+    pub fn label(&self) -> String {
+        match self {
+            WeightingMode::SlidingWindow { size } => format!("直近 {} 件", size),
+            WeightingMode::TimeDecay { half_life_days } => {
+                format!("経過日数で減衰（半減期 {} 日）", half_life_days)
+            }
+            _ => self.kind().label().to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_checks_half_life_range() {
+        // 半減期が範囲内なら成功し、範囲外や NaN ならエラーになることを確認する。
+        assert!(WeightingMode::Exponential.validate().is_ok());
+        assert!(
+            WeightingMode::TimeDecay {
+                half_life_days: 30.0
+            }
+            .validate()
+            .is_ok()
+        );
+        for days in [0.0, -1.0, MAX_HALF_LIFE_DAYS + 1.0, f64::NAN] {
+            let result = WeightingMode::TimeDecay {
+                half_life_days: days,
+            }
+            .validate();
+            assert!(matches!(result, Err(DomainError::Validation(_))));
+        }
+    }
+
+    #[test]
+    fn validate_checks_window_size_range() {
+        // 直近N件の件数が範囲外ならエラーになることを確認する。
+        assert!(WeightingMode::SlidingWindow { size: 5 }.validate().is_ok());
+        for size in [0, MAX_WINDOW_SIZE + 1] {
+            let result = WeightingMode::SlidingWindow { size }.validate();
+            assert!(matches!(result, Err(DomainError::Validation(_))));
+        }
+    }
+
+    #[test]
+    fn serde_uses_tagged_representation() {
+        // 重み付け方式が mode タグ付きの JSON で保存・復元されることを確認する。
+        let mode = WeightingMode::TimeDecay {
+            half_life_days: 14.0,
+        };
+        let json = serde_json::to_string(&mode).unwrap();
+        assert_eq!(json, r#"{"mode":"time_decay","half_life_days":14.0}"#);
+
+        let restored: WeightingMode = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, mode);
+
+        let window: WeightingMode =
+            serde_json::from_str(r#"{"mode":"sliding_window","size":3}"#).unwrap();
+        assert_eq!(window, WeightingMode::SlidingWindow { size: 3 });
+    }
+}
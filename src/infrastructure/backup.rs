@@ -0,0 +1,372 @@
+use std::cell::RefCell;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local, Utc};
+use serde::Deserialize;
+
+use crate::application::{AppError, BackupInfo, BackupKind};
+use crate::constants::{
+    BACKUP_DIR_NAME, BACKUP_EXTENSION, BACKUP_KEEP_DAILY, BACKUP_KEEP_RECENT, BACKUP_KEEP_WEEKLY,
+    QUARANTINE_SUFFIX, QUARANTINE_TIMESTAMP_FORMAT,
+};
+use crate::domain::AppData;
+
+const KINDS: [BackupKind; 3] = [BackupKind::Recent, BackupKind::Daily, BackupKind::Weekly];
+
+/// バックアップの保持件数
+///
+/// 保存場所の設定ファイルから読み込む（省略した件数は既定値、0 ならその種類は作成しない）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BackupPolicy {
+    /// 直近の保存ごとのバックアップ件数
+    pub keep_recent: usize,
+    /// 日次バックアップの件数
+    pub keep_daily: usize,
+    /// 週次バックアップの件数
+    pub keep_weekly: usize,
+}
+
+impl Default for BackupPolicy {
+    fn default() -> Self {
+        Self {
+            keep_recent: BACKUP_KEEP_RECENT,
+            keep_daily: BACKUP_KEEP_DAILY,
+            keep_weekly: BACKUP_KEEP_WEEKLY,
+        }
+    }
+}
+
+impl BackupPolicy {
+    fn keep(&self, kind: BackupKind) -> usize {
+        match kind {
+            BackupKind::Recent => self.keep_recent,
+            BackupKind::Daily => self.keep_daily,
+            BackupKind::Weekly => self.keep_weekly,
+        }
+    }
+}
+
+/// ファイル名に使う種類のタグ
+fn kind_tag(kind: BackupKind) -> &'static str {
+    match kind {
+        BackupKind::Recent => "recent",
+        BackupKind::Daily => "daily",
+        BackupKind::Weekly => "weekly",
+    }
+}
+
+/// ファイル名に使う日時（辞書順がそのまま時系列順になる形式）
+fn kind_stamp(kind: BackupKind, now: DateTime<Local>) -> String {
+    let format = match kind {
+        BackupKind::Recent => "%Y%m%d-%H%M%S%.3f",
+        BackupKind::Daily => "%Y%m%d",
+        BackupKind::Weekly => "%G-W%V",
+    };
+    now.format(format).to_string()
+}
+
+/// 読み込めなかったデータファイルを日時付きの名前へ退避し、退避先を返す
+pub fn quarantine_file(path: &Path) -> Result<PathBuf, AppError> {
+    let stamp = Local::now().format(QUARANTINE_TIMESTAMP_FORMAT);
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}-{}", QUARANTINE_SUFFIX, stamp));
+    let target = path.with_file_name(name);
+
+    fs::rename(path, &target).map_err(|e| {
+        AppError::Persistence(format!(
+            "読み込めなかったファイルの退避に失敗しました ({}): {}",
+            path.display(),
+            e
+        ))
+    })?;
+
+    Ok(target)
+}
+
+/// データファイルの世代バックアップ
+///
+/// バックアップフォルダに "<ファイル名>.<種類>-<日時>.bak" の形式で保存する。
+/// 日次・週次はその期間の最初の保存内容を残し、同じ期間内の以降の保存では作成しない。
+pub struct BackupRotation {
+    dir: PathBuf,
+    prefix: String,
+    policy: BackupPolicy,
+    // 保存後のバックアップで最後に起きたエラー（保存自体は成功している）
+    failure: RefCell<Option<String>>,
+}
+
+impl BackupRotation {
+    pub fn new(data_path: &Path, policy: BackupPolicy) -> Self {
+        let dir = data_path
+            .parent()
+            .unwrap_or_else(|| Path::new(""))
+            .join(BACKUP_DIR_NAME);
+        let file_name = data_path.file_name().unwrap_or_default().to_string_lossy();
+
+        Self {
+            dir,
+            prefix: format!("{}.", file_name),
+            policy,
+            failure: RefCell::new(None),
+        }
+    }
+
+    /// `snapshot` を行い、失敗した場合はエラーを返さずに記録する（保存は続けるため）
+    pub fn try_snapshot(&self, source: &Path) {
+        if let Err(err) = self.snapshot(source) {
+            self.failure.replace(Some(format!(
+                "バックアップを作成できませんでした（データの保存は続けます）。\n{}",
+                err
+            )));
+        }
+    }
+
+    /// 最後に失敗したバックアップのエラーを取り出す
+    pub fn take_failure(&self) -> Option<String> {
+        self.failure.take()
+    }
+
+    /// 保存直後のデータファイルをバックアップし、保持件数を超えた古いものを削除する
+    pub fn snapshot(&self, source: &Path) -> Result<(), AppError> {
+        fs::create_dir_all(&self.dir).map_err(|e| {
+            AppError::Persistence(format!("バックアップフォルダを作成できません: {}", e))
+        })?;
+
+        let now = Local::now();
+        for kind in KINDS {
+            if self.policy.keep(kind) == 0 {
+                continue;
+            }
+            let target = self.dir.join(format!(
+                "{}{}-{}.{}",
+                self.prefix,
+                kind_tag(kind),
+                kind_stamp(kind, now),
+                BACKUP_EXTENSION
+            ));
+            // 日次・週次は期間内に1回だけ複製する
+            if kind != BackupKind::Recent && target.exists() {
+                continue;
+            }
+            fs::copy(source, target).map_err(|e| {
+                AppError::Persistence(format!("バックアップの作成に失敗しました: {}", e))
+            })?;
+        }
+
+        KINDS.iter().try_for_each(|kind| self.prune(*kind))
+    }
+
+    /// バックアップの一覧（種類, パス, 保存日時）を新しい順に返す
+    pub fn list(&self) -> Result<Vec<(BackupKind, PathBuf, DateTime<Utc>)>, AppError> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut backups = Vec::new();
+        for (kind, name) in self.entries()? {
+            let path = self.dir.join(&name);
+            let modified = fs::metadata(&path)
+                .and_then(|meta| meta.modified())
+                .map_err(|e| {
+                    AppError::Persistence(format!("バックアップの情報を取得できません: {}", e))
+                })?;
+            backups.push((kind, path, DateTime::<Utc>::from(modified)));
+        }
+
+        backups.sort_by_key(|backup| std::cmp::Reverse(backup.2));
+        Ok(backups)
+    }
+
+    /// 一覧表示用のバックアップ情報（件数は read で読み込んで求める）
+    pub fn describe<F>(&self, read: F) -> Result<Vec<BackupInfo>, AppError>
+    where
+        F: Fn(&Path) -> Result<AppData, AppError>,
+    {
+        Ok(self
+            .list()?
+            .into_iter()
+            .map(|(kind, path, saved_at)| {
+                // 読み込めないバックアップも一覧には表示する
+                let counts = read(&path).ok().map(|data| {
+                    let items = data.categories.values().map(|c| c.items.len()).sum();
+                    (data.categories.len(), items)
+                });
+                BackupInfo {
+                    id: path
+                        .file_name()
+                        .unwrap_or_default()
+                        .to_string_lossy()
+                        .to_string(),
+                    kind,
+                    saved_at,
+                    counts,
+                }
+            })
+            .collect())
+    }
+
+    /// 識別子（ファイル名）からバックアップのパスを求める（フォルダ外を指す名前は拒否）
+    pub fn resolve(&self, id: &str) -> Result<PathBuf, AppError> {
+        let is_plain_name = Path::new(id).file_name().is_some_and(|name| name == id);
+        if !is_plain_name || Self::parse_kind(&self.prefix, id).is_none() {
+            return Err(AppError::Persistence(format!(
+                "バックアップが見つかりません: {}",
+                id
+            )));
+        }
+
+        let path = self.dir.join(id);
+        if !path.exists() {
+            return Err(AppError::Persistence(format!(
+                "バックアップが見つかりません: {}",
+                id
+            )));
+        }
+        Ok(path)
+    }
+
+    /// フォルダ内のバックアップファイル名（このデータファイルのもののみ）
+    fn entries(&self) -> Result<Vec<(BackupKind, String)>, AppError> {
+        let read_dir = fs::read_dir(&self.dir).map_err(|e| {
+            AppError::Persistence(format!("バックアップフォルダを読み込めません: {}", e))
+        })?;
+
+        Ok(read_dir
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .filter_map(|name| Some((Self::parse_kind(&self.prefix, &name)?, name)))
+            .collect())
+    }
+
+    fn parse_kind(prefix: &str, name: &str) -> Option<BackupKind> {
+        let rest = name
+            .strip_prefix(prefix)?
+            .strip_suffix(&format!(".{}", BACKUP_EXTENSION))?;
+        KINDS
+            .into_iter()
+            .find(|kind| rest.starts_with(&format!("{}-", kind_tag(*kind))))
+    }
+
+    /// 保持件数を超えた古いバックアップを削除
+    fn prune(&self, kind: BackupKind) -> Result<(), AppError> {
+        let mut names: Vec<String> = self
+            .entries()?
+            .into_iter()
+            .filter(|(k, _)| *k == kind)
+            .map(|(_, name)| name)
+            .collect();
+        names.sort_by(|a, b| b.cmp(a));
+
+        names
+            .iter()
+            .skip(self.policy.keep(kind))
+            .try_for_each(|name| {
+                fs::remove_file(self.dir.join(name)).map_err(|e| {
+                    AppError::Persistence(format!("古いバックアップの削除に失敗しました: {}", e))
+                })
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn unique_dir(name: &str) -> PathBuf {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time went backwards")
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("{name}-{unique}"));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn count_of(rotation: &BackupRotation, kind: BackupKind) -> usize {
+        rotation
+            .list()
+            .unwrap()
+            .iter()
+            .filter(|(k, _, _)| *k == kind)
+            .count()
+    }
+
+    #[test]
+    fn snapshot_keeps_recent_up_to_limit_and_one_per_period() {
+        // 直近の保存は上限件数まで残り、日次・週次は同じ期間内の最初の保存の1件だけになることを確認する。
+        let dir = unique_dir("weighted-score-backup-rotation");
+        let data_path = dir.join("data.json");
+        let policy = BackupPolicy {
+            keep_recent: 2,
+            keep_daily: 3,
+            keep_weekly: 3,
+        };
+        let rotation = BackupRotation::new(&data_path, policy);
+
+        for i in 0..4 {
+            fs::write(&data_path, format!("{{\"v\":{}}}", i)).unwrap();
+            rotation.snapshot(&data_path).unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+
+        assert_eq!(count_of(&rotation, BackupKind::Recent), 2);
+        assert_eq!(count_of(&rotation, BackupKind::Daily), 1);
+        assert_eq!(count_of(&rotation, BackupKind::Weekly), 1);
+
+        // 最新のバックアップは最後に保存した内容
+        let (_, latest, _) = rotation
+            .list()
+            .unwrap()
+            .into_iter()
+            .find(|(k, _, _)| *k == BackupKind::Recent)
+            .unwrap();
+        assert_eq!(fs::read_to_string(latest).unwrap(), "{\"v\":3}");
+        let (_, daily, _) = rotation
+            .list()
+            .unwrap()
+            .into_iter()
+            .find(|(k, _, _)| *k == BackupKind::Daily)
+            .unwrap();
+        assert_eq!(fs::read_to_string(daily).unwrap(), "{\"v\":0}");
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn list_ignores_backups_of_other_files() {
+        // 同じフォルダにある別のデータファイルのバックアップは一覧に含まれないことを確認する。
+        let dir = unique_dir("weighted-score-backup-other");
+        let data_path = dir.join("data.json");
+        let other_path = dir.join("other.json");
+        fs::write(&data_path, "{}").unwrap();
+        fs::write(&other_path, "{}").unwrap();
+
+        BackupRotation::new(&other_path, BackupPolicy::default())
+            .snapshot(&other_path)
+            .unwrap();
+        let rotation = BackupRotation::new(&data_path, BackupPolicy::default());
+        assert!(rotation.list().unwrap().is_empty());
+
+        rotation.snapshot(&data_path).unwrap();
+        assert_eq!(rotation.list().unwrap().len(), 3);
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn resolve_rejects_names_outside_backup_dir() {
+        // 識別子にパス区切りや無関係なファイル名を指定した場合は拒否されることを確認する。
+        let dir = unique_dir("weighted-score-backup-resolve");
+        let data_path = dir.join("data.json");
+        let rotation = BackupRotation::new(&data_path, BackupPolicy::default());
+
+        assert!(rotation.resolve("../data.json").is_err());
+        assert!(rotation.resolve("data.json").is_err());
+        assert!(rotation.resolve("data.json.recent-20240101.bak").is_err());
+
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,27 @@
+use std::fs;
+use std::path::Path;
+
+use crate::application::{AppError, CsvTable};
+
+/// CSV ファイルを読み込む
+pub fn read_csv(path: &Path) -> Result<CsvTable, AppError> {
+    let text = fs::read_to_string(path).map_err(|e| {
+        AppError::Persistence(format!(
+            "CSVファイルを開けません ({}): {}",
+            path.display(),
+            e
+        ))
+    })?;
+    CsvTable::parse(&text)
+}
+
+/// CSV ファイルを書き出す（既存のファイルは上書き）
+pub fn write_csv(path: &Path, text: &str) -> Result<(), AppError> {
+    fs::write(path, text).map_err(|e| {
+        AppError::Persistence(format!(
+            "CSVファイルを書き出せません ({}): {}",
+            path.display(),
+            e
+        ))
+    })
+}
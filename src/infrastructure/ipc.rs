@@ -0,0 +1,308 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::application::AppError;
+use crate::constants::{IPC_CONNECT_TIMEOUT_SECS, IPC_MAX_MESSAGE_BYTES, IPC_REPLY_TIMEOUT_SECS};
+use crate::domain::{ScoreEntry, ValueType};
+
+use super::lock::{DataLock, LockOwner};
+
+/// 起動中の画面に送る操作
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum IpcRequest {
+    /// 名前で指定した項目にスコアを記録する（入力は画面・コマンドラインと同じ形式）
+    AddScore {
+        /// カテゴリ名
+        category: String,
+        /// 項目名
+        item: String,
+        /// スコアの入力
+        score: String,
+        /// 記録日時の入力（空なら現在時刻）
+        timestamp: String,
+        /// メモ
+        note: String,
+        /// タグの入力（カンマ区切り）
+        tags: String,
+    },
+}
+
+/// 操作の結果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum IpcReply {
+    /// スコアを記録した
+    Added {
+        /// 記録したスコア
+        entry: ScoreEntry,
+        /// 項目の値の種類（表示用）
+        value_type: ValueType,
+    },
+    /// 反映できなかった
+    Failed(AppError),
+}
+
+/// 1件の接続で送るメッセージ（ロックファイルの識別子で送り主を確かめる）
+#[derive(Serialize, Deserialize)]
+struct Envelope {
+    token: String,
+    request: IpcRequest,
+}
+
+/// 受け付けた操作（`reply` で送り主に結果を返す）
+#[derive(Debug, Clone)]
+pub struct IpcCommand {
+    /// 操作の内容
+    pub request: IpcRequest,
+    reply: Sender<IpcReply>,
+}
+
+impl IpcCommand {
+    /// 送り主に結果を返す（送り主が待ちきれずに切断していれば何もしない）
+    pub fn reply(self, reply: IpcReply) {
+        let _ = self.reply.send(reply);
+    }
+}
+
+/// ほかのプロセスからの操作の受付（破棄すると待ち受けをやめる）
+///
+/// 127.0.0.1 の空きポートで待ち受け、ポートはロックファイルに書き込んで知らせる。
+/// 1行の JSON で操作を受け取り、画面が反映した結果を1行の JSON で返す。
+pub struct IpcListener {
+    port: u16,
+    stop: Arc<AtomicBool>,
+    commands: Receiver<IpcCommand>,
+}
+
+impl IpcListener {
+    /// 待ち受けを始める（操作を受け付けるたびに `notify` を呼ぶ）
+    pub fn start<F>(lock: &mut DataLock, notify: F) -> Result<Self, AppError>
+    where
+        F: Fn() + Send + 'static,
+    {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).map_err(ipc_error)?;
+        let port = listener.local_addr().map_err(ipc_error)?.port();
+        lock.advertise_ipc(port)?;
+
+        let token = lock.owner().token().to_string();
+        let stop = Arc::new(AtomicBool::new(false));
+        let (sender, commands) = mpsc::channel();
+        let stopped = stop.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                if stopped.load(Ordering::SeqCst) {
+                    break;
+                }
+                // 1件ずつ処理する（途中で切れた接続は捨てる）
+                if let Ok(stream) = stream {
+                    serve_connection(&stream, &token, &sender, &notify);
+                }
+            }
+        });
+
+        Ok(Self {
+            port,
+            stop,
+            commands,
+        })
+    }
+
+    /// 受け付けた操作を1件取り出す
+    pub fn try_recv(&self) -> Option<IpcCommand> {
+        self.commands.try_recv().ok()
+    }
+}
+
+impl Drop for IpcListener {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        // 接続を待っているスレッドを起こして終了させる
+        let _ = TcpStream::connect((Ipv4Addr::LOCALHOST, self.port));
+    }
+}
+
+/// 起動中の画面に操作を送り、結果を待つ
+pub fn send_request(owner: &LockOwner, request: IpcRequest) -> Result<IpcReply, AppError> {
+    let port = owner.ipc_port.ok_or_else(|| {
+        AppError::Persistence("使用中のウィンドウは操作の転送を受け付けていません。".to_string())
+    })?;
+    let address = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    let stream =
+        TcpStream::connect_timeout(&address, Duration::from_secs(IPC_CONNECT_TIMEOUT_SECS))
+            .map_err(ipc_error)?;
+    // 画面の応答を待つ時間より少し長く待つ
+    let timeout = Duration::from_secs(IPC_REPLY_TIMEOUT_SECS + IPC_CONNECT_TIMEOUT_SECS);
+    stream.set_read_timeout(Some(timeout)).map_err(ipc_error)?;
+
+    let envelope = Envelope {
+        token: owner.token().to_string(),
+        request,
+    };
+    write_message(&stream, &envelope)?;
+    read_message(&stream)
+}
+
+fn serve_connection<F: Fn()>(
+    stream: &TcpStream,
+    token: &str,
+    commands: &Sender<IpcCommand>,
+    notify: &F,
+) {
+    let timeout = Duration::from_secs(IPC_REPLY_TIMEOUT_SECS);
+    let reply = match stream
+        .set_read_timeout(Some(timeout))
+        .map_err(ipc_error)
+        .and_then(|()| read_message::<Envelope>(stream))
+    {
+        Ok(envelope) if envelope.token == token => forward(envelope.request, commands, notify),
+        Ok(_) => IpcReply::Failed(AppError::Input(
+            "ロックファイルと一致しない接続は受け付けません。".to_string(),
+        )),
+        Err(err) => IpcReply::Failed(err),
+    };
+    // 送り主が切断していれば何もしない
+    let _ = write_message(stream, &reply);
+}
+
+/// 画面に操作を渡し、反映した結果を待つ
+fn forward<F: Fn()>(request: IpcRequest, commands: &Sender<IpcCommand>, notify: &F) -> IpcReply {
+    let (reply, result) = mpsc::channel();
+    if commands.send(IpcCommand { request, reply }).is_err() {
+        return IpcReply::Failed(AppError::Persistence(
+            "ウィンドウが操作を受け付けていません。".to_string(),
+        ));
+    }
+    notify();
+    result
+        .recv_timeout(Duration::from_secs(IPC_REPLY_TIMEOUT_SECS))
+        .unwrap_or_else(|_| {
+            IpcReply::Failed(AppError::Persistence(
+                "ウィンドウから応答がありません（後で反映される場合があります）。".to_string(),
+            ))
+        })
+}
+
+fn ipc_error(e: std::io::Error) -> AppError {
+    AppError::Persistence(format!("起動中のウィンドウと通信できません: {}", e))
+}
+
+fn write_message<T: Serialize>(mut stream: &TcpStream, message: &T) -> Result<(), AppError> {
+    let mut line = serde_json::to_vec(message)
+        .map_err(|e| AppError::Persistence(format!("送信内容を作成できません: {}", e)))?;
+    line.push(b'\n');
+    stream.write_all(&line).map_err(ipc_error)
+}
+
+fn read_message<T: DeserializeOwned>(stream: &TcpStream) -> Result<T, AppError> {
+    let mut line = String::new();
+    BufReader::new(stream.take(IPC_MAX_MESSAGE_BYTES as u64))
+        .read_line(&mut line)
+        .map_err(ipc_error)?;
+    serde_json::from_str(&line)
+        .map_err(|e| AppError::Input(format!("受信内容を解釈できません: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::LockState;
+    use chrono::Utc;
+    use std::path::{Path, PathBuf};
+    use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+    fn unique_path(name: &str) -> PathBuf {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time went backwards")
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("{name}-{unique}"));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.join("data.json")
+    }
+
+    fn acquire(path: &Path) -> DataLock {
+        let LockState::Acquired(lock) = DataLock::acquire(path).unwrap() else {
+            panic!("lock should be acquired");
+        };
+        lock
+    }
+
+    fn add_request() -> IpcRequest {
+        IpcRequest::AddScore {
+            category: "Cat".to_string(),
+            item: "Item".to_string(),
+            score: "10".to_string(),
+            timestamp: String::new(),
+            note: String::new(),
+            tags: String::new(),
+        }
+    }
+
+    /// 受け付けた操作を待って取り出す
+    fn receive(listener: &IpcListener) -> IpcCommand {
+        let started = Instant::now();
+        loop {
+            if let Some(command) = listener.try_recv() {
+                return command;
+            }
+            assert!(started.elapsed() < Duration::from_secs(5), "no command");
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn request_is_forwarded_and_answered() {
+        // ロックファイルに書いたポートへ送った操作が受付側に届き、返した結果が送り主に戻ることを確認する。
+        let path = unique_path("ipc-forward");
+        let mut lock = acquire(&path);
+        let listener = IpcListener::start(&mut lock, || {}).unwrap();
+        let owner = lock.owner().clone();
+        assert!(owner.ipc_port.is_some());
+
+        let client = thread::spawn(move || send_request(&owner, add_request()).unwrap());
+        let command = receive(&listener);
+        assert_eq!(command.request, add_request());
+        let entry = ScoreEntry {
+            score: 10.0,
+            timestamp: Utc::now(),
+            note: None,
+            tags: Vec::new(),
+        };
+        let added = IpcReply::Added {
+            entry,
+            value_type: ValueType::default(),
+        };
+        command.reply(added.clone());
+
+        assert_eq!(client.join().unwrap(), added);
+        drop(listener);
+        drop(lock);
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn request_with_wrong_token_is_rejected() {
+        // ロックファイルの識別子と一致しない送り主の操作は受付側に渡さずに拒否することを確認する。
+        let path = unique_path("ipc-token");
+        let mut lock = acquire(&path);
+        let listener = IpcListener::start(&mut lock, || {}).unwrap();
+
+        let mut forged = serde_json::to_value(lock.owner()).unwrap();
+        forged["token"] = "forged".into();
+        let forged: LockOwner = serde_json::from_value(forged).unwrap();
+
+        let reply = send_request(&forged, add_request()).unwrap();
+        assert!(matches!(reply, IpcReply::Failed(AppError::Input(_))));
+        assert!(listener.try_recv().is_none());
+        drop(listener);
+        drop(lock);
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+}
@@ -0,0 +1,499 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::application::AppError;
+use crate::domain::{
+    AppData, CategoryData, CategoryId, ItemData, ItemId, ScoreDirection, ScoreEntry, ValueRules,
+    ValueType, WeightingMode,
+};
+
+use super::migration::{
+    CURRENT_SCHEMA_VERSION, SCHEMA_VERSION_KEY, migrate, migrate_item, stamp_version,
+};
+
+/// 項目のスコア以外の内容（名前・設定・更新日時）
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ItemFields {
+    pub name: String,
+    pub decay_rate: f64,
+    pub value_type: ValueType,
+    pub rules: ValueRules,
+    pub direction: ScoreDirection,
+    pub weighting: WeightingMode,
+    pub importance: f64,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ItemFields {
+    fn of(item: &ItemData) -> Self {
+        Self {
+            name: item.name.clone(),
+            decay_rate: item.decay_rate,
+            value_type: item.value_type,
+            rules: item.rules,
+            direction: item.direction,
+            weighting: item.weighting,
+            importance: item.importance,
+            updated_at: item.updated_at,
+        }
+    }
+
+    fn apply_to(self, item: &mut ItemData) {
+        item.name = self.name;
+        item.decay_rate = self.decay_rate;
+        item.value_type = self.value_type;
+        item.rules = self.rules;
+        item.direction = self.direction;
+        item.weighting = self.weighting;
+        item.importance = self.importance;
+        item.updated_at = self.updated_at;
+    }
+}
+
+/// ジャーナルに記録するデータの変更
+///
+/// スコアの位置（index）は変更を適用する時点での位置。
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum JournalEvent {
+    /// データ全体（圧縮時にファイルの先頭に書き、それ以前の変更を置き換える）
+    Snapshot {
+        data: Value,
+    },
+    CategoryAdded {
+        id: CategoryId,
+        name: String,
+        created_at: DateTime<Utc>,
+    },
+    CategoryRenamed {
+        id: CategoryId,
+        name: String,
+    },
+    CategoryRemoved {
+        id: CategoryId,
+    },
+    ItemAdded {
+        category: CategoryId,
+        id: ItemId,
+        item: ItemData,
+    },
+    ItemMoved {
+        id: ItemId,
+        category: CategoryId,
+    },
+    ItemUpdated {
+        id: ItemId,
+        fields: ItemFields,
+    },
+    ItemRemoved {
+        id: ItemId,
+    },
+    ScoreAdded {
+        item: ItemId,
+        index: usize,
+        entry: ScoreEntry,
+    },
+    ScoreUpdated {
+        item: ItemId,
+        index: usize,
+        entry: ScoreEntry,
+    },
+    ScoreRemoved {
+        item: ItemId,
+        index: usize,
+    },
+}
+
+/// ジャーナルの1行（変更日時＋記録時のスキーマバージョン＋変更内容）
+#[derive(Serialize, Deserialize, Debug)]
+pub struct JournalRecord {
+    pub at: DateTime<Utc>,
+    /// 記録時のスキーマバージョン（記載のない行はバージョン 0 として扱う）
+    #[serde(default)]
+    pub schema_version: u64,
+    #[serde(flatten)]
+    pub event: JournalEvent,
+}
+
+impl JournalRecord {
+    /// 現在のスキーマバージョンで記録する
+    pub fn new(event: JournalEvent) -> Self {
+        Self {
+            at: Utc::now(),
+            schema_version: CURRENT_SCHEMA_VERSION,
+            event,
+        }
+    }
+
+    /// 1行分の JSON から復元する（古いバージョンで記録した項目の内容は現在の形式へ変換する）
+    pub fn from_value(mut value: Value) -> Result<Self, AppError> {
+        let version = value.get(SCHEMA_VERSION_KEY).cloned();
+        let payload = match value.get("type").and_then(Value::as_str) {
+            Some("item_added") => Some("item"),
+            Some("item_updated") => Some("fields"),
+            _ => None,
+        };
+        if let Some(item) = payload.and_then(|key| value.get_mut(key)) {
+            migrate_item(item, version)?;
+        }
+        serde_json::from_value(value)
+            .map_err(|e| AppError::Persistence(format!("ジャーナルの内容を読み込めません: {}", e)))
+    }
+}
+
+fn inconsistent(detail: String) -> AppError {
+    AppError::Persistence(format!("ジャーナルの内容が不整合です: {}", detail))
+}
+
+impl JournalEvent {
+    /// データ全体を現在のスキーマバージョン付きで記録する
+    pub fn snapshot(data: &AppData) -> Result<Self, AppError> {
+        let mut value = serde_json::to_value(data)
+            .map_err(|e| AppError::Persistence(format!("JSON保存に失敗しました: {}", e)))?;
+        stamp_version(&mut value);
+        Ok(Self::Snapshot { data: value })
+    }
+
+    /// 変更をデータに適用する（対象が見つからない場合はエラー）
+    pub fn apply(self, data: &mut AppData) -> Result<(), AppError> {
+        match self {
+            Self::Snapshot { data: mut value } => {
+                // 古いアプリで圧縮したスナップショットも現在の形式へ変換する
+                migrate(&mut value)?;
+                *data = serde_json::from_value(value)
+                    .map_err(|e| AppError::Persistence(format!("JSON読込に失敗しました: {}", e)))?;
+            }
+            Self::CategoryAdded {
+                id,
+                name,
+                created_at,
+            } => {
+                data.categories.insert(
+                    id,
+                    CategoryData {
+                        name,
+                        items: HashMap::new(),
+                        created_at,
+                    },
+                );
+            }
+            Self::CategoryRenamed { id, name } => category_mut(data, id)?.name = name,
+            Self::CategoryRemoved { id } => {
+                data.categories
+                    .remove(&id)
+                    .ok_or_else(|| inconsistent(format!("カテゴリがありません ({})", id)))?;
+            }
+            Self::ItemAdded { category, id, item } => {
+                category_mut(data, category)?.items.insert(id, item);
+            }
+            Self::ItemMoved { id, category } => {
+                category_mut(data, category)?;
+                let item = take_item(data, id)?;
+                category_mut(data, category)?.items.insert(id, item);
+            }
+            Self::ItemUpdated { id, fields } => fields.apply_to(item_mut(data, id)?),
+            Self::ItemRemoved { id } => {
+                take_item(data, id)?;
+            }
+            Self::ScoreAdded { item, index, entry } => {
+                let scores = &mut item_mut(data, item)?.scores;
+                if index > scores.len() {
+                    return Err(inconsistent(format!("スコアの位置が範囲外です ({})", item)));
+                }
+                scores.insert(index, entry);
+            }
+            Self::ScoreUpdated { item, index, entry } => {
+                let slot = item_mut(data, item)?
+                    .scores
+                    .get_mut(index)
+                    .ok_or_else(|| inconsistent(format!("スコアの位置が範囲外です ({})", item)))?;
+                *slot = entry;
+            }
+            Self::ScoreRemoved { item, index } => {
+                let scores = &mut item_mut(data, item)?.scores;
+                if index >= scores.len() {
+                    return Err(inconsistent(format!("スコアの位置が範囲外です ({})", item)));
+                }
+                scores.remove(index);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn category_mut(data: &mut AppData, id: CategoryId) -> Result<&mut CategoryData, AppError> {
+    data.categories
+        .get_mut(&id)
+        .ok_or_else(|| inconsistent(format!("カテゴリがありません ({})", id)))
+}
+
+fn item_mut(data: &mut AppData, id: ItemId) -> Result<&mut ItemData, AppError> {
+    data.categories
+        .values_mut()
+        .find_map(|category| category.items.get_mut(&id))
+        .ok_or_else(|| inconsistent(format!("項目がありません ({})", id)))
+}
+
+fn take_item(data: &mut AppData, id: ItemId) -> Result<ItemData, AppError> {
+    data.categories
+        .values_mut()
+        .find_map(|category| category.items.remove(&id))
+        .ok_or_else(|| inconsistent(format!("項目がありません ({})", id)))
+}
+
+/// 項目ID -> (所属カテゴリID, 項目)
+fn items_by_id(data: &AppData) -> HashMap<ItemId, (CategoryId, &ItemData)> {
+    data.categories
+        .iter()
+        .flat_map(|(cat, category)| category.items.iter().map(|(id, item)| (*id, (*cat, item))))
+        .collect()
+}
+
+/// previous から current への変更を、適用順に並べた変更の一覧として求める
+pub fn diff(previous: &AppData, current: &AppData) -> Vec<JournalEvent> {
+    let mut events = Vec::new();
+
+    for (id, category) in &current.categories {
+        match previous.categories.get(id) {
+            None => events.push(JournalEvent::CategoryAdded {
+                id: *id,
+                name: category.name.clone(),
+                created_at: category.created_at,
+            }),
+            Some(old) if old.name != category.name => events.push(JournalEvent::CategoryRenamed {
+                id: *id,
+                name: category.name.clone(),
+            }),
+            Some(_) => {}
+        }
+    }
+
+    let previous_items = items_by_id(previous);
+    let current_items = items_by_id(current);
+    for (id, (cat, item)) in &current_items {
+        let Some((old_cat, old_item)) = previous_items.get(id) else {
+            events.push(JournalEvent::ItemAdded {
+                category: *cat,
+                id: *id,
+                item: (*item).clone(),
+            });
+            continue;
+        };
+
+        if old_cat != cat {
+            events.push(JournalEvent::ItemMoved {
+                id: *id,
+                category: *cat,
+            });
+        }
+        let fields = ItemFields::of(item);
+        if ItemFields::of(old_item) != fields {
+            events.push(JournalEvent::ItemUpdated { id: *id, fields });
+        }
+        push_score_changes(&mut events, *id, &old_item.scores, &item.scores);
+    }
+
+    // 削除したカテゴリに属していた項目はカテゴリの削除で一緒に消える
+    for (id, (cat, _)) in &previous_items {
+        if !current_items.contains_key(id) && current.categories.contains_key(cat) {
+            events.push(JournalEvent::ItemRemoved { id: *id });
+        }
+    }
+    for id in previous.categories.keys() {
+        if !current.categories.contains_key(id) {
+            events.push(JournalEvent::CategoryRemoved { id: *id });
+        }
+    }
+
+    events
+}
+
+/// 先頭と末尾の一致部分を除いた範囲を、更新（件数が同じ場合）または削除＋追加として記録する
+fn push_score_changes(
+    events: &mut Vec<JournalEvent>,
+    item: ItemId,
+    previous: &[ScoreEntry],
+    current: &[ScoreEntry],
+) {
+    let prefix = previous
+        .iter()
+        .zip(current)
+        .take_while(|(old, new)| old == new)
+        .count();
+    let suffix = previous[prefix..]
+        .iter()
+        .rev()
+        .zip(current[prefix..].iter().rev())
+        .take_while(|(old, new)| old == new)
+        .count();
+    let removed = &previous[prefix..previous.len() - suffix];
+    let added = &current[prefix..current.len() - suffix];
+
+    if removed.len() == added.len() {
+        events.extend(
+            added
+                .iter()
+                .enumerate()
+                .map(|(i, entry)| JournalEvent::ScoreUpdated {
+                    item,
+                    index: prefix + i,
+                    entry: entry.clone(),
+                }),
+        );
+        return;
+    }
+
+    events.extend(removed.iter().map(|_| JournalEvent::ScoreRemoved {
+        item,
+        index: prefix,
+    }));
+    events.extend(
+        added
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| JournalEvent::ScoreAdded {
+                item,
+                index: prefix + i,
+                entry: entry.clone(),
+            }),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn seed() -> (AppData, CategoryId, ItemId) {
+        let mut data = AppData::default();
+        let cat = data.add_category("Training".to_string()).unwrap();
+        let item = data
+            .add_item(cat, "Sprint".to_string(), 0.8, ValueType::Integer)
+            .unwrap();
+        let now = Utc::now();
+        for (i, score) in [10.0, 12.0, 11.0].into_iter().enumerate() {
+            data.add_score(cat, item, score, now - Duration::days(3 - i as i64))
+                .unwrap();
+        }
+        (data, cat, item)
+    }
+
+    /// 変更を適用した結果が変更後のデータと一致することを確かめ、変更の一覧を返す
+    fn replay(previous: &AppData, current: &AppData) -> Vec<JournalEvent> {
+        let events = diff(previous, current);
+        let mut replayed = previous.clone();
+        for event in events.clone() {
+            event.apply(&mut replayed).unwrap();
+        }
+        assert_eq!(&replayed, current);
+        events
+    }
+
+    #[test]
+    fn diff_records_score_changes_at_their_positions() {
+        // 過去日時の記録追加・メモ編集・削除が、それぞれ位置付きの1件の変更として記録されることを確認する。
+        let (data, cat, item) = seed();
+
+        let mut backdated = data.clone();
+        backdated
+            .add_score(cat, item, 9.0, Utc::now() - Duration::days(30))
+            .unwrap();
+        let events = replay(&data, &backdated);
+        assert!(events.iter().any(|event| matches!(
+            event,
+            JournalEvent::ScoreAdded { index: 0, entry, .. } if entry.score == 9.0
+        )));
+        assert!(
+            events
+                .iter()
+                .all(|event| !matches!(event, JournalEvent::ScoreRemoved { .. }))
+        );
+
+        let mut noted = data.clone();
+        noted
+            .update_score_note(cat, item, 1, "fast".to_string(), Vec::new())
+            .unwrap();
+        let events = replay(&data, &noted);
+        assert!(
+            events
+                .iter()
+                .any(|event| matches!(event, JournalEvent::ScoreUpdated { index: 1, .. }))
+        );
+
+        let mut removed = data.clone();
+        removed.remove_score(cat, item, 1).unwrap();
+        let events = replay(&data, &removed);
+        assert!(
+            events
+                .iter()
+                .any(|event| matches!(event, JournalEvent::ScoreRemoved { index: 1, .. }))
+        );
+    }
+
+    #[test]
+    fn diff_replays_category_and_item_changes() {
+        // カテゴリの追加・名前変更・削除と項目の移動・設定変更・削除を適用すると変更後のデータになることを確認する。
+        let (data, cat, item) = seed();
+
+        let mut changed = data.clone();
+        let other = changed.add_category("Running".to_string()).unwrap();
+        changed.move_item(cat, other, item).unwrap();
+        changed
+            .rename_item(other, item, "Dash".to_string())
+            .unwrap();
+        changed.remove_category(cat).unwrap();
+        changed.rename_category(other, "Track".to_string()).unwrap();
+        let events = replay(&data, &changed);
+        assert!(
+            events
+                .iter()
+                .any(|event| matches!(event, JournalEvent::ItemMoved { .. }))
+        );
+
+        let mut removed = changed.clone();
+        removed.remove_item(other, item).unwrap();
+        replay(&changed, &removed);
+        assert!(diff(&removed, &removed).is_empty());
+    }
+
+    #[test]
+    fn record_is_written_as_single_tagged_json_line() {
+        // 変更が種類名付きの1行の JSON として保存・復元できることを確認する。
+        let (data, _, item) = seed();
+        let record = JournalRecord::new(JournalEvent::ScoreRemoved { item, index: 2 });
+
+        let line = serde_json::to_string(&record).unwrap();
+        assert!(!line.contains('\n'));
+        assert!(line.contains("\"type\":\"score_removed\""));
+        let restored: JournalRecord = serde_json::from_str(&line).unwrap();
+        assert_eq!(restored.event, record.event);
+
+        let snapshot = JournalEvent::snapshot(&data).unwrap();
+        let line = serde_json::to_string(&JournalRecord::new(snapshot)).unwrap();
+        let restored: JournalRecord = serde_json::from_str(&line).unwrap();
+        let mut replayed = AppData::default();
+        restored.event.apply(&mut replayed).unwrap();
+        assert_eq!(replayed, data);
+    }
+
+    #[test]
+    fn apply_rejects_changes_to_missing_targets() {
+        // 存在しない項目や範囲外の位置への変更は不整合エラーになることを確認する。
+        let (mut data, _, item) = seed();
+
+        let err = JournalEvent::ItemRemoved {
+            id: ItemId::generate(),
+        }
+        .apply(&mut data)
+        .unwrap_err();
+        assert!(matches!(&err, AppError::Persistence(msg) if msg.contains("不整合")));
+
+        let err = JournalEvent::ScoreRemoved { item, index: 3 }
+            .apply(&mut data)
+            .unwrap_err();
+        assert!(matches!(err, AppError::Persistence(_)));
+    }
+}
@@ -0,0 +1,516 @@
+use std::cell::{Cell, RefCell};
+use std::fs::{self, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::application::{AppError, BackupInfo, DataStore};
+use crate::constants::JOURNAL_COMPACT_THRESHOLD;
+use crate::domain::AppData;
+
+use super::backup::{BackupPolicy, BackupRotation, quarantine_file};
+use super::journal::{JournalEvent, JournalRecord, diff};
+
+/// 変更を1行ずつ追記するジャーナル形式のストア
+///
+/// 保存時は前回読み書きした内容との差分だけを追記し、起動時は先頭から再生してデータを復元する。
+/// 追記が一定件数に達したら、全データ1行だけのファイルに置き換えて圧縮する。
+/// 圧縮より前の変更の行は残らないため、ファイルに残る履歴は最後の圧縮以降の分だけとなる。
+/// バックアップは圧縮のたびに作成する（圧縮時点の全データのみ）。
+pub struct JournalStore {
+    path: PathBuf,
+    // 最後に読み込んだ／保存した内容（差分の基準。未設定なら圧縮して書き直す）
+    synced: RefCell<Option<AppData>>,
+    // 最後の圧縮以降に追記した変更の件数
+    appended: Cell<usize>,
+    compact_threshold: usize,
+    backups: BackupRotation,
+}
+
+impl JournalStore {
+    /// 既定のバックアップ保持件数と圧縮件数で作成する
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self::with_settings(path, BackupPolicy::default(), JOURNAL_COMPACT_THRESHOLD)
+    }
+
+    /// バックアップの保持件数と、圧縮するまでの追記件数を指定して作成する
+    pub fn with_settings<P: Into<PathBuf>>(
+        path: P,
+        policy: BackupPolicy,
+        compact_threshold: usize,
+    ) -> Self {
+        let path = path.into();
+        Self {
+            backups: BackupRotation::new(&path, policy),
+            path,
+            synced: RefCell::new(None),
+            appended: Cell::new(0),
+            compact_threshold: compact_threshold.max(1),
+        }
+    }
+
+    fn temp_path(&self) -> PathBuf {
+        let mut name = self.path.file_name().unwrap_or_default().to_os_string();
+        name.push(".tmp");
+        self.path.with_file_name(name)
+    }
+
+    /// ジャーナルを再生してデータを復元し、最後の圧縮以降の変更件数とともに返す
+    fn read_file(path: &Path) -> Result<(AppData, usize), AppError> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| AppError::Persistence(format!("ファイルを開けません: {}", e)))?;
+        let last_line = text.lines().count().saturating_sub(1);
+
+        let mut data = AppData::default();
+        let mut appended = 0;
+        for (number, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let record = match serde_json::from_str(line) {
+                Ok(value) => JournalRecord::from_value(value)?,
+                // 書き込み途中で終了した最終行は無視する（それ以前の変更までを復元）
+                Err(_) if number == last_line && !text.ends_with('\n') => break,
+                Err(e) => {
+                    return Err(AppError::Persistence(format!(
+                        "ジャーナルの {} 行目を読み込めません: {}",
+                        number + 1,
+                        e
+                    )));
+                }
+            };
+
+            if matches!(record.event, JournalEvent::Snapshot { .. }) {
+                appended = 0;
+            } else {
+                appended += 1;
+            }
+            record.event.apply(&mut data)?;
+        }
+
+        Ok((data, appended))
+    }
+
+    fn write_line(writer: &mut impl Write, event: JournalEvent) -> Result<(), AppError> {
+        let record = JournalRecord::new(event);
+        serde_json::to_writer(&mut *writer, &record)
+            .map_err(|e| AppError::Persistence(format!("ジャーナルの書込に失敗しました: {}", e)))?;
+        writer
+            .write_all(b"\n")
+            .map_err(|e| AppError::Persistence(format!("ジャーナルの書込に失敗しました: {}", e)))
+    }
+
+    /// 変更をファイル末尾に追記する
+    fn append(&self, events: Vec<JournalEvent>) -> Result<(), AppError> {
+        self.append_via(events, |file| file)
+    }
+
+    /// `wrap` で包んだ書き込み先を通して追記する
+    ///
+    /// 途中で失敗した場合は追記前の長さに切り詰め、差分の基準も捨てて次の保存で全体を書き直す
+    /// （書きかけの行や、同じ変更の二重の追記を残さないため）。
+    fn append_via<W: Write>(
+        &self,
+        events: Vec<JournalEvent>,
+        wrap: impl FnOnce(fs::File) -> W,
+    ) -> Result<(), AppError> {
+        let count = events.len();
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|e| AppError::Persistence(format!("ファイルを開けません: {}", e)))?;
+        let length = file
+            .metadata()
+            .map_err(|e| AppError::Persistence(format!("ファイルの状態を取得できません: {}", e)))?
+            .len();
+        let truncate = file
+            .try_clone()
+            .map_err(|e| AppError::Persistence(format!("ファイルを開けません: {}", e)))?;
+
+        if let Err(err) = Self::write_lines(BufWriter::new(wrap(file)), events) {
+            let _ = truncate.set_len(length);
+            self.synced.replace(None);
+            return Err(err);
+        }
+
+        self.appended.set(self.appended.get() + count);
+        Ok(())
+    }
+
+    fn write_lines(mut writer: impl Write, events: Vec<JournalEvent>) -> Result<(), AppError> {
+        events
+            .into_iter()
+            .try_for_each(|event| Self::write_line(&mut writer, event))?;
+        writer
+            .flush()
+            .map_err(|e| AppError::Persistence(format!("ジャーナルの書込に失敗しました: {}", e)))
+    }
+
+    /// 全データ1行だけのファイルに置き換える（一時ファイルに書いてから置換）
+    fn compact(&self, data: &AppData) -> Result<(), AppError> {
+        let tmp_path = self.temp_path();
+        let file = fs::File::create(&tmp_path)
+            .map_err(|e| AppError::Persistence(format!("一時ファイル作成に失敗しました: {}", e)))?;
+        let mut writer = BufWriter::new(file);
+
+        Self::write_line(&mut writer, JournalEvent::snapshot(data)?)?;
+        writer
+            .flush()
+            .map_err(|e| AppError::Persistence(format!("一時ファイル書込に失敗しました: {}", e)))?;
+
+        fs::rename(&tmp_path, &self.path).map_err(|e| {
+            AppError::Persistence(format!(
+                "ファイルの置換に失敗しました ({} -> {}): {}",
+                tmp_path.display(),
+                self.path.display(),
+                e
+            ))
+        })?;
+        self.appended.set(0);
+
+        // 圧縮した内容を世代バックアップに残す
+        self.backups.try_snapshot(&self.path);
+        Ok(())
+    }
+}
+
+impl DataStore for JournalStore {
+    fn load(&self) -> Result<Option<AppData>, AppError> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+
+        let (data, appended) = Self::read_file(&self.path)?;
+        self.synced.replace(Some(data.clone()));
+        self.appended.set(appended);
+        Ok(Some(data))
+    }
+
+    fn save(&self, data: &AppData) -> Result<(), AppError> {
+        let events = self
+            .synced
+            .borrow()
+            .as_ref()
+            .map(|previous| diff(previous, data));
+
+        match events {
+            Some(events) if events.is_empty() => {}
+            Some(events) => {
+                self.append(events)?;
+                if self.appended.get() >= self.compact_threshold {
+                    self.compact(data)?;
+                }
+            }
+            None => self.compact(data)?,
+        }
+
+        self.synced.replace(Some(data.clone()));
+        Ok(())
+    }
+
+    fn quarantine(&self) -> Result<String, AppError> {
+        self.synced.replace(None);
+        self.appended.set(0);
+        quarantine_file(&self.path).map(|target| target.display().to_string())
+    }
+
+    fn list_backups(&self) -> Result<Vec<BackupInfo>, AppError> {
+        self.backups
+            .describe(|path| Self::read_file(path).map(|(data, _)| data))
+    }
+
+    fn load_backup(&self, id: &str) -> Result<AppData, AppError> {
+        Self::read_file(&self.backups.resolve(id)?).map(|(data, _)| data)
+    }
+
+    fn take_backup_warning(&self) -> Option<String> {
+        self.backups.take_failure()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::migration::CURRENT_SCHEMA_VERSION;
+    use super::*;
+    use crate::constants::QUARANTINE_SUFFIX;
+    use crate::domain::{CategoryId, ItemId, ValueType, WeightingMode};
+    use chrono::Utc;
+    use serde_json::json;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    /// テストごとの一時フォルダ内のジャーナルパス（バックアップフォルダも同じ場所に作られる）
+    fn unique_path(name: &str) -> PathBuf {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time went backwards")
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("{name}-{unique}"));
+        fs::create_dir_all(&dir).unwrap();
+        dir.join("data.journal")
+    }
+
+    fn cleanup(path: &Path) {
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    fn line_count(path: &Path) -> usize {
+        fs::read_to_string(path).unwrap().lines().count()
+    }
+
+    fn seed() -> (AppData, CategoryId, ItemId) {
+        let mut data = AppData::default();
+        let cat = data.add_category("Training".to_string()).unwrap();
+        let item = data
+            .add_item(cat, "Sprint".to_string(), 0.8, ValueType::Integer)
+            .unwrap();
+        (data, cat, item)
+    }
+
+    #[test]
+    fn journal_store_roundtrip() {
+        // 追記したジャーナルを別のストアで再生して内容が保持されることを確認する。
+        let path = unique_path("weighted-score-journal");
+        let (mut data, cat, item) = seed();
+        let store = JournalStore::new(&path);
+        store.save(&data).unwrap();
+        data.add_score(cat, item, 10.0, Utc::now()).unwrap();
+        store.save(&data).unwrap();
+        data.rename_category(cat, "Running".to_string()).unwrap();
+        store.save(&data).unwrap();
+
+        let loaded = JournalStore::new(&path)
+            .load()
+            .expect("failed to load test data")
+            .expect("expected data");
+        assert_eq!(loaded, data);
+
+        cleanup(&path);
+    }
+
+    #[test]
+    fn load_returns_none_when_file_does_not_exist() {
+        // ジャーナルファイルが存在しない場合に load が None を返すことを確認する。
+        let path = unique_path("weighted-score-journal-missing");
+        assert!(JournalStore::new(&path).load().unwrap().is_none());
+
+        cleanup(&path);
+    }
+
+    #[test]
+    fn load_returns_error_for_invalid_line() {
+        // 途中の行が壊れているジャーナルを読み込むと永続化エラーになることを確認する。
+        let path = unique_path("weighted-score-journal-invalid");
+        let (data, _, _) = seed();
+        JournalStore::new(&path).save(&data).unwrap();
+        let text = fs::read_to_string(&path).unwrap();
+        fs::write(&path, format!("{{ not-json }}\n{}", text)).unwrap();
+
+        let result = JournalStore::new(&path).load();
+        assert!(matches!(result, Err(AppError::Persistence(_))));
+
+        cleanup(&path);
+    }
+
+    #[test]
+    fn save_appends_one_line_per_change() {
+        // スコアの追加ではファイル全体を書き直さず、変更分の行だけが追記されることを確認する。
+        let path = unique_path("weighted-score-journal-append");
+        let (mut data, cat, item) = seed();
+        let store = JournalStore::new(&path);
+        store.save(&data).unwrap();
+        let first_line = fs::read_to_string(&path).unwrap();
+        assert_eq!(line_count(&path), 1);
+
+        data.add_score(cat, item, 10.0, Utc::now()).unwrap();
+        store.save(&data).unwrap();
+        let text = fs::read_to_string(&path).unwrap();
+        assert!(text.starts_with(&first_line));
+        // 項目の更新日時とスコアの追加の2行
+        assert_eq!(line_count(&path), 3);
+        assert!(text.contains("\"type\":\"score_added\""));
+
+        store.save(&data).unwrap();
+        assert_eq!(line_count(&path), 3);
+
+        cleanup(&path);
+    }
+
+    /// 指定したバイト数まで書き込んだ後に失敗する書き込み先（ディスクの空き不足などを再現する）
+    struct FailAfter<W> {
+        inner: W,
+        remaining: usize,
+    }
+
+    impl<W: Write> Write for FailAfter<W> {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            if self.remaining == 0 {
+                return Err(std::io::Error::other("disk full"));
+            }
+            let written = self.inner.write(&buf[..buf.len().min(self.remaining)])?;
+            self.remaining -= written;
+            Ok(written)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            self.inner.flush()
+        }
+    }
+
+    #[test]
+    fn failed_append_is_rolled_back_and_next_save_rewrites() {
+        // 追記が途中で失敗してもファイルは追記前の長さに戻り、次の保存で全体を書き直して変更が重複しないことを確認する。
+        let path = unique_path("weighted-score-journal-partial");
+        let (mut data, cat, item) = seed();
+        let store = JournalStore::new(&path);
+        store.save(&data).unwrap();
+        let before = fs::read_to_string(&path).unwrap();
+
+        let previous = data.clone();
+        data.add_score(cat, item, 10.0, Utc::now()).unwrap();
+        let result = store.append_via(diff(&previous, &data), |file| FailAfter {
+            inner: file,
+            remaining: 40,
+        });
+        assert!(result.is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), before);
+
+        store.save(&data).unwrap();
+        assert_eq!(line_count(&path), 1);
+        let loaded = JournalStore::new(&path).load().unwrap().unwrap();
+        assert_eq!(loaded.get_item(cat, item).unwrap().scores.len(), 1);
+        assert_eq!(loaded, data);
+
+        cleanup(&path);
+    }
+
+    #[test]
+    fn item_events_recorded_by_older_version_are_migrated() {
+        // バージョン記載前の形式（created_at・entry_decay）やバージョン 1 で記録された項目の追加・変更も、現在の形式に変換して再生されることを確認する。
+        let path = unique_path("weighted-score-journal-v1-item");
+        let mut data = AppData::default();
+        let cat = data.add_category("Training".to_string()).unwrap();
+        JournalStore::new(&path).save(&data).unwrap();
+
+        let item = ItemId::generate();
+        let fields = json!({
+            "name": "Sprint",
+            "decay_rate": 0.9,
+            "value_type": "integer",
+            "rules": {},
+            "direction": "lower_is_better",
+            "weighting": { "mode": "entry_decay" },
+            "importance": 2.0,
+            "created_at": "2024-05-01T21:00:00Z"
+        });
+        let mut added = fields.clone();
+        added["scores"] = json!([{ "score": 12, "timestamp": "2024-05-01T21:00:00Z" }]);
+        let mut renamed = fields;
+        renamed["name"] = json!("Dash");
+        renamed["weighting"] = json!({ "mode": "exponential" });
+        let object = renamed.as_object_mut().unwrap();
+        object.remove("created_at");
+        object.insert("updated_at".to_string(), json!("2024-05-02T21:00:00Z"));
+        let lines = [
+            json!({ "at": "2024-05-01T21:00:00Z", "type": "item_added", "category": cat, "id": item, "item": added }),
+            json!({ "at": "2024-05-02T21:00:00Z", "schema_version": 1, "type": "item_updated", "id": item, "fields": renamed }),
+        ];
+        let mut text = fs::read_to_string(&path).unwrap();
+        for line in lines {
+            text.push_str(&format!("{}\n", line));
+        }
+        fs::write(&path, text).unwrap();
+
+        let loaded = JournalStore::new(&path).load().unwrap().unwrap();
+        let item = loaded.get_item(cat, item).unwrap();
+        assert_eq!(item.name, "Dash");
+        // 変換しなければ entry_decay を読み込めずにエラーになる
+        assert_eq!(item.weighting, WeightingMode::Exponential);
+        assert_eq!(item.importance, 2.0);
+        assert_eq!(item.scores.len(), 1);
+        assert_eq!(item.updated_at.to_rfc3339(), "2024-05-02T21:00:00+00:00");
+
+        cleanup(&path);
+    }
+
+    #[test]
+    fn journal_is_compacted_into_snapshot_with_backup() {
+        // 追記件数が上限に達するとスナップショット1行に圧縮され、バックアップが作成されることを確認する。
+        let path = unique_path("weighted-score-journal-compact");
+        let policy = BackupPolicy {
+            keep_recent: 5,
+            keep_daily: 0,
+            keep_weekly: 0,
+        };
+        let (mut data, cat, item) = seed();
+        let store = JournalStore::with_settings(&path, policy, 4);
+        store.save(&data).unwrap();
+
+        data.add_score(cat, item, 10.0, Utc::now()).unwrap();
+        store.save(&data).unwrap();
+        assert_eq!(line_count(&path), 3);
+        data.add_score(cat, item, 11.0, Utc::now()).unwrap();
+        store.save(&data).unwrap();
+        assert_eq!(line_count(&path), 1);
+
+        let loaded = JournalStore::new(&path).load().unwrap();
+        assert_eq!(loaded, Some(data));
+        let backups = store.list_backups().unwrap();
+        assert!(!backups.is_empty());
+        assert_eq!(backups[0].counts, Some((1, 1)));
+
+        cleanup(&path);
+    }
+
+    #[test]
+    fn torn_last_line_is_ignored() {
+        // 書き込み途中で終了した最終行は無視され、それ以前の変更までが復元されることを確認する。
+        let path = unique_path("weighted-score-journal-torn");
+        let (data, _, _) = seed();
+        JournalStore::new(&path).save(&data).unwrap();
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"{\"at\":\"2024-01-01T00:00:00Z\",\"type\":\"score_")
+            .unwrap();
+
+        assert_eq!(JournalStore::new(&path).load().unwrap(), Some(data));
+
+        cleanup(&path);
+    }
+
+    #[test]
+    fn quarantine_moves_file_to_timestamped_name() {
+        // 退避処理でファイルが日時付きの名前へ移動し、次の保存で新しいジャーナルが作られることを確認する。
+        let path = unique_path("weighted-score-journal-broken");
+        fs::write(&path, "{ not-json }\n").unwrap();
+
+        let store = JournalStore::new(&path);
+        assert!(store.load().is_err());
+        let moved = PathBuf::from(store.quarantine().unwrap());
+        let moved_name = moved.file_name().unwrap().to_string_lossy().to_string();
+        assert!(moved_name.contains(&format!(".{}-", QUARANTINE_SUFFIX)));
+        assert_eq!(fs::read_to_string(&moved).unwrap(), "{ not-json }\n");
+
+        store.save(&AppData::default()).unwrap();
+        assert_eq!(line_count(&path), 1);
+
+        cleanup(&path);
+    }
+
+    #[test]
+    fn load_fails_for_newer_schema_version() {
+        // 新しいバージョンのアプリで圧縮されたスナップショットは読み込みエラーになることを確認する。
+        let path = unique_path("weighted-score-journal-newer");
+        let line = format!(
+            r#"{{"at":"2024-01-01T00:00:00Z","type":"snapshot","data":{{"schema_version":{},"categories":{{}}}}}}"#,
+            CURRENT_SCHEMA_VERSION + 1
+        );
+        fs::write(&path, format!("{}\n", line)).unwrap();
+
+        assert!(matches!(
+            JournalStore::new(&path).load(),
+            Err(AppError::Persistence(_))
+        ));
+
+        cleanup(&path);
+    }
+}
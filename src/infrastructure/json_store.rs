@@ -1,17 +1,64 @@
+use std::cell::Cell;
 use std::fs;
-use std::io::{BufReader, BufWriter, Write};
+use std::hash::{DefaultHasher, Hash, Hasher};
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
-use crate::application::{AppError, DataStore};
+use crate::application::{AppError, BackupInfo, DataStore};
 use crate::domain::AppData;
 
+use super::backup::{BackupPolicy, BackupRotation, quarantine_file};
+use super::migration::{migrate, stamp_version};
+
+/// 読み込み・保存した時点のデータファイルの状態（ほかのプログラムによる変更の検出用）
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct FileStamp {
+    modified: Option<SystemTime>,
+    len: u64,
+    hash: u64,
+}
+
+impl FileStamp {
+    fn new(bytes: &[u8], metadata: &fs::Metadata) -> Self {
+        let mut hasher = DefaultHasher::new();
+        bytes.hash(&mut hasher);
+        Self {
+            modified: metadata.modified().ok(),
+            len: metadata.len(),
+            hash: hasher.finish(),
+        }
+    }
+
+    /// 更新日時とサイズが同じなら、内容を読まずに変更なしとみなす
+    fn matches(&self, metadata: &fs::Metadata) -> bool {
+        self.modified.is_some()
+            && self.modified == metadata.modified().ok()
+            && self.len == metadata.len()
+    }
+}
+
+/// JSON ファイル1つにデータ全体を保存するストア（一時ファイルに書いてから置き換える）
 pub struct JsonFileStore {
     path: PathBuf,
+    backups: BackupRotation,
+    /// 最後に読み込み・保存した時点のファイルの状態（ファイルがなければ None）
+    synced: Cell<Option<FileStamp>>,
 }
 
 impl JsonFileStore {
+    /// 既定のバックアップ保持件数で作成する
     pub fn new<P: Into<PathBuf>>(path: P) -> Self {
-        Self { path: path.into() }
+        Self::with_backup_policy(path, BackupPolicy::default())
+    }
+
+    /// バックアップの保持件数を指定して作成する
+    pub fn with_backup_policy<P: Into<PathBuf>>(path: P, policy: BackupPolicy) -> Self {
+        let path = path.into();
+        Self {
+            backups: BackupRotation::new(&path, policy),
+            path,
+            synced: Cell::new(None),
+        }
     }
 
     fn temp_path(&self) -> PathBuf {
@@ -29,35 +76,42 @@ impl JsonFileStore {
     fn path_exists(path: &Path) -> bool {
         path.exists()
     }
-}
 
-impl DataStore for JsonFileStore {
-    fn load(&self) -> Result<Option<AppData>, AppError> {
-        if !Self::path_exists(&self.path) {
-            return Ok(None);
-        }
+    fn read_bytes(path: &Path) -> Result<Vec<u8>, AppError> {
+        fs::read(path).map_err(|e| AppError::Persistence(format!("ファイルを開けません: {}", e)))
+    }
 
-        let file = fs::File::open(&self.path)
-            .map_err(|e| AppError::Persistence(format!("ファイルを開けません: {}", e)))?;
-        let reader = BufReader::new(file);
+    fn read_file(path: &Path) -> Result<AppData, AppError> {
+        Self::parse(&Self::read_bytes(path)?)
+    }
 
-        let data = serde_json::from_reader(reader)
+    fn parse(bytes: &[u8]) -> Result<AppData, AppError> {
+        let mut value: serde_json::Value = serde_json::from_slice(bytes)
             .map_err(|e| AppError::Persistence(format!("JSON読込に失敗しました: {}", e)))?;
 
-        Ok(Some(data))
+        // 古いスキーマのデータを現在の形式へ変換する（新しいバージョンのファイルはエラー）
+        migrate(&mut value)?;
+
+        serde_json::from_value(value)
+            .map_err(|e| AppError::Persistence(format!("JSON読込に失敗しました: {}", e)))
     }
 
-    fn save(&self, data: &AppData) -> Result<(), AppError> {
-        let tmp_path = self.temp_path();
+    fn metadata(&self) -> Result<fs::Metadata, AppError> {
+        fs::metadata(&self.path)
+            .map_err(|e| AppError::Persistence(format!("ファイルの状態を取得できません: {}", e)))
+    }
 
-        let file = fs::File::create(&tmp_path)
-            .map_err(|e| AppError::Persistence(format!("一時ファイル作成に失敗しました: {}", e)))?;
-        let mut writer = BufWriter::new(file);
+    /// 一時ファイルに書き出してから置き換え、書き込んだ内容を同期済みとして記録する
+    fn write(&self, data: &AppData) -> Result<(), AppError> {
+        let tmp_path = self.temp_path();
 
-        serde_json::to_writer_pretty(&mut writer, data)
+        let mut value = serde_json::to_value(data)
             .map_err(|e| AppError::Persistence(format!("JSON保存に失敗しました: {}", e)))?;
-        writer
-            .flush()
+        stamp_version(&mut value);
+        let bytes = serde_json::to_vec_pretty(&value)
+            .map_err(|e| AppError::Persistence(format!("JSON保存に失敗しました: {}", e)))?;
+
+        fs::write(&tmp_path, &bytes)
             .map_err(|e| AppError::Persistence(format!("一時ファイル書込に失敗しました: {}", e)))?;
 
         fs::rename(&tmp_path, &self.path).map_err(|e| {
@@ -68,22 +122,105 @@ impl DataStore for JsonFileStore {
                 e
             ))
         })?;
+        self.synced
+            .set(Some(FileStamp::new(&bytes, &self.metadata()?)));
 
+        // 保存した内容を世代バックアップに残す
+        self.backups.try_snapshot(&self.path);
         Ok(())
     }
 }
 
+impl DataStore for JsonFileStore {
+    fn load(&self) -> Result<Option<AppData>, AppError> {
+        if !Self::path_exists(&self.path) {
+            self.synced.set(None);
+            return Ok(None);
+        }
+
+        let bytes = Self::read_bytes(&self.path)?;
+        let data = Self::parse(&bytes)?;
+        self.synced
+            .set(Some(FileStamp::new(&bytes, &self.metadata()?)));
+        Ok(Some(data))
+    }
+
+    fn save(&self, data: &AppData) -> Result<(), AppError> {
+        if self.changed_externally()? {
+            return Err(AppError::Conflict(format!(
+                "{} は読み込み後にほかのプログラムによって変更されています。",
+                self.path.display()
+            )));
+        }
+        self.write(data)
+    }
+
+    /// 最後の読み込み・保存の後にファイルの内容が変わったかどうか
+    ///
+    /// 削除された場合は変更とみなさない（次の保存で作り直す）。
+    fn changed_externally(&self) -> Result<bool, AppError> {
+        if !Self::path_exists(&self.path) {
+            return Ok(false);
+        }
+        let Some(synced) = self.synced.get() else {
+            // 読み込み時になかったファイルが作られた
+            return Ok(true);
+        };
+
+        let metadata = self.metadata()?;
+        if synced.matches(&metadata) {
+            return Ok(false);
+        }
+        let current = FileStamp::new(&Self::read_bytes(&self.path)?, &metadata);
+        if current.hash != synced.hash {
+            return Ok(true);
+        }
+        // 内容が同じなら（同期ソフトによる更新日時の変更など）以降は新しい状態と比べる
+        self.synced.set(Some(current));
+        Ok(false)
+    }
+
+    fn save_overwriting(&self, data: &AppData) -> Result<(), AppError> {
+        self.write(data)
+    }
+
+    fn quarantine(&self) -> Result<String, AppError> {
+        quarantine_file(&self.path).map(|target| target.display().to_string())
+    }
+
+    fn list_backups(&self) -> Result<Vec<BackupInfo>, AppError> {
+        self.backups.describe(Self::read_file)
+    }
+
+    fn load_backup(&self, id: &str) -> Result<AppData, AppError> {
+        Self::read_file(&self.backups.resolve(id)?)
+    }
+
+    fn take_backup_warning(&self) -> Option<String> {
+        self.backups.take_failure()
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use super::super::migration::CURRENT_SCHEMA_VERSION;
     use super::*;
+    use crate::constants::{BACKUP_DIR_NAME, QUARANTINE_SUFFIX};
     use std::time::{SystemTime, UNIX_EPOCH};
 
+    /// テストごとの一時フォルダ内のデータファイルパス（バックアップフォルダも同じ場所に作られる）
     fn unique_path(name: &str) -> PathBuf {
         let unique = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .expect("time went backwards")
             .as_nanos();
-        std::env::temp_dir().join(format!("{name}-{unique}.json"))
+        let dir = std::env::temp_dir().join(format!("{name}-{unique}"));
+        fs::create_dir_all(&dir).unwrap();
+        dir.join("data.json")
+    }
+
+    fn cleanup(path: &Path) {
+        let _ = fs::remove_dir_all(path.parent().unwrap());
     }
 
     #[test]
@@ -102,18 +239,20 @@ mod tests {
             .load()
             .expect("failed to load test data")
             .expect("expected data");
-        assert!(loaded.categories.contains_key("test"));
+        assert!(loaded.find_category_id("test").is_some());
 
-        let _ = fs::remove_file(path);
+        cleanup(&path);
     }
 
     #[test]
     fn load_returns_none_when_file_does_not_exist() {
         // 保存ファイルが存在しない場合に load が None を返すことを確認する。
         let path = unique_path("weighted-score-missing");
-        let store = JsonFileStore::new(path);
+        let store = JsonFileStore::new(&path);
         let loaded = store.load().unwrap();
         assert!(loaded.is_none());
+
+        cleanup(&path);
     }
 
     #[test]
@@ -126,6 +265,158 @@ mod tests {
         let result = store.load();
         assert!(matches!(result, Err(AppError::Persistence(_))));
 
-        let _ = fs::remove_file(path);
+        cleanup(&path);
+    }
+
+    #[test]
+    fn quarantine_moves_file_to_timestamped_name() {
+        // 退避処理でファイルが日時付きの名前へ移動し、内容がそのまま残ることを確認する。
+        let path = unique_path("weighted-score-broken");
+        fs::write(&path, "{ not-json }").unwrap();
+
+        let store = JsonFileStore::new(&path);
+        let moved = PathBuf::from(store.quarantine().unwrap());
+
+        assert!(!path.exists());
+        let moved_name = moved.file_name().unwrap().to_string_lossy().to_string();
+        assert!(moved_name.contains(&format!(".{}-", QUARANTINE_SUFFIX)));
+        assert_eq!(fs::read_to_string(&moved).unwrap(), "{ not-json }");
+
+        cleanup(&moved);
+    }
+
+    #[test]
+    fn save_creates_backups_that_can_be_listed_and_loaded() {
+        // 保存のたびにバックアップが作成され、一覧の件数表示と読み込みができることを確認する。
+        let path = unique_path("weighted-score-backups");
+        let policy = BackupPolicy {
+            keep_recent: 5,
+            keep_daily: 0,
+            keep_weekly: 0,
+        };
+        let store = JsonFileStore::with_backup_policy(&path, policy);
+
+        let mut data = AppData::default();
+        let cat = data.add_category("first".to_string()).unwrap();
+        data.add_item(
+            cat,
+            "Item".to_string(),
+            0.9,
+            crate::domain::ValueType::Integer,
+        )
+        .unwrap();
+        store.save(&data).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        data.add_category("second".to_string()).unwrap();
+        store.save(&data).unwrap();
+
+        let backups = store.list_backups().unwrap();
+        assert_eq!(backups.len(), 2);
+        assert_eq!(backups[0].counts, Some((2, 1)));
+        assert_eq!(backups[1].counts, Some((1, 1)));
+
+        let oldest = store.load_backup(&backups[1].id).unwrap();
+        assert!(oldest.find_category_id("second").is_none());
+        assert!(store.load_backup("../data.json").is_err());
+
+        cleanup(&path);
+    }
+
+    #[test]
+    fn backup_failure_does_not_fail_save() {
+        // バックアップフォルダを作れなくても保存は成功し、失敗の説明を一度だけ取り出せることを確認する。
+        let path = unique_path("weighted-score-backup-failure");
+        fs::write(path.with_file_name(BACKUP_DIR_NAME), "not a directory").unwrap();
+        let store = JsonFileStore::new(&path);
+
+        store.save(&AppData::default()).unwrap();
+        assert_eq!(store.load().unwrap(), Some(AppData::default()));
+        assert!(store.take_backup_warning().is_some());
+        assert!(store.take_backup_warning().is_none());
+
+        cleanup(&path);
+    }
+
+    #[test]
+    fn save_writes_current_schema_version() {
+        // 保存したファイルに現在のスキーマバージョンが記録されることを確認する。
+        let path = unique_path("weighted-score-version");
+        let store = JsonFileStore::new(&path);
+        store.save(&AppData::default()).unwrap();
+
+        let written: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(written["schema_version"], CURRENT_SCHEMA_VERSION);
+
+        cleanup(&path);
+    }
+
+    #[test]
+    fn load_fails_for_newer_schema_version() {
+        // 新しいバージョンのアプリで保存されたファイルは読み込みエラーになることを確認する。
+        let path = unique_path("weighted-score-newer");
+        let json = format!(
+            r#"{{"schema_version":{},"categories":{{}}}}"#,
+            CURRENT_SCHEMA_VERSION + 1
+        );
+        fs::write(&path, json).unwrap();
+
+        let store = JsonFileStore::new(&path);
+        assert!(matches!(store.load(), Err(AppError::Persistence(_))));
+
+        cleanup(&path);
+    }
+
+    /// ほかのプログラムによる書き込みの代わりに、別のストアで読み込んでから保存する
+    fn write_externally(path: &Path, data: &AppData) {
+        let other = JsonFileStore::new(path);
+        other.load().unwrap();
+        other.save(data).unwrap();
+    }
+
+    #[test]
+    fn save_refuses_to_overwrite_external_changes() {
+        // 読み込み後にほかのプログラムが書き換えたファイルは通常の保存では上書きせず、上書き保存でのみ置き換わることを確認する。
+        let path = unique_path("weighted-score-external");
+        let store = JsonFileStore::new(&path);
+        store.save(&AppData::default()).unwrap();
+        assert!(!store.changed_externally().unwrap());
+
+        let mut external = AppData::default();
+        external.add_category("External".to_string()).unwrap();
+        write_externally(&path, &external);
+
+        assert!(store.changed_externally().unwrap());
+        assert!(matches!(
+            store.save(&AppData::default()),
+            Err(AppError::Conflict(_))
+        ));
+        assert_eq!(store.load().unwrap(), Some(external));
+        assert!(!store.changed_externally().unwrap());
+
+        let mut external = AppData::default();
+        external.add_category("Again".to_string()).unwrap();
+        write_externally(&path, &external);
+        store.save_overwriting(&AppData::default()).unwrap();
+        assert_eq!(store.load().unwrap(), Some(AppData::default()));
+
+        cleanup(&path);
+    }
+
+    #[test]
+    fn rewriting_same_content_is_not_an_external_change() {
+        // 同じ内容で書き直された（更新日時だけが変わった）ファイルは変更とみなさないことを確認する。
+        let path = unique_path("weighted-score-touch");
+        let store = JsonFileStore::new(&path);
+        store.save(&AppData::default()).unwrap();
+
+        let bytes = fs::read(&path).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        fs::write(&path, bytes).unwrap();
+
+        assert!(!store.changed_externally().unwrap());
+        store.save(&AppData::default()).unwrap();
+
+        cleanup(&path);
     }
 }
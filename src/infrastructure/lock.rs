@@ -0,0 +1,425 @@
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::application::AppError;
+use crate::constants::{LOCK_EXTENSION, LOCK_STALE_SECS};
+
+/// ロックの取得を試みる回数
+const ACQUIRE_ATTEMPTS: usize = 5;
+/// 読めないロックを読み直すまでの待ち時間
+const RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+/// ロックファイルの内容（どのプロセスがいつから使用しているか）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LockOwner {
+    /// 使用中のプロセスID
+    pub pid: u32,
+    /// 使用を始めた日時
+    pub since: DateTime<Utc>,
+    token: String,
+    /// ほかのプロセスからの操作を受け付けるポート（127.0.0.1。受け付けていなければ None）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ipc_port: Option<u16>,
+}
+
+impl LockOwner {
+    /// 読み取り専用バナー用の説明
+    pub fn describe(&self) -> String {
+        format!(
+            "このデータファイルはほかのウィンドウ（プロセス {}、{} から）で使用中です。",
+            self.pid,
+            self.since.with_timezone(&Local).format("%Y-%m-%d %H:%M")
+        )
+    }
+
+    /// ロックごとの識別子（ほかのプロセスからの操作の認証にも使う）
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+}
+
+/// ロックの取得結果
+pub enum LockState {
+    /// 取得した（破棄するまで保持する）
+    Acquired(DataLock),
+    /// ほかのプロセスが使用中
+    HeldByOther(LockOwner),
+}
+
+/// データファイルの使用中ロック（`<データファイル名>.lock`。破棄するとロックファイルを削除する）
+///
+/// 強制力のない目印のロックで、使用中は定期的に refresh して生きていることを示す。
+/// 一定時間更新されていないロックや、終了したプロセスのロックは残骸として取り除く
+/// （同時に起動したほかのプロセスが取り直したロックは消さない）。
+/// ロックファイルは内容を書き終えた一時ファイルから作るため、ほかのプロセスが書き込み途中の
+/// 空のロックを読むことはない。
+/// 同じプロセスが取り直した場合は新しいロックに引き継ぐ（古いロックを破棄しても消えない）。
+pub struct DataLock {
+    path: PathBuf,
+    owner: LockOwner,
+}
+
+impl DataLock {
+    /// データファイルのロックを取得する
+    pub fn acquire(data_path: &Path) -> Result<LockState, AppError> {
+        let path = lock_path(data_path);
+        let owner = LockOwner {
+            pid: std::process::id(),
+            since: Utc::now(),
+            token: Uuid::new_v4().to_string(),
+            ipc_port: None,
+        };
+
+        // 残骸を取り除いた後や、読めないロックを待った後に作成し直す
+        for _ in 0..ACQUIRE_ATTEMPTS {
+            match write_lock(&path, &owner, true) {
+                Ok(()) => return Ok(LockState::Acquired(Self { path, owner })),
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {}
+                Err(e) => return Err(lock_error(&path, e)),
+            }
+
+            match read_lock(&path) {
+                // 同じプロセスのロックは開き直しとみなして引き継ぐ
+                Some(existing) if existing.pid == owner.pid => {
+                    write_lock(&path, &owner, false).map_err(|e| lock_error(&path, e))?;
+                    return Ok(LockState::Acquired(Self { path, owner }));
+                }
+                Some(existing) if !is_stale(&path, &existing) => {
+                    return Ok(LockState::HeldByOther(existing));
+                }
+                Some(existing) => remove_stale(&path, Some(&existing), &owner.token)?,
+                // 読めないロックは書き込み途中の可能性があるため、古くなるまで取り除かない
+                None if is_expired(&path) => remove_stale(&path, None, &owner.token)?,
+                None => thread::sleep(RETRY_INTERVAL),
+            }
+        }
+        Err(AppError::Persistence(format!(
+            "ロックファイルを作成できません（内容を読み込めないロックがあります）: {}",
+            path.display()
+        )))
+    }
+
+    /// ロックの更新日時を新しくする
+    ///
+    /// ロックファイルがこのロックのものでなくなっていれば（残骸とみなしたほかのインスタンスが
+    /// 取り直した、削除されたなど）書き換えずに `AppError::Conflict` を返す。以降は書き込まないこと。
+    pub fn refresh(&self) -> Result<(), AppError> {
+        if read_lock(&self.path).is_none_or(|owner| owner.token != self.owner.token) {
+            return Err(AppError::Conflict(
+                "データファイルのロックを失いました（ほかのウィンドウが使用を始めたか、ロックファイルが削除されました）。以降の変更は保存しません。"
+                    .to_string(),
+            ));
+        }
+        write_lock(&self.path, &self.owner, false).map_err(|e| lock_error(&self.path, e))
+    }
+
+    /// ロックの内容
+    pub fn owner(&self) -> &LockOwner {
+        &self.owner
+    }
+
+    /// ほかのプロセスからの操作を受け付けるポートをロックファイルに書き込む
+    pub fn advertise_ipc(&mut self, port: u16) -> Result<(), AppError> {
+        self.owner.ipc_port = Some(port);
+        self.refresh()
+    }
+}
+
+impl Drop for DataLock {
+    fn drop(&mut self) {
+        // ほかのインスタンスが取り直したロックは消さない
+        if read_lock(&self.path).is_some_and(|owner| owner.token == self.owner.token) {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+fn lock_path(data_path: &Path) -> PathBuf {
+    let mut name = data_path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}", LOCK_EXTENSION));
+    data_path.with_file_name(name)
+}
+
+fn lock_error(path: &Path, e: std::io::Error) -> AppError {
+    AppError::Persistence(format!(
+        "ロックファイルを書き込めません ({}): {}",
+        path.display(),
+        e
+    ))
+}
+
+/// ロックファイルを書き込む（create_new なら既にある場合は AlreadyExists）
+///
+/// 内容を一時ファイルに書き終えてから、新規作成ではハードリンク、更新では名前の変更で置くため、
+/// ほかのプロセスから書き込み途中の内容は見えない。
+fn write_lock(path: &Path, owner: &LockOwner, create_new: bool) -> std::io::Result<()> {
+    let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+    temp_name.push(format!(".{}.tmp", owner.token));
+    let temp = path.with_file_name(temp_name);
+    let written = write_lock_file(&temp, owner, false).and_then(|()| {
+        if create_new {
+            fs::hard_link(&temp, path)
+        } else {
+            fs::rename(&temp, path)
+        }
+    });
+    let _ = fs::remove_file(&temp);
+
+    match written {
+        // ハードリンクを作れないファイルシステムでは直接作成する（読めないロックは古くなるまで残す）
+        Err(e) if create_new && e.kind() != ErrorKind::AlreadyExists => {
+            write_lock_file(path, owner, true)
+        }
+        result => result,
+    }
+}
+
+fn write_lock_file(path: &Path, owner: &LockOwner, create_new: bool) -> std::io::Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true);
+    if create_new {
+        options.create_new(true);
+    } else {
+        options.create(true).truncate(true);
+    }
+
+    let mut file = options.open(path)?;
+    let json = serde_json::to_vec(owner).map_err(std::io::Error::other)?;
+    file.write_all(&json)?;
+    file.set_modified(SystemTime::now())
+}
+
+fn read_lock(path: &Path) -> Option<LockOwner> {
+    let bytes = fs::read(path).ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
+/// 残骸と判定したロック（`stale`。読めないロックなら None）を取り除く
+///
+/// パスを指定して削除すると、判定の後にほかのプロセスが取り直した新しいロックまで消してしまうため、
+/// 一意の名前へ移してから中身を確かめ、判定したロックでなければ元の名前に戻す。
+fn remove_stale(path: &Path, stale: Option<&LockOwner>, token: &str) -> Result<(), AppError> {
+    let remove_error = |e: std::io::Error| {
+        AppError::Persistence(format!(
+            "古いロックファイルを削除できません ({}): {}",
+            path.display(),
+            e
+        ))
+    };
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}.stale", token));
+    let moved = path.with_file_name(name);
+
+    match fs::rename(path, &moved) {
+        Ok(()) => {}
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(remove_error(e)),
+    }
+
+    let is_judged = match (stale, read_lock(&moved)) {
+        (Some(stale), Some(moved_owner)) => moved_owner.token == stale.token,
+        (None, None) => is_expired(&moved),
+        _ => false,
+    };
+    if !is_judged {
+        // 元に戻す（その間にさらに別のロックが作られていれば、そちらを残す）
+        let _ = fs::hard_link(&moved, path);
+    }
+    fs::remove_file(&moved).map_err(remove_error)
+}
+
+/// 一定時間更新されていないか、持ち主のプロセスが終了していれば残骸とみなす
+fn is_stale(path: &Path, owner: &LockOwner) -> bool {
+    is_expired(path) || !process_alive(owner.pid)
+}
+
+/// ロックファイルが一定時間更新されていないかどうか
+fn is_expired(path: &Path) -> bool {
+    let stale_after = Duration::from_secs(LOCK_STALE_SECS);
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .is_some_and(|age| age > stale_after)
+}
+
+/// プロセスが動いているかどうか（/proc で確認できない場合は動いているとみなす）
+#[cfg(target_os = "linux")]
+fn process_alive(pid: u32) -> bool {
+    let proc_dir = Path::new("/proc");
+    if !proc_dir.join("self").exists() {
+        return true;
+    }
+    proc_dir.join(pid.to_string()).exists()
+}
+
+/// プロセスが動いているかどうか（Linux 以外では確認せず、ロックの更新日時だけで残骸を判定する）
+#[cfg(not(target_os = "linux"))]
+fn process_alive(_pid: u32) -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::UNIX_EPOCH;
+
+    fn unique_path(name: &str) -> PathBuf {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time went backwards")
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("{name}-{unique}"));
+        fs::create_dir_all(&dir).unwrap();
+        dir.join("data.json")
+    }
+
+    fn cleanup(path: &Path) {
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    /// ほかのプロセスが持つロックを作る（pid 1 は常に動いている）
+    fn write_foreign_lock(path: &Path) -> LockOwner {
+        let owner = LockOwner {
+            pid: 1,
+            since: Utc::now(),
+            token: "foreign".to_string(),
+            ipc_port: None,
+        };
+        write_lock(&lock_path(path), &owner, true).unwrap();
+        owner
+    }
+
+    fn set_lock_age(path: &Path, age: Duration) {
+        fs::File::options()
+            .write(true)
+            .open(lock_path(path))
+            .unwrap()
+            .set_modified(SystemTime::now() - age)
+            .unwrap();
+    }
+
+    #[test]
+    fn lock_held_by_other_process_is_reported() {
+        // ほかのプロセスが使用中のロックは取得できずに持ち主が返り、ロックファイルは残ることを確認する。
+        let path = unique_path("lock-held");
+        let foreign = write_foreign_lock(&path);
+
+        let LockState::HeldByOther(owner) = DataLock::acquire(&path).unwrap() else {
+            panic!("lock should be held");
+        };
+        assert_eq!(owner, foreign);
+        assert_eq!(read_lock(&lock_path(&path)), Some(foreign));
+        cleanup(&path);
+    }
+
+    #[test]
+    fn lock_is_released_on_drop_and_reacquired_by_same_process() {
+        // 同じプロセスは自分のロックを取り直せ、最後のロックを破棄するとロックファイルが消えることを確認する。
+        let path = unique_path("lock-release");
+        let LockState::Acquired(first) = DataLock::acquire(&path).unwrap() else {
+            panic!("lock should be acquired");
+        };
+        let LockState::Acquired(second) = DataLock::acquire(&path).unwrap() else {
+            panic!("own lock should be taken over");
+        };
+
+        drop(first);
+        assert!(lock_path(&path).exists());
+        drop(second);
+        assert!(!lock_path(&path).exists());
+        cleanup(&path);
+    }
+
+    #[test]
+    fn refresh_fails_after_lock_is_taken_over_or_removed() {
+        // ほかのインスタンスが取り直したロックや削除されたロックは、更新で上書き・再作成しないことを確認する。
+        let path = unique_path("lock-refresh");
+        let LockState::Acquired(lock) = DataLock::acquire(&path).unwrap() else {
+            panic!("lock should be acquired");
+        };
+        lock.refresh().unwrap();
+
+        let foreign = LockOwner {
+            pid: 1,
+            since: Utc::now(),
+            token: "foreign".to_string(),
+            ipc_port: None,
+        };
+        write_lock(&lock_path(&path), &foreign, false).unwrap();
+        assert!(matches!(lock.refresh(), Err(AppError::Conflict(_))));
+        assert_eq!(read_lock(&lock_path(&path)), Some(foreign));
+
+        fs::remove_file(lock_path(&path)).unwrap();
+        assert!(matches!(lock.refresh(), Err(AppError::Conflict(_))));
+        assert!(!lock_path(&path).exists());
+
+        drop(lock);
+        cleanup(&path);
+    }
+
+    #[test]
+    fn stale_lock_is_taken_over() {
+        // 更新が途絶えたロックや終了したプロセスのロックは取り除いて取得し直すことを確認する。
+        let path = unique_path("lock-stale");
+        write_foreign_lock(&path);
+        set_lock_age(&path, Duration::from_secs(LOCK_STALE_SECS + 60));
+
+        let LockState::Acquired(lock) = DataLock::acquire(&path).unwrap() else {
+            panic!("stale lock should be replaced");
+        };
+        assert_eq!(read_lock(&lock_path(&path)), Some(lock.owner.clone()));
+
+        drop(lock);
+        cleanup(&path);
+    }
+
+    #[test]
+    fn stale_removal_keeps_lock_taken_over_in_the_meantime() {
+        // 残骸と判定した後にほかのプロセスが取り直したロックは、残骸の削除で消さないことを確認する。
+        let path = unique_path("lock-stale-race");
+        let stale = write_foreign_lock(&path);
+        fs::remove_file(lock_path(&path)).unwrap();
+        let fresh = LockOwner {
+            token: "fresh".to_string(),
+            ..stale.clone()
+        };
+        write_lock(&lock_path(&path), &fresh, true).unwrap();
+
+        remove_stale(&lock_path(&path), Some(&stale), "mine").unwrap();
+        assert_eq!(read_lock(&lock_path(&path)), Some(fresh.clone()));
+
+        remove_stale(&lock_path(&path), Some(&fresh), "mine").unwrap();
+        assert!(!lock_path(&path).exists());
+        assert_eq!(fs::read_dir(path.parent().unwrap()).unwrap().count(), 0);
+        cleanup(&path);
+    }
+
+    #[test]
+    fn unreadable_lock_is_kept_until_it_expires() {
+        // 書き込み途中に見える空のロックは新しいうちは取り除かず、古くなってから取得し直すことを確認する。
+        let path = unique_path("lock-unreadable");
+        fs::write(lock_path(&path), "").unwrap();
+
+        assert!(DataLock::acquire(&path).is_err());
+        assert!(lock_path(&path).exists());
+
+        set_lock_age(&path, Duration::from_secs(LOCK_STALE_SECS + 60));
+        let LockState::Acquired(lock) = DataLock::acquire(&path).unwrap() else {
+            panic!("expired unreadable lock should be replaced");
+        };
+        assert_eq!(read_lock(&lock_path(&path)), Some(lock.owner.clone()));
+
+        // 一時ファイルは残らない
+        drop(lock);
+        assert_eq!(fs::read_dir(path.parent().unwrap()).unwrap().count(), 0);
+        cleanup(&path);
+    }
+}
@@ -0,0 +1,280 @@
+use serde_json::{Map, Value, json};
+use uuid::Uuid;
+
+use crate::application::AppError;
+
+/// 現在のデータファイルのスキーマバージョン
+pub const CURRENT_SCHEMA_VERSION: u64 = 2;
+
+/// スキーマバージョンを書き込むキー
+pub const SCHEMA_VERSION_KEY: &str = "schema_version";
+
+/// バージョン i のデータを i + 1 へ変換する処理（添字がそのまま変換元のバージョン）
+const MIGRATIONS: [fn(&mut Value); CURRENT_SCHEMA_VERSION as usize] =
+    [rename_legacy_fields, rekey_by_uuid];
+
+/// 読み込んだデータを現在のスキーマへ段階的に変換する
+///
+/// バージョンの記載がないファイルはバージョン 0 として扱う。
+/// 各変換は変換済みの内容に適用しても変化しないため、バージョン導入前に保存された新しい形式も読み込める。
+pub fn migrate(root: &mut Value) -> Result<(), AppError> {
+    let object = root.as_object_mut().ok_or_else(|| {
+        AppError::Persistence("データファイルの形式が正しくありません。".to_string())
+    })?;
+
+    let version = match object.remove(SCHEMA_VERSION_KEY) {
+        None => 0,
+        Some(value) => value.as_u64().ok_or_else(|| {
+            AppError::Persistence(format!(
+                "データファイルのスキーマバージョンが不正です: {}",
+                value
+            ))
+        })?,
+    };
+
+    if version > CURRENT_SCHEMA_VERSION {
+        return Err(AppError::Persistence(format!(
+            "このデータファイルは新しいバージョンのアプリで保存されています（スキーマバージョン {}、対応バージョン {} まで）。アプリを更新してください。",
+            version, CURRENT_SCHEMA_VERSION
+        )));
+    }
+
+    MIGRATIONS[version as usize..]
+        .iter()
+        .for_each(|step| step(root));
+    Ok(())
+}
+
+/// 項目1件分の内容（ジャーナルの項目の追加・変更）を、記録時のバージョンから現在のスキーマへ変換する
+///
+/// 項目だけを持つデータとして `migrate` にかけ、変換後の項目を取り出す。
+pub fn migrate_item(item: &mut Value, version: Option<Value>) -> Result<(), AppError> {
+    let mut root = json!({ "categories": { "": { "name": "", "items": { "": item.take() } } } });
+    if let (Some(version), Some(object)) = (version, root.as_object_mut()) {
+        object.insert(SCHEMA_VERSION_KEY.to_string(), version);
+    }
+    migrate(&mut root)?;
+
+    *item = items_mut(&mut root)
+        .next()
+        .map(Value::take)
+        .unwrap_or_default();
+    Ok(())
+}
+
+/// 保存するデータに現在のスキーマバージョンを書き込む
+pub fn stamp_version(root: &mut Value) {
+    if let Some(object) = root.as_object_mut() {
+        object.insert(
+            SCHEMA_VERSION_KEY.to_string(),
+            Value::from(CURRENT_SCHEMA_VERSION),
+        );
+    }
+}
+
+/// カテゴリの一覧（可変参照）
+fn categories_mut(root: &mut Value) -> Option<&mut Map<String, Value>> {
+    root.get_mut("categories").and_then(Value::as_object_mut)
+}
+
+/// 全項目への可変参照
+fn items_mut(root: &mut Value) -> impl Iterator<Item = &mut Value> {
+    categories_mut(root)
+        .into_iter()
+        .flat_map(|categories| categories.values_mut())
+        .filter_map(|category| category.get_mut("items").and_then(Value::as_object_mut))
+        .flat_map(|items| items.values_mut())
+}
+
+/// v0 -> v1: 旧名称のフィールド・値を現在の名称に揃える
+///
+/// - 項目の "created_at" -> "updated_at"
+/// - 重み付け方式 "entry_decay" -> "exponential"
+fn rename_legacy_fields(root: &mut Value) {
+    for item in items_mut(root).filter_map(Value::as_object_mut) {
+        if !item.contains_key("updated_at")
+            && let Some(created_at) = item.remove("created_at")
+        {
+            item.insert("updated_at".to_string(), created_at);
+        }
+
+        if let Some(mode) = item
+            .get_mut("weighting")
+            .and_then(|weighting| weighting.get_mut("mode"))
+            && mode == "entry_decay"
+        {
+            *mode = Value::from("exponential");
+        }
+    }
+}
+
+/// v1 -> v2: 名前をキーにしていたカテゴリ・項目を、UUIDキー＋name フィールドの形式へ変換する
+fn rekey_by_uuid(root: &mut Value) {
+    let Some(categories) = categories_mut(root) else {
+        return;
+    };
+
+    rekey_entries(categories);
+    categories
+        .values_mut()
+        .filter_map(|category| category.get_mut("items").and_then(Value::as_object_mut))
+        .for_each(rekey_entries);
+}
+
+/// name フィールドを持たないエントリを、キーを name に移したうえで新しいUUIDキーに付け替える
+fn rekey_entries(entries: &mut Map<String, Value>) {
+    let legacy_keys: Vec<String> = entries
+        .iter()
+        .filter(|(_, entry)| entry.is_object() && entry.get("name").is_none())
+        .map(|(key, _)| key.clone())
+        .collect();
+
+    for key in legacy_keys {
+        let Some(mut entry) = entries.remove(&key) else {
+            continue;
+        };
+        if let Some(object) = entry.as_object_mut() {
+            object.insert("name".to_string(), Value::String(key));
+        }
+        entries.insert(Uuid::new_v4().to_string(), entry);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{AppData, ItemData, ScoreDirection, ValueType, WeightingMode};
+
+    /// フィクスチャを読み込み、変換してから AppData として復元する
+    fn load_fixture(json: &str) -> AppData {
+        let mut root: Value = serde_json::from_str(json).unwrap();
+        migrate(&mut root).unwrap();
+        serde_json::from_value(root).unwrap()
+    }
+
+    fn find_item<'a>(data: &'a AppData, cat_name: &str, item_name: &str) -> &'a ItemData {
+        let cat = data.find_category_id(cat_name).unwrap();
+        let category = data.get_category(cat).unwrap();
+        category
+            .get_item(category.find_item_id(item_name).unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn v0_baseline_fixture_is_upgraded() {
+        // 最初期の形式（名前キー・created_at）が読み込まれ、名前・スコア・日時が保持されることを確認する。
+        let data = load_fixture(include_str!("fixtures/v0_baseline.json"));
+
+        let item = find_item(&data, "Training", "Pushups");
+        assert_eq!(item.scores.len(), 2);
+        assert_eq!(item.scores[1].score, 25.0);
+        assert_eq!(item.value_type, ValueType::Integer);
+        assert_eq!(item.updated_at.to_rfc3339(), "2024-01-01T08:00:00+00:00");
+    }
+
+    #[test]
+    fn v0_fixture_with_item_settings_is_upgraded() {
+        // 項目設定を持つ名前キー形式が読み込まれ、旧名称の重み付け方式が指数減衰に変換されることを確認する。
+        let data = load_fixture(include_str!("fixtures/v0_named_settings.json"));
+
+        let item = find_item(&data, "Games", "Sprint");
+        assert_eq!(item.value_type, ValueType::Decimal(1));
+        assert_eq!(item.direction, ScoreDirection::LowerIsBetter);
+        assert_eq!(item.weighting, WeightingMode::Exponential);
+        assert_eq!(item.importance, 2.0);
+        assert_eq!(item.scores[0].note.as_deref(), Some("warm day"));
+    }
+
+    #[test]
+    fn v1_named_fixture_is_rekeyed_by_uuid() {
+        // バージョン 1 の名前キー形式が UUID キーに変換され、名前と項目設定が保たれることを確認する。
+        let mut root: Value =
+            serde_json::from_str(include_str!("fixtures/v1_named_keys.json")).unwrap();
+        migrate(&mut root).unwrap();
+
+        let categories = root["categories"].as_object().unwrap();
+        let (cat_key, category) = categories.iter().next().unwrap();
+        assert!(Uuid::parse_str(cat_key).is_ok());
+        assert_eq!(category["name"], "Running");
+        let (item_key, item) = category["items"]
+            .as_object()
+            .unwrap()
+            .iter()
+            .next()
+            .unwrap();
+        assert!(Uuid::parse_str(item_key).is_ok());
+        assert_eq!(item["name"], "5k");
+
+        let data: AppData = serde_json::from_value(root).unwrap();
+        let item = find_item(&data, "Running", "5k");
+        assert_eq!(item.direction, ScoreDirection::LowerIsBetter);
+        assert_eq!(item.weighting, WeightingMode::Exponential);
+        assert_eq!(item.importance, 1.5);
+        assert_eq!(item.updated_at.to_rfc3339(), "2024-04-10T06:30:00+00:00");
+    }
+
+    #[test]
+    fn unstamped_uuid_fixture_keeps_existing_ids() {
+        // バージョン記載のない UUID キー形式では、既存の ID がそのまま保たれることを確認する。
+        let json = include_str!("fixtures/unstamped_uuid_keys.json");
+        let data = load_fixture(json);
+
+        let cat = data.find_category_id("Reading").unwrap();
+        assert!(json.contains(&cat.to_string()));
+        let item = find_item(&data, "Reading", "Pages");
+        assert_eq!(
+            item.weighting,
+            WeightingMode::TimeDecay {
+                half_life_days: 14.0
+            }
+        );
+    }
+
+    #[test]
+    fn current_fixture_is_loaded_without_changes() {
+        // 現行バージョンのファイルは変換されず、バージョン記載を除いて内容が一致することを確認する。
+        let mut root: Value =
+            serde_json::from_str(include_str!("fixtures/v2_current.json")).unwrap();
+        let mut expected = root.clone();
+        expected.as_object_mut().unwrap().remove(SCHEMA_VERSION_KEY);
+
+        migrate(&mut root).unwrap();
+        assert_eq!(root, expected);
+    }
+
+    #[test]
+    fn newer_schema_version_is_rejected() {
+        // 対応バージョンより新しいファイルは、バージョンを示すエラーで読み込みが拒否されることを確認する。
+        let mut root = serde_json::json!({
+            "schema_version": CURRENT_SCHEMA_VERSION + 1,
+            "categories": {}
+        });
+
+        let err = migrate(&mut root).unwrap_err();
+        assert!(matches!(&err, AppError::Persistence(msg) if msg.contains("新しいバージョン")));
+    }
+
+    #[test]
+    fn invalid_schema_version_is_rejected() {
+        // スキーマバージョンが数値でない場合はエラーになることを確認する。
+        let mut root = serde_json::json!({ "schema_version": "two", "categories": {} });
+        assert!(matches!(migrate(&mut root), Err(AppError::Persistence(_))));
+    }
+
+    #[test]
+    fn stamp_version_writes_current_version() {
+        // 保存時に現在のスキーマバージョンが書き込まれ、変換後は元のデータと一致することを確認する。
+        let mut data = AppData::default();
+        let cat = data.add_category("Work".to_string()).unwrap();
+        data.add_item(cat, "Run".to_string(), 0.9, ValueType::Integer)
+            .unwrap();
+
+        let original = serde_json::to_value(&data).unwrap();
+        let mut root = original.clone();
+        stamp_version(&mut root);
+        assert_eq!(root[SCHEMA_VERSION_KEY], CURRENT_SCHEMA_VERSION);
+
+        migrate(&mut root).unwrap();
+        assert_eq!(root, original);
+    }
+}
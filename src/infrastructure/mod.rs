@@ -1,3 +1,98 @@
+mod backup;
+mod csv_file;
+mod ipc;
+mod journal;
+mod journal_store;
 mod json_store;
+mod lock;
+mod migration;
+mod profiles;
+mod sqlite_store;
 
+use std::path::PathBuf;
+
+use crate::application::{AppError, DataStore};
+use crate::constants::{JOURNAL_COMPACT_THRESHOLD, JOURNAL_EXTENSION, SQLITE_EXTENSIONS};
+
+pub use backup::BackupPolicy;
+pub use csv_file::{read_csv, write_csv};
+pub use ipc::{IpcCommand, IpcListener, IpcReply, IpcRequest, send_request};
+pub use journal_store::JournalStore;
 pub use json_store::JsonFileStore;
+pub use lock::{DataLock, LockOwner, LockState};
+pub use profiles::DataLocation;
+pub use sqlite_store::SqliteStore;
+
+/// データファイルの拡張子に応じたストアを既定のバックアップ保持件数で開く
+///
+/// .journal はジャーナル、.db / .sqlite / .sqlite3 は SQLite、それ以外は JSON ファイルとして扱う。
+/// SQLite の場合、拡張子を .json にした同名のファイルがあれば、その内容を初回のみ取り込む。
+pub fn open_store<P: Into<PathBuf>>(path: P) -> Result<Box<dyn DataStore>, AppError> {
+    open_store_with_backups(path, BackupPolicy::default(), true)
+}
+
+/// バックアップの保持件数を指定して `open_store` と同じようにストアを開く
+///
+/// `writable` が false（ロックを取れず読み取り専用で開く場合）なら、SQLite への初回取り込みは行わない。
+pub fn open_store_with_backups<P: Into<PathBuf>>(
+    path: P,
+    policy: BackupPolicy,
+    writable: bool,
+) -> Result<Box<dyn DataStore>, AppError> {
+    let path = path.into();
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_ascii_lowercase())
+        .unwrap_or_default();
+    if extension == JOURNAL_EXTENSION {
+        return Ok(Box::new(JournalStore::with_settings(
+            path,
+            policy,
+            JOURNAL_COMPACT_THRESHOLD,
+        )));
+    }
+    if !SQLITE_EXTENSIONS.contains(&extension.as_str()) {
+        return Ok(Box::new(JsonFileStore::with_backup_policy(path, policy)));
+    }
+
+    let store = SqliteStore::with_backup_policy(&path, policy);
+    let legacy = path.with_extension("json");
+    if writable && legacy.exists() {
+        store.import_once(&JsonFileStore::new(legacy))?;
+    }
+    Ok(Box::new(store))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::AppData;
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    #[test]
+    fn read_only_open_does_not_import_legacy_json() {
+        // 読み取り専用で開いたときは旧 JSON を SQLite に取り込まず、書き込み可能で開いたときだけ取り込むことを確認する。
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time went backwards")
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("weighted-score-open-store-{unique}"));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("data.db");
+        let mut data = AppData::default();
+        data.add_category("Training".to_string()).unwrap();
+        JsonFileStore::new(path.with_extension("json"))
+            .save(&data)
+            .unwrap();
+
+        let store = open_store_with_backups(&path, BackupPolicy::default(), false).unwrap();
+        assert_eq!(store.load().unwrap(), None);
+
+        let store = open_store_with_backups(&path, BackupPolicy::default(), true).unwrap();
+        assert_eq!(store.load().unwrap(), Some(data));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
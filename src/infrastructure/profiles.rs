@@ -0,0 +1,267 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::application::AppError;
+use crate::constants::{
+    ACTIVE_PROFILE_FILENAME, APP_DIR_NAME, BACKUP_SETTINGS_FILENAME, DATA_FILENAME,
+    DEFAULT_PROFILE, MAX_PROFILE_NAME_LENGTH, PROFILES_DIR_NAME,
+};
+
+use super::backup::BackupPolicy;
+
+/// データの保存場所（プロファイルごとのフォルダをまとめる）
+///
+/// `<root>/profiles/<プロファイル名>/` にプロファイルごとのデータファイルとバックアップを置く。
+pub struct DataLocation {
+    root: PathBuf,
+}
+
+impl DataLocation {
+    /// 保存場所のフォルダを指定して作成する
+    pub fn new<P: Into<PathBuf>>(root: P) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// 保存場所を決める（指定がなければユーザーごとのデータフォルダ、取得できなければ現在のフォルダ）
+    pub fn resolve(override_dir: Option<PathBuf>) -> Self {
+        let root = override_dir.unwrap_or_else(|| {
+            dirs::data_dir()
+                .map(|dir| dir.join(APP_DIR_NAME))
+                .unwrap_or_else(|| PathBuf::from("."))
+        });
+        Self::new(root)
+    }
+
+    fn profiles_dir(&self) -> PathBuf {
+        self.root.join(PROFILES_DIR_NAME)
+    }
+
+    /// プロファイルのデータファイルのパス
+    pub fn data_path(&self, profile: &str) -> PathBuf {
+        self.profiles_dir().join(profile).join(DATA_FILENAME)
+    }
+
+    /// プロファイルのフォルダを用意し、データファイルのパスを返す
+    pub fn prepare(&self, profile: &str) -> Result<PathBuf, AppError> {
+        let name = validate_profile_name(profile)?;
+        let path = self.data_path(&name);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| {
+                AppError::Persistence(format!(
+                    "プロファイルのフォルダを作成できません ({}): {}",
+                    dir.display(),
+                    e
+                ))
+            })?;
+        }
+        Ok(path)
+    }
+
+    /// プロファイル名の一覧（既定のプロファイルは常に含める。名前順）
+    pub fn list_profiles(&self) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(self.profiles_dir())
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .filter(|entry| entry.path().is_dir())
+                    .filter_map(|entry| entry.file_name().into_string().ok())
+                    .filter(|name| validate_profile_name(name).is_ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+        if !names.iter().any(|name| name == DEFAULT_PROFILE) {
+            names.push(DEFAULT_PROFILE.to_string());
+        }
+        names.sort();
+        names
+    }
+
+    /// 最後に使ったプロファイル名（記録がなければ既定のプロファイル）
+    pub fn last_profile(&self) -> String {
+        fs::read_to_string(self.root.join(ACTIVE_PROFILE_FILENAME))
+            .ok()
+            .and_then(|name| validate_profile_name(&name).ok())
+            .unwrap_or_else(|| DEFAULT_PROFILE.to_string())
+    }
+
+    /// 使用中のプロファイル名を記録する（次回起動時に開く）
+    pub fn remember_profile(&self, profile: &str) -> Result<(), AppError> {
+        fs::create_dir_all(&self.root)
+            .and_then(|_| fs::write(self.root.join(ACTIVE_PROFILE_FILENAME), profile))
+            .map_err(|e| {
+                AppError::Persistence(format!("使用中のプロファイルを記録できません: {}", e))
+            })
+    }
+
+    /// バックアップの保持件数（保存場所の backup.json で指定。ファイルがなければ既定値）
+    ///
+    /// 例：`{"keep_recent": 20, "keep_daily": 14, "keep_weekly": 0}`（省略した件数は既定値）
+    pub fn backup_policy(&self) -> Result<BackupPolicy, AppError> {
+        let path = self.root.join(BACKUP_SETTINGS_FILENAME);
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(BackupPolicy::default());
+            }
+            Err(e) => {
+                return Err(AppError::Persistence(format!(
+                    "バックアップの設定を読み込めません ({}): {}",
+                    path.display(),
+                    e
+                )));
+            }
+        };
+        serde_json::from_str(&text).map_err(|e| {
+            AppError::Input(format!(
+                "バックアップの設定を解釈できません ({}): {}",
+                path.display(),
+                e
+            ))
+        })
+    }
+
+    /// 以前の保存場所（起動したフォルダ）のデータファイルを既定のプロファイルへ複製する
+    ///
+    /// 既定のプロファイルにまだデータファイルがない場合のみ複製し、複製したかどうかを返す。
+    pub fn adopt_legacy_file(&self, legacy: &Path) -> Result<bool, AppError> {
+        let target = self.data_path(DEFAULT_PROFILE);
+        if target.exists() || !legacy.is_file() {
+            return Ok(false);
+        }
+
+        let target = self.prepare(DEFAULT_PROFILE)?;
+        fs::copy(legacy, &target).map_err(|e| {
+            AppError::Persistence(format!(
+                "以前のデータファイルを移せません ({}): {}",
+                legacy.display(),
+                e
+            ))
+        })?;
+        Ok(true)
+    }
+}
+
+/// プロファイル名を検証する（前後の空白は除く。フォルダ名に使えない文字は不可）
+pub fn validate_profile_name(name: &str) -> Result<String, AppError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::Input(
+            "プロファイル名を入力してください。".to_string(),
+        ));
+    }
+    if name.chars().count() > MAX_PROFILE_NAME_LENGTH {
+        return Err(AppError::Input(format!(
+            "プロファイル名は{}文字以内で入力してください。",
+            MAX_PROFILE_NAME_LENGTH
+        )));
+    }
+    let invalid = |c: char| c.is_control() || r#"/\:*?"<>|"#.contains(c);
+    if name.starts_with('.') || name.chars().any(invalid) {
+        return Err(AppError::Input(format!(
+            "プロファイル名「{}」は使用できません（先頭の . と / \\ : * ? \" < > | は使えません）。",
+            name
+        )));
+    }
+    Ok(name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    /// テストごとの一時フォルダ
+    fn unique_root(name: &str) -> PathBuf {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time went backwards")
+            .as_nanos();
+        std::env::temp_dir().join(format!("{name}-{unique}"))
+    }
+
+    #[test]
+    fn profiles_are_listed_with_default() {
+        // 作成したプロファイルが名前順に列挙され、既定のプロファイルは常に含まれることを確認する。
+        let root = unique_root("profiles-list");
+        let location = DataLocation::new(&root);
+        assert_eq!(location.list_profiles(), vec![DEFAULT_PROFILE.to_string()]);
+
+        let path = location.prepare("work").unwrap();
+        assert_eq!(
+            path,
+            root.join(PROFILES_DIR_NAME)
+                .join("work")
+                .join(DATA_FILENAME)
+        );
+        location.prepare("home").unwrap();
+
+        assert_eq!(location.list_profiles(), vec!["default", "home", "work"]);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn last_profile_is_remembered() {
+        // 使用中のプロファイル名を記録すると次回の既定値になり、記録がなければ既定のプロファイルになることを確認する。
+        let root = unique_root("profiles-last");
+        let location = DataLocation::new(&root);
+        assert_eq!(location.last_profile(), DEFAULT_PROFILE);
+
+        location.remember_profile("work").unwrap();
+        assert_eq!(location.last_profile(), "work");
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn backup_policy_is_read_from_settings_file() {
+        // 設定ファイルがなければ既定値、あれば指定した件数（省略分は既定値）になり、不正な内容はエラーになることを確認する。
+        let root = unique_root("profiles-backup-policy");
+        fs::create_dir_all(&root).unwrap();
+        let location = DataLocation::new(&root);
+        assert_eq!(location.backup_policy().unwrap(), BackupPolicy::default());
+
+        let settings = root.join(BACKUP_SETTINGS_FILENAME);
+        fs::write(&settings, r#"{"keep_recent": 3, "keep_weekly": 0}"#).unwrap();
+        let policy = location.backup_policy().unwrap();
+        assert_eq!(policy.keep_recent, 3);
+        assert_eq!(policy.keep_daily, BackupPolicy::default().keep_daily);
+        assert_eq!(policy.keep_weekly, 0);
+
+        fs::write(&settings, r#"{"keep_recnt": 3}"#).unwrap();
+        assert!(matches!(location.backup_policy(), Err(AppError::Input(_))));
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn legacy_file_is_adopted_only_once() {
+        // 以前のデータファイルは既定のプロファイルにデータがない場合だけ複製されることを確認する。
+        let root = unique_root("profiles-legacy");
+        fs::create_dir_all(&root).unwrap();
+        let legacy = root.join("legacy.json");
+        fs::write(&legacy, "{}").unwrap();
+        let location = DataLocation::new(root.join("data"));
+
+        assert!(location.adopt_legacy_file(&legacy).unwrap());
+        assert_eq!(
+            fs::read_to_string(location.data_path(DEFAULT_PROFILE)).unwrap(),
+            "{}"
+        );
+
+        fs::write(&legacy, "{\"changed\":true}").unwrap();
+        assert!(!location.adopt_legacy_file(&legacy).unwrap());
+        assert_eq!(
+            fs::read_to_string(location.data_path(DEFAULT_PROFILE)).unwrap(),
+            "{}"
+        );
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn profile_name_rejects_path_characters() {
+        // パス区切りや先頭の . を含む名前・空の名前は拒否し、前後の空白は取り除くことを確認する。
+        assert_eq!(validate_profile_name("  仕事  ").unwrap(), "仕事");
+        assert!(validate_profile_name("").is_err());
+        assert!(validate_profile_name("../other").is_err());
+        assert!(validate_profile_name("a/b").is_err());
+        assert!(validate_profile_name(".hidden").is_err());
+    }
+}
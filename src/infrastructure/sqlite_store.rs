@@ -0,0 +1,728 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use rusqlite::types::FromSql;
+use rusqlite::{Connection, OptionalExtension, Row, params};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use crate::application::{AppError, BackupInfo, DataStore};
+use crate::domain::{AppData, CategoryData, CategoryId, ItemData, ItemId, ScoreEntry};
+
+use super::backup::{BackupPolicy, BackupRotation, quarantine_file};
+
+/// 現在のデータベースのスキーマバージョン
+const SQLITE_SCHEMA_VERSION: i64 = 1;
+
+const SCHEMA_VERSION_KEY: &str = "schema_version";
+// 既存ストアからの取り込み日時（取り込みは初回のみ）
+const IMPORTED_AT_KEY: &str = "imported_at";
+
+const SCHEMA: &str = "
+PRAGMA foreign_keys = ON;
+CREATE TABLE IF NOT EXISTS meta (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS categories (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS items (
+    id TEXT PRIMARY KEY,
+    category_id TEXT NOT NULL REFERENCES categories(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    decay_rate REAL NOT NULL,
+    value_type TEXT NOT NULL,
+    rules TEXT NOT NULL,
+    direction TEXT NOT NULL,
+    weighting TEXT NOT NULL,
+    importance REAL NOT NULL,
+    updated_at TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS scores (
+    item_id TEXT NOT NULL REFERENCES items(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    score REAL NOT NULL,
+    timestamp TEXT NOT NULL,
+    note TEXT,
+    tags TEXT NOT NULL,
+    PRIMARY KEY (item_id, position)
+);
+";
+
+/// SQLite データベースに保存するストア
+///
+/// カテゴリ・項目・スコアを別々のテーブルに持ち、保存時は前回読み書きした内容との差分だけを書き込む。
+/// バックアップはファイル全体の複製になるため、保存ごとではなく起動後の最初の保存前に作成する。
+pub struct SqliteStore {
+    path: PathBuf,
+    conn: RefCell<Option<Connection>>,
+    // 最後に読み込んだ／保存した内容（差分の基準。未設定なら全件を書き直す）
+    synced: RefCell<Option<AppData>>,
+    backed_up: Cell<bool>,
+    backups: BackupRotation,
+}
+
+impl SqliteStore {
+    /// 既定のバックアップ保持件数で作成する
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self::with_backup_policy(path, BackupPolicy::default())
+    }
+
+    /// バックアップの保持件数を指定して作成する
+    pub fn with_backup_policy<P: Into<PathBuf>>(path: P, policy: BackupPolicy) -> Self {
+        let path = path.into();
+        Self {
+            backups: BackupRotation::new(&path, policy),
+            path,
+            conn: RefCell::new(None),
+            synced: RefCell::new(None),
+            backed_up: Cell::new(false),
+        }
+    }
+
+    /// 既存のストア（JSON ファイルなど）の内容を取り込む（初回のみ）
+    ///
+    /// 取り込み済みの場合やデータベースに既にカテゴリがある場合は何もしない。取り込んだ場合は true を返す。
+    pub fn import_once<S: DataStore + ?Sized>(&self, source: &S) -> Result<bool, AppError> {
+        let populated = self.with_conn(|conn| {
+            let categories: i64 = conn
+                .query_row("SELECT COUNT(*) FROM categories", [], |row| row.get(0))
+                .map_err(db_error)?;
+            Ok(categories > 0 || meta_value(conn, IMPORTED_AT_KEY)?.is_some())
+        })?;
+        if populated {
+            return Ok(false);
+        }
+
+        let Some(data) = source.load()? else {
+            return Ok(false);
+        };
+        self.synced.replace(None);
+        self.save(&data)?;
+        self.with_conn(|conn| set_meta(conn, IMPORTED_AT_KEY, &format_time(&Utc::now())))?;
+        Ok(true)
+    }
+
+    /// 接続（初回はファイルを開いてテーブルを用意する）を使って処理する
+    fn with_conn<T>(
+        &self,
+        f: impl FnOnce(&Connection) -> Result<T, AppError>,
+    ) -> Result<T, AppError> {
+        let mut conn = self.conn.borrow_mut();
+        if conn.is_none() {
+            *conn = Some(Self::open(&self.path)?);
+        }
+        f(conn.as_ref().expect("connection is opened above"))
+    }
+
+    fn open(path: &Path) -> Result<Connection, AppError> {
+        let conn = Connection::open(path).map_err(db_error)?;
+        conn.execute_batch(SCHEMA).map_err(db_error)?;
+
+        match meta_value(&conn, SCHEMA_VERSION_KEY)? {
+            None => set_meta(
+                &conn,
+                SCHEMA_VERSION_KEY,
+                &SQLITE_SCHEMA_VERSION.to_string(),
+            )?,
+            Some(value) => {
+                let version: i64 = value.parse().map_err(|_| {
+                    AppError::Persistence(format!(
+                        "データベースのスキーマバージョンが不正です: {}",
+                        value
+                    ))
+                })?;
+                if version > SQLITE_SCHEMA_VERSION {
+                    return Err(AppError::Persistence(format!(
+                        "このデータベースは新しいバージョンのアプリで保存されています（スキーマバージョン {}、対応バージョン {} まで）。アプリを更新してください。",
+                        version, SQLITE_SCHEMA_VERSION
+                    )));
+                }
+            }
+        }
+        Ok(conn)
+    }
+
+    /// バックアップなど、別のデータベースファイルを読み取り専用で読み込む
+    fn read_file(path: &Path) -> Result<AppData, AppError> {
+        let conn = Connection::open_with_flags(path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .map_err(db_error)?;
+        read_all(&conn)
+    }
+}
+
+impl DataStore for SqliteStore {
+    fn load(&self) -> Result<Option<AppData>, AppError> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+
+        let data = self.with_conn(read_all)?;
+        self.synced.replace(Some(data.clone()));
+        Ok(Some(data))
+    }
+
+    fn save(&self, data: &AppData) -> Result<(), AppError> {
+        // 起動後の最初の保存前の内容を世代バックアップに残す
+        if !self.backed_up.get() && self.path.exists() {
+            self.backups.try_snapshot(&self.path);
+            self.backed_up.set(true);
+        }
+
+        let mut synced = self.synced.borrow_mut();
+        self.with_conn(|conn| {
+            let tx = conn.unchecked_transaction().map_err(db_error)?;
+            match synced.as_ref() {
+                Some(previous) => write_changes(&tx, previous, data)?,
+                None => {
+                    tx.execute_batch(
+                        "DELETE FROM scores; DELETE FROM items; DELETE FROM categories;",
+                    )
+                    .map_err(db_error)?;
+                    write_changes(&tx, &AppData::default(), data)?;
+                }
+            }
+            tx.commit().map_err(db_error)
+        })?;
+
+        *synced = Some(data.clone());
+        Ok(())
+    }
+
+    fn quarantine(&self) -> Result<String, AppError> {
+        // 開いたままのファイルは移動できない環境があるため、先に接続を閉じる
+        self.conn.replace(None);
+        self.synced.replace(None);
+        quarantine_file(&self.path).map(|target| target.display().to_string())
+    }
+
+    fn list_backups(&self) -> Result<Vec<BackupInfo>, AppError> {
+        self.backups.describe(Self::read_file)
+    }
+
+    fn load_backup(&self, id: &str) -> Result<AppData, AppError> {
+        Self::read_file(&self.backups.resolve(id)?)
+    }
+
+    fn take_backup_warning(&self) -> Option<String> {
+        self.backups.take_failure()
+    }
+}
+
+fn db_error(e: rusqlite::Error) -> AppError {
+    AppError::Persistence(format!("データベースの読み書きに失敗しました: {}", e))
+}
+
+fn column<T: FromSql>(row: &Row, index: usize) -> Result<T, AppError> {
+    row.get(index).map_err(db_error)
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String, AppError> {
+    serde_json::to_string(value)
+        .map_err(|e| AppError::Persistence(format!("データの変換に失敗しました: {}", e)))
+}
+
+fn from_json<T: DeserializeOwned>(text: &str) -> Result<T, AppError> {
+    serde_json::from_str(text)
+        .map_err(|e| AppError::Persistence(format!("データの変換に失敗しました: {}", e)))
+}
+
+fn format_time(time: &DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+}
+
+fn parse_time(text: &str) -> Result<DateTime<Utc>, AppError> {
+    DateTime::parse_from_rfc3339(text)
+        .map(|time| time.with_timezone(&Utc))
+        .map_err(|e| {
+            AppError::Persistence(format!("日時の形式が正しくありません ({}): {}", text, e))
+        })
+}
+
+fn parse_id<T: FromStr>(text: &str) -> Result<T, AppError> {
+    text.parse()
+        .map_err(|_| AppError::Persistence(format!("IDの形式が正しくありません: {}", text)))
+}
+
+fn meta_value(conn: &Connection, key: &str) -> Result<Option<String>, AppError> {
+    conn.query_row("SELECT value FROM meta WHERE key = ?1", [key], |row| {
+        row.get(0)
+    })
+    .optional()
+    .map_err(db_error)
+}
+
+fn set_meta(conn: &Connection, key: &str, value: &str) -> Result<(), AppError> {
+    conn.execute(
+        "INSERT OR REPLACE INTO meta (key, value) VALUES (?1, ?2)",
+        params![key, value],
+    )
+    .map(|_| ())
+    .map_err(db_error)
+}
+
+/// データベースの内容をすべて読み込む
+fn read_all(conn: &Connection) -> Result<AppData, AppError> {
+    let mut data = AppData::default();
+
+    let mut stmt = conn
+        .prepare("SELECT id, name, created_at FROM categories")
+        .map_err(db_error)?;
+    let mut rows = stmt.query([]).map_err(db_error)?;
+    while let Some(row) = rows.next().map_err(db_error)? {
+        let category = CategoryData {
+            name: column(row, 1)?,
+            items: HashMap::new(),
+            created_at: parse_time(&column::<String>(row, 2)?)?,
+        };
+        data.categories
+            .insert(parse_id(&column::<String>(row, 0)?)?, category);
+    }
+
+    // 項目ID -> 所属カテゴリID（スコアの振り分け用）
+    let mut owners: HashMap<ItemId, CategoryId> = HashMap::new();
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, category_id, name, decay_rate, value_type, rules, direction, weighting, importance, updated_at FROM items",
+        )
+        .map_err(db_error)?;
+    let mut rows = stmt.query([]).map_err(db_error)?;
+    while let Some(row) = rows.next().map_err(db_error)? {
+        let id: ItemId = parse_id(&column::<String>(row, 0)?)?;
+        let cat: CategoryId = parse_id(&column::<String>(row, 1)?)?;
+        let item = ItemData {
+            name: column(row, 2)?,
+            scores: Vec::new(),
+            decay_rate: column(row, 3)?,
+            value_type: from_json(&column::<String>(row, 4)?)?,
+            rules: from_json(&column::<String>(row, 5)?)?,
+            direction: from_json(&column::<String>(row, 6)?)?,
+            weighting: from_json(&column::<String>(row, 7)?)?,
+            importance: column(row, 8)?,
+            updated_at: parse_time(&column::<String>(row, 9)?)?,
+        };
+
+        let category = data.categories.get_mut(&cat).ok_or_else(|| {
+            AppError::Persistence(format!("項目の所属カテゴリが見つかりません: {}", id))
+        })?;
+        category.items.insert(id, item);
+        owners.insert(id, cat);
+    }
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT item_id, score, timestamp, note, tags FROM scores ORDER BY item_id, position",
+        )
+        .map_err(db_error)?;
+    let mut rows = stmt.query([]).map_err(db_error)?;
+    while let Some(row) = rows.next().map_err(db_error)? {
+        let id: ItemId = parse_id(&column::<String>(row, 0)?)?;
+        let entry = ScoreEntry {
+            score: column(row, 1)?,
+            timestamp: parse_time(&column::<String>(row, 2)?)?,
+            note: column(row, 3)?,
+            tags: from_json(&column::<String>(row, 4)?)?,
+        };
+
+        let item = owners
+            .get(&id)
+            .and_then(|cat| data.categories.get_mut(cat))
+            .and_then(|category| category.items.get_mut(&id))
+            .ok_or_else(|| {
+                AppError::Persistence(format!("スコアの記録先の項目が見つかりません: {}", id))
+            })?;
+        item.scores.push(entry);
+    }
+
+    Ok(data)
+}
+
+/// 項目ID -> (所属カテゴリID, 項目)
+fn items_by_id(data: &AppData) -> HashMap<ItemId, (CategoryId, &ItemData)> {
+    data.categories
+        .iter()
+        .flat_map(|(cat, category)| category.items.iter().map(|(id, item)| (*id, (*cat, item))))
+        .collect()
+}
+
+/// 前回の内容から変わった行だけを書き込む
+fn write_changes(conn: &Connection, previous: &AppData, current: &AppData) -> Result<(), AppError> {
+    for (id, category) in &current.categories {
+        let unchanged = previous
+            .categories
+            .get(id)
+            .is_some_and(|old| old.name == category.name && old.created_at == category.created_at);
+        if !unchanged {
+            conn.execute(
+                "INSERT INTO categories (id, name, created_at) VALUES (?1, ?2, ?3)
+                 ON CONFLICT(id) DO UPDATE SET name = excluded.name, created_at = excluded.created_at",
+                params![id.to_string(), category.name, format_time(&category.created_at)],
+            )
+            .map_err(db_error)?;
+        }
+    }
+
+    let previous_items = items_by_id(previous);
+    let current_items = items_by_id(current);
+    for (id, (cat, item)) in &current_items {
+        let old = previous_items.get(id);
+        let unchanged = old.is_some_and(|(old_cat, old_item)| {
+            old_cat == cat
+                && old_item.name == item.name
+                && old_item.settings() == item.settings()
+                && old_item.updated_at == item.updated_at
+        });
+        if !unchanged {
+            write_item(conn, *id, *cat, item)?;
+        }
+
+        let old_scores = old.map(|(_, old_item)| old_item.scores.as_slice());
+        write_scores(conn, *id, old_scores.unwrap_or_default(), &item.scores)?;
+    }
+
+    // 削除した項目のスコア、削除したカテゴリの項目は外部キーで一緒に削除される
+    for id in previous_items
+        .keys()
+        .filter(|id| !current_items.contains_key(id))
+    {
+        conn.execute("DELETE FROM items WHERE id = ?1", [id.to_string()])
+            .map_err(db_error)?;
+    }
+    for id in previous
+        .categories
+        .keys()
+        .filter(|id| !current.categories.contains_key(id))
+    {
+        conn.execute("DELETE FROM categories WHERE id = ?1", [id.to_string()])
+            .map_err(db_error)?;
+    }
+
+    Ok(())
+}
+
+fn write_item(
+    conn: &Connection,
+    id: ItemId,
+    cat: CategoryId,
+    item: &ItemData,
+) -> Result<(), AppError> {
+    conn.execute(
+        "INSERT INTO items (id, category_id, name, decay_rate, value_type, rules, direction, weighting, importance, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+         ON CONFLICT(id) DO UPDATE SET
+             category_id = excluded.category_id,
+             name = excluded.name,
+             decay_rate = excluded.decay_rate,
+             value_type = excluded.value_type,
+             rules = excluded.rules,
+             direction = excluded.direction,
+             weighting = excluded.weighting,
+             importance = excluded.importance,
+             updated_at = excluded.updated_at",
+        params![
+            id.to_string(),
+            cat.to_string(),
+            item.name,
+            item.decay_rate,
+            to_json(&item.value_type)?,
+            to_json(&item.rules)?,
+            to_json(&item.direction)?,
+            to_json(&item.weighting)?,
+            item.importance,
+            format_time(&item.updated_at),
+        ],
+    )
+    .map(|_| ())
+    .map_err(db_error)
+}
+
+/// 先頭から一致している記録はそのまま残し、それ以降だけを書き直す
+fn write_scores(
+    conn: &Connection,
+    item: ItemId,
+    previous: &[ScoreEntry],
+    current: &[ScoreEntry],
+) -> Result<(), AppError> {
+    let kept = previous
+        .iter()
+        .zip(current)
+        .take_while(|(old, new)| old == new)
+        .count();
+
+    if kept < previous.len() {
+        conn.execute(
+            "DELETE FROM scores WHERE item_id = ?1 AND position >= ?2",
+            params![item.to_string(), kept as i64],
+        )
+        .map_err(db_error)?;
+    }
+
+    let mut insert = conn
+        .prepare_cached(
+            "INSERT INTO scores (item_id, position, score, timestamp, note, tags) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        )
+        .map_err(db_error)?;
+    for (position, entry) in current.iter().enumerate().skip(kept) {
+        insert
+            .execute(params![
+                item.to_string(),
+                position as i64,
+                entry.score,
+                format_time(&entry.timestamp),
+                entry.note,
+                to_json(&entry.tags)?,
+            ])
+            .map_err(db_error)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::QUARANTINE_SUFFIX;
+    use crate::domain::{ScoreDirection, ValueType, WeightingMode};
+    use crate::infrastructure::JsonFileStore;
+    use chrono::Duration;
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    /// テストごとの一時フォルダ内のデータベースパス（バックアップフォルダも同じ場所に作られる）
+    fn unique_path(name: &str) -> PathBuf {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time went backwards")
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("{name}-{unique}"));
+        fs::create_dir_all(&dir).unwrap();
+        dir.join("data.db")
+    }
+
+    fn cleanup(path: &Path) {
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    /// 設定・メモ・タグを含むデータ
+    fn sample_data() -> (AppData, CategoryId, ItemId) {
+        let mut data = AppData::default();
+        let cat = data.add_category("Training".to_string()).unwrap();
+        let item = data
+            .add_item(cat, "Sprint".to_string(), 0.8, ValueType::Decimal(1))
+            .unwrap();
+        let mut settings = data.get_item(cat, item).unwrap().settings();
+        settings.direction = ScoreDirection::LowerIsBetter;
+        settings.weighting = WeightingMode::TimeDecay {
+            half_life_days: 14.0,
+        };
+        data.update_item_settings(cat, item, settings).unwrap();
+
+        let now = Utc::now();
+        for (i, score) in [12.5, 12.1, 11.8].into_iter().enumerate() {
+            data.add_score(cat, item, score, now - Duration::days(3 - i as i64))
+                .unwrap();
+        }
+        data.update_score_note(
+            cat,
+            item,
+            1,
+            "warm day".to_string(),
+            vec!["outdoor".to_string()],
+        )
+        .unwrap();
+        data.add_category("Empty".to_string()).unwrap();
+        (data, cat, item)
+    }
+
+    fn total_changes(store: &SqliteStore) -> u64 {
+        store.with_conn(|conn| Ok(conn.total_changes())).unwrap()
+    }
+
+    #[test]
+    fn sqlite_store_roundtrip() {
+        // データベースへ保存したデータを別の接続で再読み込みして内容が保持されることを確認する。
+        let path = unique_path("weighted-score-sqlite");
+        let (data, _, _) = sample_data();
+        SqliteStore::new(&path).save(&data).unwrap();
+
+        let loaded = SqliteStore::new(&path)
+            .load()
+            .expect("failed to load test data")
+            .expect("expected data");
+        assert_eq!(loaded, data);
+
+        cleanup(&path);
+    }
+
+    #[test]
+    fn load_returns_none_when_file_does_not_exist() {
+        // データベースファイルが存在しない場合に load が None を返し、ファイルも作られないことを確認する。
+        let path = unique_path("weighted-score-sqlite-missing");
+        let store = SqliteStore::new(&path);
+        assert!(store.load().unwrap().is_none());
+        assert!(!path.exists());
+
+        cleanup(&path);
+    }
+
+    #[test]
+    fn load_returns_error_for_invalid_file() {
+        // データベースではないファイルを読み込むと永続化エラーになることを確認する。
+        let path = unique_path("weighted-score-sqlite-invalid");
+        fs::write(&path, "this is not a database file at all").unwrap();
+
+        let store = SqliteStore::new(&path);
+        assert!(matches!(store.load(), Err(AppError::Persistence(_))));
+
+        cleanup(&path);
+    }
+
+    #[test]
+    fn quarantine_moves_file_to_timestamped_name() {
+        // 退避処理でファイルが日時付きの名前へ移動し、以降の保存は新しいファイルに行われることを確認する。
+        let path = unique_path("weighted-score-sqlite-broken");
+        let (data, _, _) = sample_data();
+        let store = SqliteStore::new(&path);
+        store.save(&data).unwrap();
+
+        let moved = PathBuf::from(store.quarantine().unwrap());
+        let moved_name = moved.file_name().unwrap().to_string_lossy().to_string();
+        assert!(moved_name.contains(&format!(".{}-", QUARANTINE_SUFFIX)));
+        assert!(!path.exists());
+
+        store.save(&AppData::default()).unwrap();
+        assert_eq!(
+            SqliteStore::new(&path).load().unwrap(),
+            Some(AppData::default())
+        );
+        assert_eq!(SqliteStore::read_file(&moved).unwrap(), data);
+
+        cleanup(&path);
+    }
+
+    #[test]
+    fn save_writes_only_changed_rows() {
+        // スコアを1件追加して保存したとき、変更のあった項目と追加したスコアの行だけが書き込まれることを確認する。
+        let path = unique_path("weighted-score-sqlite-incremental");
+        let (mut data, cat, item) = sample_data();
+        let store = SqliteStore::new(&path);
+        store.save(&data).unwrap();
+
+        let before = total_changes(&store);
+        data.add_score(cat, item, 11.5, Utc::now()).unwrap();
+        store.save(&data).unwrap();
+        assert_eq!(total_changes(&store) - before, 2);
+
+        let before = total_changes(&store);
+        store.save(&data).unwrap();
+        assert_eq!(total_changes(&store), before);
+
+        cleanup(&path);
+    }
+
+    #[test]
+    fn save_reflects_moves_edits_and_deletions() {
+        // 移動・名前変更・過去日時の記録追加・削除を重ねて保存しても、読み込み結果が一致することを確認する。
+        let path = unique_path("weighted-score-sqlite-edits");
+        let (mut data, cat, item) = sample_data();
+        let store = SqliteStore::new(&path);
+        store.save(&data).unwrap();
+
+        let empty = data.find_category_id("Empty").unwrap();
+        data.move_item(cat, empty, item).unwrap();
+        data.rename_category(empty, "Running".to_string()).unwrap();
+        data.add_score(empty, item, 13.0, Utc::now() - Duration::days(10))
+            .unwrap();
+        data.remove_score(empty, item, 2).unwrap();
+        store.save(&data).unwrap();
+
+        data.remove_category(cat).unwrap();
+        let other = data
+            .add_item(empty, "Pushups".to_string(), 0.9, ValueType::Integer)
+            .unwrap();
+        data.add_score(empty, other, 30.0, Utc::now()).unwrap();
+        store.save(&data).unwrap();
+
+        assert_eq!(SqliteStore::new(&path).load().unwrap(), Some(data));
+
+        cleanup(&path);
+    }
+
+    #[test]
+    fn import_once_copies_json_store_only_once() {
+        // JSON ストアの内容が初回だけ取り込まれ、2回目以降は取り込まれないことを確認する。
+        let path = unique_path("weighted-score-sqlite-import");
+        let json = JsonFileStore::new(path.with_extension("json"));
+        let (mut data, _, _) = sample_data();
+        json.save(&data).unwrap();
+
+        let store = SqliteStore::new(&path);
+        assert!(store.import_once(&json).unwrap());
+        assert_eq!(store.load().unwrap(), Some(data.clone()));
+
+        // 取り込み後に全カテゴリを削除しても、再度取り込まれない
+        store.save(&AppData::default()).unwrap();
+        data.add_category("later".to_string()).unwrap();
+        json.save(&data).unwrap();
+        assert!(!SqliteStore::new(&path).import_once(&json).unwrap());
+        assert_eq!(store.load().unwrap(), Some(AppData::default()));
+
+        cleanup(&path);
+    }
+
+    #[test]
+    fn first_save_of_session_creates_loadable_backup() {
+        // 起動後の最初の保存前の内容がバックアップされ、一覧の件数表示と読み込みができることを確認する。
+        let path = unique_path("weighted-score-sqlite-backups");
+        let policy = BackupPolicy {
+            keep_recent: 5,
+            keep_daily: 0,
+            keep_weekly: 0,
+        };
+        let (mut data, _, _) = sample_data();
+        let first = data.clone();
+        SqliteStore::with_backup_policy(&path, policy)
+            .save(&data)
+            .unwrap();
+
+        let store = SqliteStore::with_backup_policy(&path, policy);
+        store.load().unwrap();
+        data.add_category("second".to_string()).unwrap();
+        store.save(&data).unwrap();
+        store.save(&data).unwrap();
+
+        let backups = store.list_backups().unwrap();
+        assert_eq!(backups.len(), 1);
+        assert_eq!(backups[0].counts, Some((2, 1)));
+        assert_eq!(store.load_backup(&backups[0].id).unwrap(), first);
+        assert!(store.load_backup("../data.db").is_err());
+
+        cleanup(&path);
+    }
+
+    #[test]
+    fn load_fails_for_newer_schema_version() {
+        // 新しいバージョンのアプリで保存されたデータベースは読み込みエラーになることを確認する。
+        let path = unique_path("weighted-score-sqlite-newer");
+        SqliteStore::new(&path).save(&AppData::default()).unwrap();
+        let conn = Connection::open(&path).unwrap();
+        set_meta(
+            &conn,
+            SCHEMA_VERSION_KEY,
+            &(SQLITE_SCHEMA_VERSION + 1).to_string(),
+        )
+        .unwrap();
+        drop(conn);
+
+        let err = SqliteStore::new(&path).load().unwrap_err();
+        assert!(matches!(&err, AppError::Persistence(msg) if msg.contains("新しいバージョン")));
+
+        cleanup(&path);
+    }
+}
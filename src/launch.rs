@@ -0,0 +1,152 @@
+use std::path::PathBuf;
+
+use crate::cli::CliRequest;
+
+/// 起動時のコマンドライン引数
+///
+/// `--data <フォルダ>` でデータの保存場所を、`--profile <名前>` で開くプロファイルを指定する。
+/// それ以外の引数があればサブコマンドとして解釈し、画面は開かずに実行する。
+#[derive(Debug, Default, PartialEq)]
+pub struct LaunchOptions {
+    pub data_dir: Option<PathBuf>,
+    pub profile: Option<String>,
+    pub command: Option<CliRequest>,
+}
+
+/// 使い方の説明（serve の行は server 機能を有効にした場合のみ入れる）
+macro_rules! usage {
+    ($serve:literal) => {
+        concat!(
+            "\
+使い方:
+  WeightedScoreTracker [--data <フォルダ>] [--profile <名前>]
+  WeightedScoreTracker [--data <フォルダ>] [--profile <名前>] <コマンド> [--json]
+
+コマンド:
+  add <カテゴリ> <項目> <スコア> [--at <日時>] [--note <メモ>] [--tags <タグ,...>]
+                                  スコアを記録する（日時は YYYY-MM-DD HH:MM または YYYY-MM-DD）
+  list                            カテゴリと項目の一覧
+  history <カテゴリ> <項目>       スコア履歴
+  stats <カテゴリ> <項目>         加重平均などの統計値
+",
+            $serve,
+            "  help                            この説明を表示する
+
+--json を付けると結果を JSON で出力する。
+負のスコアはそのまま指定できる。- で始まる名前は -- の後に書く。"
+        )
+    };
+}
+
+#[cfg(feature = "server")]
+pub const USAGE: &str = usage!(
+    "  serve [--port <番号>]           画面を開かずに localhost で HTTP API を待ち受ける（既定 8765）\n"
+);
+#[cfg(not(feature = "server"))]
+pub const USAGE: &str = usage!("");
+
+impl LaunchOptions {
+    /// 引数（プログラム名を除く）を解釈する
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Self, String> {
+        let mut options = Self::default();
+        let mut args = args.into_iter();
+        let mut rest = Vec::new();
+
+        while let Some(arg) = args.next() {
+            // -- 以降はそのままサブコマンドに渡す
+            if arg == "--" {
+                rest.push(arg);
+                rest.extend(args.by_ref());
+                break;
+            }
+            let (flag, inline) = match arg.split_once('=') {
+                Some((flag, value)) => (flag.to_string(), Some(value.to_string())),
+                None => (arg, None),
+            };
+            let mut value = || {
+                inline
+                    .clone()
+                    .or_else(|| args.next())
+                    .filter(|value| !value.is_empty())
+                    .ok_or_else(|| format!("{} には値を指定してください。\n{}", flag, USAGE))
+            };
+
+            match flag.as_str() {
+                "--data" => options.data_dir = Some(PathBuf::from(value()?)),
+                "--profile" => options.profile = Some(value()?),
+                _ => rest.push(match inline {
+                    Some(value) => format!("{}={}", flag, value),
+                    None => flag,
+                }),
+            }
+        }
+
+        if !rest.is_empty() {
+            options.command = Some(CliRequest::parse(rest)?);
+        }
+        Ok(options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::Command;
+
+    fn parse(args: &[&str]) -> Result<LaunchOptions, String> {
+        LaunchOptions::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn parse_accepts_separate_and_inline_values() {
+        // --data と --profile は「フラグ 値」と「フラグ=値」のどちらの形でも指定できることを確認する。
+        assert_eq!(parse(&[]).unwrap(), LaunchOptions::default());
+
+        let options = parse(&["--data", "/tmp/scores", "--profile=work"]).unwrap();
+        assert_eq!(options.data_dir, Some(PathBuf::from("/tmp/scores")));
+        assert_eq!(options.profile.as_deref(), Some("work"));
+        assert_eq!(options.command, None);
+    }
+
+    #[test]
+    fn parse_passes_remaining_arguments_to_subcommand() {
+        // --data と --profile はサブコマンドの前後どちらにも書け、残りの引数がサブコマンドになることを確認する。
+        let options = parse(&["stats", "Cat", "--profile", "work", "Item", "--json"]).unwrap();
+        assert_eq!(options.profile.as_deref(), Some("work"));
+        assert_eq!(
+            options.command,
+            Some(CliRequest {
+                command: Command::Stats {
+                    category: "Cat".to_string(),
+                    item: "Item".to_string(),
+                },
+                json: true,
+            })
+        );
+
+        // -- 以降の --profile はプロファイルの指定ではなく引数になる
+        let options = parse(&["history", "--", "Cat", "--profile"]).unwrap();
+        assert_eq!(options.profile, None);
+        assert!(matches!(
+            options.command,
+            Some(CliRequest { command: Command::History { ref item, .. }, .. }) if item == "--profile"
+        ));
+    }
+
+    #[test]
+    fn parse_rejects_unknown_or_missing_values() {
+        // 不明な引数と値のないフラグはエラーになることを確認する。
+        assert!(parse(&["--verbose"]).is_err());
+        assert!(parse(&["list", "extra"]).is_err());
+        assert!(parse(&["--data"]).is_err());
+        assert!(parse(&["--profile="]).is_err());
+    }
+
+    #[test]
+    fn usage_lists_serve_only_with_server_feature() {
+        // serve の説明は server 機能を有効にしたビルドの使い方にだけ含まれることを確認する。
+        assert_eq!(USAGE.contains("serve"), cfg!(feature = "server"));
+        assert!(USAGE.contains("stats <カテゴリ> <項目>"));
+        assert!(USAGE.contains("  help "));
+    }
+}
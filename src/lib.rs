@@ -0,0 +1,38 @@
+//! 重み付きスコア記録の中核ライブラリ
+//!
+//! 画面（`gui` 機能で有効になる実行ファイル）に依存しない部分をまとめる。
+//!
+//! - [`domain`]：カテゴリ・項目・スコアのデータモデルと検証規則
+//! - [`application`]：[`TrackerService`](application::TrackerService) による操作（元に戻す・競合の解決・CSV など）と保存先の抽象 [`DataStore`](application::DataStore)
+//! - [`infrastructure`]：JSON / SQLite / ジャーナルの各ストア、バックアップ、使用中ロック、プロファイルごとの保存場所
+//! - [`logic`]：重み付け方式と加重平均・傾向・カテゴリ集計の計算
+//!
+//! ```no_run
+//! use weighted_score_tracker::application::TrackerService;
+//! use weighted_score_tracker::infrastructure::open_store;
+//! use weighted_score_tracker::logic::{calculate_stats, scheme_for};
+//!
+//! let store = open_store("scores_data.json")?;
+//! let mut service = TrackerService::try_open(store)?;
+//! service.add_score_to_item(("勉強", "英語"), "80", "", String::new(), "")?;
+//!
+//! let (cat, item) = service.find_item("勉強", "英語")?;
+//! let item = service.model().get_item(cat, item)?;
+//! let (mean, _std, count, _weights) = calculate_stats(&item.scores, scheme_for(item).as_ref());
+//! println!("{count} 件の加重平均: {mean}");
+//! # Ok::<(), weighted_score_tracker::application::AppError>(())
+//! ```
+
+#![warn(missing_docs)]
+
+/// カテゴリ・項目・スコアの操作（ユースケース）と保存先の抽象
+pub mod application;
+pub mod constants;
+/// データモデルと検証規則
+pub mod domain;
+/// データファイルの読み書き（JSON / SQLite / ジャーナル）と保存場所
+pub mod infrastructure;
+/// 重み付けと統計値の計算
+pub mod logic;
+/// 数値の表示と加重統計の補助関数
+pub mod utils;
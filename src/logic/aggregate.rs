@@ -0,0 +1,176 @@
+use super::{calculate_stats, scheme_for};
+use crate::domain::{CategoryData, ItemData, ItemId, ScoreDirection, ValueType};
+
+/// 換算値の範囲の上限（下限は 0）
+const NORMALIZED_SCALE: f64 = 100.0;
+
+/// カテゴリ概要における1項目分の集計
+pub struct ItemSummary {
+    /// 項目ID
+    pub id: ItemId,
+    /// 項目名
+    pub name: String,
+    /// 加重平均（スコアがなければ None）
+    pub average: Option<f64>,
+    /// 加重平均をスコアの範囲で 0〜100 に換算した値（良い向きほど大きい。スコアがなければ None）
+    pub normalized: Option<f64>,
+    /// スコアの件数
+    pub count: usize,
+    /// カテゴリ集計での重要度
+    pub importance: f64,
+    /// 値の種類（表示桁数に使う）
+    pub value_type: ValueType,
+}
+
+/// カテゴリ全体の集計
+pub struct CategorySummary {
+    /// 項目ごとの集計（名前順）
+    pub items: Vec<ItemSummary>,
+    /// 各項目の換算値を重要度で加重平均した値（0〜100）
+    pub aggregate: Option<f64>,
+}
+
+/// カテゴリ内の各項目の加重平均を重要度で合成する
+///
+/// 項目ごとに単位や評価の向きが異なるため、加重平均をその項目のスコアの範囲
+/// （最小値・最大値が設定されていればその範囲、なければ記録したスコアの最小〜最大）で
+/// 0〜100 に換算し（低いほど良い項目は反転）、換算値を重要度で加重平均する。
+pub fn summarize_category(category: &CategoryData) -> CategorySummary {
+    let mut items = category
+        .items
+        .iter()
+        .map(|(id, item)| {
+            let (average, _, count, _) = calculate_stats(&item.scores, scheme_for(item).as_ref());
+            let average = (count > 0).then_some(average);
+            ItemSummary {
+                id: *id,
+                name: item.name.clone(),
+                average,
+                normalized: average.map(|avg| normalize(item, avg)),
+                count,
+                importance: item.importance,
+                value_type: item.value_type,
+            }
+        })
+        .collect::<Vec<_>>();
+    items.sort_by(|a, b| a.name.cmp(&b.name));
+
+    // スコアのない項目は集計対象外
+    let (weighted_sum, importance_sum) = items
+        .iter()
+        .filter_map(|summary| summary.normalized.map(|value| (value, summary.importance)))
+        .fold((0.0, 0.0), |(sum, total), (value, importance)| {
+            (sum + value * importance, total + importance)
+        });
+    let aggregate = (importance_sum > 0.0).then(|| weighted_sum / importance_sum);
+
+    CategorySummary { items, aggregate }
+}
+
+/// 加重平均を項目のスコアの範囲で 0〜100 に換算する（範囲の幅が 0 なら中央の 50）
+fn normalize(item: &ItemData, average: f64) -> f64 {
+    let (low, high) = match (item.rules.min, item.rules.max) {
+        (Some(min), Some(max)) if max > min => (min, max),
+        _ => item
+            .scores
+            .iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(low, high), s| {
+                (low.min(s.score), high.max(s.score))
+            }),
+    };
+    if high <= low {
+        return NORMALIZED_SCALE / 2.0;
+    }
+
+    let position = ((average - low) / (high - low)).clamp(0.0, 1.0);
+    match item.direction {
+        ScoreDirection::HigherIsBetter => position * NORMALIZED_SCALE,
+        ScoreDirection::LowerIsBetter => (1.0 - position) * NORMALIZED_SCALE,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn category_with(items: &[(&str, &[f64], f64)]) -> CategoryData {
+        let mut category = CategoryData::new("Cat".to_string());
+        for (name, scores, importance) in items {
+            let id = category
+                .add_item(name.to_string(), 1.0, ValueType::Integer)
+                .unwrap();
+            let item = category.get_item_mut(id).unwrap();
+            item.importance = *importance;
+            for &score in scores.iter() {
+                item.add_score(score, Utc::now()).unwrap();
+            }
+        }
+        category
+    }
+
+    /// スコアの範囲（最小値・最大値）を設定する
+    fn set_range(category: &mut CategoryData, name: &str, min: f64, max: f64) {
+        let id = category.find_item_id(name).unwrap();
+        let item = category.get_item_mut(id).unwrap();
+        item.rules.min = Some(min);
+        item.rules.max = Some(max);
+    }
+
+    #[test]
+    fn aggregate_weights_normalized_averages_by_importance() {
+        // 各項目の加重平均を範囲内の位置（0〜100）に換算し、重要度で加重平均した値が集計値になることを確認する。
+        let mut category = category_with(&[("a", &[10.0, 20.0], 1.0), ("b", &[40.0], 3.0)]);
+        set_range(&mut category, "a", 0.0, 100.0);
+        set_range(&mut category, "b", 0.0, 50.0);
+        let summary = summarize_category(&category);
+
+        assert_eq!(summary.items.len(), 2);
+        assert_eq!(summary.items[0].average, Some(15.0));
+        assert_eq!(summary.items[0].normalized, Some(15.0));
+        assert_eq!(summary.items[0].count, 2);
+        assert_eq!(summary.items[1].normalized, Some(80.0));
+        assert_eq!(summary.aggregate, Some((15.0 + 80.0 * 3.0) / 4.0));
+    }
+
+    #[test]
+    fn aggregate_flips_lower_is_better_items() {
+        // 低いほど良い項目は換算値が反転し、単位の異なる高いほど良い項目と同じ尺度で合成されることを確認する。
+        let mut category = category_with(&[
+            ("点数", &[60.0, 80.0], 1.0),
+            ("タイム", &[10.0, 10.0, 40.0], 1.0),
+        ]);
+        set_range(&mut category, "点数", 0.0, 100.0);
+        let time = category.find_item_id("タイム").unwrap();
+        category.get_item_mut(time).unwrap().direction = ScoreDirection::LowerIsBetter;
+        let summary = summarize_category(&category);
+
+        // 範囲の設定がないタイムは記録した 10〜40 の中で平均 20 の位置を反転する
+        let time = summary.items.iter().find(|i| i.name == "タイム").unwrap();
+        let expected_time = (1.0 - (20.0 - 10.0) / 30.0) * 100.0;
+        assert!((time.normalized.unwrap() - expected_time).abs() < 1e-9);
+        let expected = (70.0 + expected_time) / 2.0;
+        assert!((summary.aggregate.unwrap() - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn aggregate_skips_items_without_scores() {
+        // スコアのない項目は集計から除外され、平均も None になり、範囲の幅が 0 の項目は中央の 50 になることを確認する。
+        let category = category_with(&[("a", &[10.0], 1.0), ("b", &[], 5.0)]);
+        let summary = summarize_category(&category);
+
+        assert_eq!(summary.items[1].average, None);
+        assert_eq!(summary.items[1].normalized, None);
+        assert_eq!(summary.aggregate, Some(50.0));
+    }
+
+    #[test]
+    fn aggregate_is_none_without_effective_importance() {
+        // 集計対象がない場合や重要度の合計が 0 の場合は集計値が None になることを確認する。
+        let empty = category_with(&[]);
+        assert_eq!(summarize_category(&empty).aggregate, None);
+
+        let zero = category_with(&[("a", &[10.0], 0.0)]);
+        assert_eq!(summarize_category(&zero).aggregate, None);
+    }
+}
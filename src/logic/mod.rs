@@ -0,0 +1,227 @@
+use crate::constants::PLOT_WEIGHT_THRESHOLD;
+use crate::domain::{ScoreEntry, ValueType};
+use crate::utils::weighted_statistics::{weighted_mean, weighted_std};
+use std::iter::zip;
+
+mod aggregate;
+mod trend;
+mod weighting;
+
+pub use aggregate::{CategorySummary, ItemSummary, summarize_category};
+pub use trend::{ScoreTrend, TrendDirection, calculate_trend};
+pub use weighting::{WeightingScheme, scheme_for};
+
+/// 項目の重み付け方式でスコアの加重平均と加重標準偏差を計算する
+///
+/// (加重平均, 加重標準偏差, 件数, 各スコアの重み) を返す。スコアがなければすべて 0 になる。
+pub fn calculate_stats(
+    scores: &[ScoreEntry],
+    scheme: &dyn WeightingScheme,
+) -> (f64, f64, usize, Vec<f64>) {
+    if scores.is_empty() {
+        return (0.0, 0.0, 0, Vec::new());
+    }
+
+    let n = scores.len();
+    let weights = scheme.weights(scores);
+    let score_values = scores.iter().map(|s| s.score).collect::<Vec<_>>();
+
+    let mean = weighted_mean(&score_values, &weights);
+    let std = weighted_std(&score_values, &weights);
+
+    (mean, std, n, weights)
+}
+
+/// グラフの縦軸の表示範囲
+pub struct PlotParams {
+    /// 上端
+    pub max_y: f64,
+    /// 下端
+    pub min_y: f64,
+}
+
+fn filtered_scores(scores: &[ScoreEntry], weights: &[f64], threshold: f64) -> Option<Vec<f64>> {
+    let v: Vec<_> = zip(scores.iter(), weights.iter())
+        .filter_map(|(entry, &w)| (w >= threshold).then_some(entry.score))
+        .collect();
+
+    (!v.is_empty()).then_some(v) // フィルターによって要素がない場合は、すべて返す
+}
+
+/// 重みに基づいて、グラフの適切な表示範囲を計算する
+pub fn calculate_plot_params(
+    scores: &[ScoreEntry],
+    scheme: &dyn WeightingScheme,
+    value_type: ValueType,
+) -> PlotParams {
+    let weights = scheme.weights(scores);
+
+    // 重みが一定以上のスコアだけを抽出（なければ全データ）
+    let relevant_scores = filtered_scores(scores, &weights, PLOT_WEIGHT_THRESHOLD)
+        .unwrap_or_else(|| scores.iter().map(|s| s.score).collect());
+
+    let min_score = relevant_scores
+        .iter()
+        .copied()
+        .reduce(f64::min)
+        .unwrap_or(0.0);
+    let max_score = relevant_scores
+        .iter()
+        .copied()
+        .reduce(f64::max)
+        .unwrap_or(0.0);
+
+    // 余白計算（すべて同じ値の場合は、値の最小単位分の余白を取る）
+    let range = max_score - min_score;
+    let padding = if range > 0.0 {
+        range * 0.1
+    } else {
+        10_f64.powi(-(value_type.precision() as i32))
+    };
+    let max_y = max_score + padding;
+    // 負のスコアがなければ下限を 0 で打ち切る
+    let min_y = if min_score >= 0.0 {
+        (min_score - padding).max(0.0)
+    } else {
+        min_score - padding
+    };
+
+    PlotParams { max_y, min_y }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+    use weighting::{Exponential, HalfLife};
+
+    /// 指定した重みをそのまま返すテスト用の方式
+    struct FixedWeights(Vec<f64>);
+
+    impl WeightingScheme for FixedWeights {
+        fn weights(&self, _scores: &[ScoreEntry]) -> Vec<f64> {
+            self.0.clone()
+        }
+    }
+
+    fn score_entries(values: &[f64]) -> Vec<ScoreEntry> {
+        values
+            .iter()
+            .map(|&score| ScoreEntry {
+                score,
+                timestamp: Utc::now(),
+                note: None,
+                tags: Vec::new(),
+            })
+            .collect()
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        let diff = (actual - expected).abs();
+        assert!(
+            diff < 1e-9,
+            "expected {expected}, got {actual}, diff {diff}"
+        );
+    }
+
+    #[test]
+    fn calculate_stats_returns_default_for_empty_scores() {
+        // スコア履歴が空のときに統計値と重みが既定値で返ることを確認する。
+        let (mean, std, n, weights) = calculate_stats(&[], &Exponential { decay_rate: 0.9 });
+        assert_eq!(mean, 0.0);
+        assert_eq!(std, 0.0);
+        assert_eq!(n, 0);
+        assert!(weights.is_empty());
+    }
+
+    #[test]
+    fn calculate_stats_generates_decay_weights_and_statistics() {
+        // 減衰率に基づく重み配列と加重平均・標準偏差が期待値になることを確認する。
+        let scores = score_entries(&[10.0, 20.0, 30.0]);
+        let (mean, std, n, weights) = calculate_stats(&scores, &Exponential { decay_rate: 0.5 });
+
+        assert_eq!(n, 3);
+        assert_eq!(weights, vec![0.25, 0.5, 1.0]);
+        assert_close(mean, 24.285714285714285);
+        assert_close(std, 7.284313590846315);
+    }
+
+    #[test]
+    fn calculate_stats_uses_half_life_for_time_decay() {
+        // 経過日数モードでは最新の記録から半減期ごとに重みが半分になり、記録件数には依存しないことを確認する。
+        let latest = Utc::now();
+        let mut scores = score_entries(&[10.0, 20.0, 30.0, 40.0]);
+        scores[0].timestamp = latest - Duration::days(20);
+        scores[1].timestamp = latest - Duration::days(10);
+        scores[2].timestamp = latest - Duration::days(10);
+        scores[3].timestamp = latest;
+
+        let scheme = HalfLife {
+            half_life_days: 10.0,
+        };
+        let (mean, _, n, weights) = calculate_stats(&scores, &scheme);
+
+        assert_eq!(n, 4);
+        assert_eq!(weights, vec![0.25, 0.5, 0.5, 1.0]);
+        assert_close(mean, (2.5 + 10.0 + 15.0 + 40.0) / 2.25);
+    }
+
+    #[test]
+    fn calculate_plot_params_uses_only_scores_above_weight_threshold() {
+        // 重み閾値以上のスコアのみを使って表示範囲が計算されることを確認する。
+        let scores = score_entries(&[10.0, 100.0, 20.0]);
+        let scheme = FixedWeights(vec![0.05, 0.2, 1.0]);
+
+        let params = calculate_plot_params(&scores, &scheme, ValueType::Integer);
+        assert_close(params.min_y, 12.0);
+        assert_close(params.max_y, 108.0);
+    }
+
+    #[test]
+    fn calculate_plot_params_falls_back_to_all_scores_when_filtered_empty() {
+        // 閾値フィルタ後に対象が空の場合は全スコアで表示範囲を再計算することを確認する。
+        let scores = score_entries(&[10.0, 30.0]);
+        let scheme = FixedWeights(vec![0.01, 0.02]);
+
+        let params = calculate_plot_params(&scores, &scheme, ValueType::Integer);
+        assert_close(params.min_y, 8.0);
+        assert_close(params.max_y, 32.0);
+    }
+
+    #[test]
+    fn calculate_plot_params_pads_flat_series_by_value_step() {
+        // すべて同じ値の場合に、値の種類の最小単位分の余白が上下に取られることを確認する。
+        let scores = score_entries(&[10.0, 10.0]);
+        let scheme = FixedWeights(vec![1.0, 1.0]);
+
+        let params = calculate_plot_params(&scores, &scheme, ValueType::Integer);
+        assert_close(params.min_y, 9.0);
+        assert_close(params.max_y, 11.0);
+
+        let params = calculate_plot_params(&scores, &scheme, ValueType::Decimal(1));
+        assert_close(params.min_y, 9.9);
+        assert_close(params.max_y, 10.1);
+    }
+
+    #[test]
+    fn calculate_plot_params_keeps_negative_range() {
+        // 負のスコアを含む場合は下限を 0 で打ち切らずに余白を取ることを確認する。
+        let scores = score_entries(&[-10.0, 10.0]);
+        let scheme = FixedWeights(vec![1.0, 1.0]);
+
+        let params = calculate_plot_params(&scores, &scheme, ValueType::Integer);
+        assert_close(params.min_y, -12.0);
+        assert_close(params.max_y, 12.0);
+    }
+
+    #[test]
+    fn calculate_plot_params_clamps_min_y_to_zero() {
+        // 表示範囲の下限が負値にならないよう 0.0 にクランプされることを確認する。
+        let scores = score_entries(&[0.0, 5.0]);
+        let scheme = FixedWeights(vec![1.0, 1.0]);
+
+        let params = calculate_plot_params(&scores, &scheme, ValueType::Integer);
+        assert_close(params.min_y, 0.0);
+        assert_close(params.max_y, 5.5);
+    }
+}
@@ -0,0 +1,162 @@
+use crate::constants::TREND_SIGNIFICANCE;
+use crate::domain::{ScoreDirection, ScoreEntry};
+use crate::utils::weighted_statistics::{WeightedRegression, weighted_linear_regression};
+
+use super::WeightingScheme;
+
+/// 1週間の秒数（記録日時の差を週単位にする）
+const SECONDS_PER_WEEK: f64 = 7.0 * 24.0 * 60.0 * 60.0;
+
+/// 傾向の判定
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrendDirection {
+    /// 良い向きに変化している
+    Improving,
+    /// 変化が標準誤差の範囲に収まる（件数が少なく判定できない場合も含む）
+    Flat,
+    /// 悪い向きに変化している
+    Declining,
+}
+
+impl TrendDirection {
+    /// 画面表示用の名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            TrendDirection::Improving => "改善傾向",
+            TrendDirection::Flat => "横ばい",
+            TrendDirection::Declining => "悪化傾向",
+        }
+    }
+}
+
+/// 重み付き回帰によるスコアの傾向
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScoreTrend {
+    /// 記録順（1回目を 0）に対する回帰直線。傾きは1回あたりの変化量
+    pub per_session: WeightedRegression,
+    /// 最初の記録からの経過週数に対する回帰直線（すべて同じ日時なら None）
+    pub per_week: Option<WeightedRegression>,
+    /// 1回あたりの傾きから判定した傾向
+    pub direction: TrendDirection,
+}
+
+/// 項目の重み付け方式の重みでスコアの傾向を求める
+///
+/// 重みのあるスコアが2件未満なら None を返す。1回あたりの傾きが標準誤差の
+/// [`TREND_SIGNIFICANCE`] 倍以内なら横ばいとし、それ以外は評価の向きに応じて改善・悪化とする。
+pub fn calculate_trend(
+    scores: &[ScoreEntry],
+    scheme: &dyn WeightingScheme,
+    direction: ScoreDirection,
+) -> Option<ScoreTrend> {
+    let first = scores.first()?;
+    let weights = scheme.weights(scores);
+    let values = scores.iter().map(|s| s.score).collect::<Vec<_>>();
+
+    let sessions = (0..scores.len()).map(|i| i as f64).collect::<Vec<_>>();
+    let per_session = weighted_linear_regression(&sessions, &values, &weights)?;
+
+    let weeks = scores
+        .iter()
+        .map(|s| (s.timestamp - first.timestamp).num_seconds() as f64 / SECONDS_PER_WEEK)
+        .collect::<Vec<_>>();
+    let per_week = weighted_linear_regression(&weeks, &values, &weights);
+
+    Some(ScoreTrend {
+        per_session,
+        per_week,
+        direction: judge(&per_session, direction),
+    })
+}
+
+fn judge(regression: &WeightedRegression, direction: ScoreDirection) -> TrendDirection {
+    let Some(std_error) = regression.slope_std_error else {
+        return TrendDirection::Flat;
+    };
+    let slope = regression.slope;
+    if slope.abs() <= std_error * TREND_SIGNIFICANCE {
+        return TrendDirection::Flat;
+    }
+    if direction.is_better(slope, 0.0) {
+        TrendDirection::Improving
+    } else {
+        TrendDirection::Declining
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::weighting::{Exponential, Uniform};
+    use chrono::{Duration, Utc};
+
+    /// 1日おきに記録したスコア
+    fn daily_scores(values: &[f64]) -> Vec<ScoreEntry> {
+        let start = Utc::now() - Duration::days(values.len() as i64 * 2);
+        values
+            .iter()
+            .enumerate()
+            .map(|(i, &score)| ScoreEntry {
+                score,
+                timestamp: start + Duration::days(i as i64 * 2),
+                note: None,
+                tags: Vec::new(),
+            })
+            .collect()
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        let diff = (actual - expected).abs();
+        assert!(
+            diff < 1e-9,
+            "expected {expected}, got {actual}, diff {diff}"
+        );
+    }
+
+    #[test]
+    fn trend_reports_slope_per_session_and_per_week() {
+        // 一定の割合で伸びるスコアは1回あたり・1週間あたりの傾きが求まり、改善傾向と判定されることを確認する。
+        let scores = daily_scores(&[10.0, 12.0, 14.0, 16.0, 18.0]);
+        let trend = calculate_trend(&scores, &Uniform, ScoreDirection::HigherIsBetter).unwrap();
+
+        assert_close(trend.per_session.slope, 2.0);
+        assert_close(trend.per_session.intercept, 10.0);
+        // 2日ごとに 2 増えるので 1週間では 7
+        assert_close(trend.per_week.unwrap().slope, 7.0);
+        assert_eq!(trend.direction, TrendDirection::Improving);
+
+        let lower = calculate_trend(&scores, &Uniform, ScoreDirection::LowerIsBetter).unwrap();
+        assert_eq!(lower.direction, TrendDirection::Declining);
+    }
+
+    #[test]
+    fn noisy_or_short_series_is_flat() {
+        // 傾きが標準誤差に埋もれる場合や件数が少なく判定できない場合は横ばいになり、1件以下なら None になることを確認する。
+        let noisy = daily_scores(&[10.0, 14.0, 9.0, 13.0, 11.0]);
+        let trend = calculate_trend(&noisy, &Uniform, ScoreDirection::HigherIsBetter).unwrap();
+        assert_eq!(trend.direction, TrendDirection::Flat);
+
+        let pair = daily_scores(&[10.0, 20.0]);
+        let trend = calculate_trend(&pair, &Uniform, ScoreDirection::HigherIsBetter).unwrap();
+        assert_close(trend.per_session.slope, 10.0);
+        assert_eq!(trend.direction, TrendDirection::Flat);
+
+        assert!(
+            calculate_trend(&daily_scores(&[10.0]), &Uniform, ScoreDirection::default()).is_none()
+        );
+    }
+
+    #[test]
+    fn recent_scores_dominate_decayed_trend() {
+        // 減衰の重みでは直近のスコアほど傾きに強く効くことを確認する。
+        let scores = daily_scores(&[50.0, 10.0, 20.0, 30.0]);
+        let uniform = calculate_trend(&scores, &Uniform, ScoreDirection::default()).unwrap();
+        let decayed = calculate_trend(
+            &scores,
+            &Exponential { decay_rate: 0.3 },
+            ScoreDirection::default(),
+        )
+        .unwrap();
+        assert!(decayed.per_session.slope > uniform.per_session.slope);
+    }
+}
@@ -0,0 +1,181 @@
+use crate::domain::{ItemData, ScoreEntry, WeightingMode};
+
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// スコア列に対する重みの付け方
+pub trait WeightingScheme {
+    /// スコアと同じ長さの重み配列を返す（記録順）
+    fn weights(&self, scores: &[ScoreEntry]) -> Vec<f64>;
+}
+
+/// 指数減衰: 最新を 1.0 とし、過去に遡るごとに減衰率を掛ける
+pub struct Exponential {
+    pub decay_rate: f64,
+}
+
+impl WeightingScheme for Exponential {
+    fn weights(&self, scores: &[ScoreEntry]) -> Vec<f64> {
+        (0..scores.len())
+            .map(|i| self.decay_rate.powi(i as i32))
+            .rev() // 最初のデータほど重みは少ない
+            .collect()
+    }
+}
+
+/// 直線減衰: 最新を 1.0 とし、最古が 1/N になるよう直線的に下げる
+pub struct LinearRamp;
+
+impl WeightingScheme for LinearRamp {
+    fn weights(&self, scores: &[ScoreEntry]) -> Vec<f64> {
+        let n = scores.len() as f64;
+        (1..=scores.len()).map(|i| i as f64 / n).collect()
+    }
+}
+
+/// 直近N件: 最新N件を 1.0、それ以前を 0.0 とする
+pub struct SlidingWindow {
+    pub size: usize,
+}
+
+impl WeightingScheme for SlidingWindow {
+    fn weights(&self, scores: &[ScoreEntry]) -> Vec<f64> {
+        let start = scores.len().saturating_sub(self.size);
+        (0..scores.len())
+            .map(|i| if i >= start { 1.0 } else { 0.0 })
+            .collect()
+    }
+}
+
+/// 均等: すべて 1.0
+pub struct Uniform;
+
+impl WeightingScheme for Uniform {
+    fn weights(&self, scores: &[ScoreEntry]) -> Vec<f64> {
+        vec![1.0; scores.len()]
+    }
+}
+
+/// 経過日数: 最新の記録を 1.0 とし、半減期ごとに半分にする
+pub struct HalfLife {
+    pub half_life_days: f64,
+}
+
+impl WeightingScheme for HalfLife {
+    fn weights(&self, scores: &[ScoreEntry]) -> Vec<f64> {
+        let Some(latest) = scores.iter().map(|s| s.timestamp).max() else {
+            return Vec::new();
+        };
+
+        scores
+            .iter()
+            .map(|entry| {
+                let age_days = (latest - entry.timestamp).num_seconds() as f64 / SECONDS_PER_DAY;
+                0.5_f64.powf(age_days / self.half_life_days)
+            })
+            .collect()
+    }
+}
+
+/// 項目の設定に対応する重み付け方式
+pub fn scheme_for(item: &ItemData) -> Box<dyn WeightingScheme> {
+    match item.weighting {
+        WeightingMode::Exponential => Box::new(Exponential {
+            decay_rate: item.decay_rate,
+        }),
+        WeightingMode::LinearRamp => Box::new(LinearRamp),
+        WeightingMode::SlidingWindow { size } => Box::new(SlidingWindow { size }),
+        WeightingMode::Uniform => Box::new(Uniform),
+        WeightingMode::TimeDecay { half_life_days } => Box::new(HalfLife { half_life_days }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::ValueType;
+    use chrono::{Duration, Utc};
+
+    fn score_entries(n: usize) -> Vec<ScoreEntry> {
+        (0..n)
+            .map(|i| ScoreEntry {
+                score: i as f64,
+                timestamp: Utc::now(),
+                note: None,
+                tags: Vec::new(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn exponential_multiplies_decay_rate_backwards() {
+        // 指数減衰では最新が 1.0 で、過去に遡るごとに減衰率が掛かることを確認する。
+        let weights = Exponential { decay_rate: 0.5 }.weights(&score_entries(3));
+        assert_eq!(weights, vec![0.25, 0.5, 1.0]);
+    }
+
+    #[test]
+    fn linear_ramp_decreases_linearly() {
+        // 直線減衰では最新が 1.0、最古が 1/N になり等間隔で並ぶことを確認する。
+        let weights = LinearRamp.weights(&score_entries(4));
+        assert_eq!(weights, vec![0.25, 0.5, 0.75, 1.0]);
+    }
+
+    #[test]
+    fn sliding_window_keeps_only_latest_entries() {
+        // 直近N件のみ 1.0 となり、件数がN未満ならすべて 1.0 になることを確認する。
+        let scheme = SlidingWindow { size: 2 };
+        assert_eq!(scheme.weights(&score_entries(4)), vec![0.0, 0.0, 1.0, 1.0]);
+        assert_eq!(scheme.weights(&score_entries(1)), vec![1.0]);
+    }
+
+    #[test]
+    fn uniform_gives_equal_weights() {
+        // 均等ではすべてのスコアの重みが 1.0 になることを確認する。
+        assert_eq!(Uniform.weights(&score_entries(3)), vec![1.0, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn half_life_halves_weight_per_period() {
+        // 経過日数では最新の記録から半減期ごとに重みが半分になることを確認する。
+        let latest = Utc::now();
+        let mut scores = score_entries(3);
+        scores[0].timestamp = latest - Duration::days(20);
+        scores[1].timestamp = latest - Duration::days(10);
+        scores[2].timestamp = latest;
+
+        let weights = HalfLife {
+            half_life_days: 10.0,
+        }
+        .weights(&scores);
+        assert_eq!(weights, vec![0.25, 0.5, 1.0]);
+    }
+
+    #[test]
+    fn schemes_return_empty_for_empty_scores() {
+        // どの方式でもスコアが空なら重みも空になることを確認する。
+        let schemes: Vec<Box<dyn WeightingScheme>> = vec![
+            Box::new(Exponential { decay_rate: 0.9 }),
+            Box::new(LinearRamp),
+            Box::new(SlidingWindow { size: 3 }),
+            Box::new(Uniform),
+            Box::new(HalfLife {
+                half_life_days: 7.0,
+            }),
+        ];
+        assert!(schemes.iter().all(|s| s.weights(&[]).is_empty()));
+    }
+
+    #[test]
+    fn scheme_for_follows_item_setting() {
+        // 項目の重み付け設定に応じた方式が選ばれることを確認する。
+        let mut item = ItemData::new("Item".to_string(), 0.5, ValueType::Integer);
+        item.scores = score_entries(3);
+        assert_eq!(
+            scheme_for(&item).weights(&item.scores),
+            vec![0.25, 0.5, 1.0]
+        );
+
+        item.weighting = WeightingMode::SlidingWindow { size: 1 };
+        assert_eq!(scheme_for(&item).weights(&item.scores), vec![0.0, 0.0, 1.0]);
+    }
+}
@@ -1,22 +1,65 @@
-// リリースビルドでコンソール非表示
-#![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
+// リリースビルドでコンソール非表示（画面なしでビルドした場合はコマンドの出力のため表示する）
+#![cfg_attr(
+    all(not(debug_assertions), feature = "gui"),
+    windows_subsystem = "windows"
+)]
 
+#[cfg(feature = "gui")]
 mod action;
+#[cfg(feature = "gui")]
 mod app;
-mod application;
-mod constants;
-mod domain;
-mod infrastructure;
-mod logic;
+mod cli;
+mod launch;
+#[cfg(feature = "server")]
+mod server;
+#[cfg(feature = "gui")]
 mod ui;
-mod utils;
 
-use app::WeightedScoreTracker;
-use constants::{WINDOW_HEIGHT, WINDOW_WIDTH};
-use eframe::egui;
-use ui::fonts::setup_custom_fonts;
+// 画面とコマンドラインのモジュールからは crate:: 直下と同じように参照する
+use weighted_score_tracker::{application, constants, domain, infrastructure, logic, utils};
+
+use infrastructure::DataLocation;
+use launch::LaunchOptions;
+use std::io::Write;
+
+fn main() {
+    let mut launch = match LaunchOptions::parse(std::env::args().skip(1)) {
+        Ok(launch) => launch,
+        Err(msg) => {
+            eprintln!("{}", msg);
+            std::process::exit(2);
+        }
+    };
+
+    // サブコマンドが指定されていれば画面を開かずに実行する
+    if let Some(request) = launch.command.take() {
+        let location = DataLocation::resolve(launch.data_dir);
+        match cli::run(&location, launch.profile.as_deref(), &request) {
+            // パイプ先が閉じられていても異常終了しない
+            Ok(output) => {
+                let _ = writeln!(std::io::stdout(), "{}", output);
+            }
+            Err(err) => {
+                eprintln!("{}", err);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    if let Err(msg) = run_gui(launch) {
+        eprintln!("{}", msg);
+        std::process::exit(1);
+    }
+}
+
+#[cfg(feature = "gui")]
+fn run_gui(launch: LaunchOptions) -> Result<(), String> {
+    use app::WeightedScoreTracker;
+    use eframe::egui;
+    use ui::constants::{WINDOW_HEIGHT, WINDOW_WIDTH};
+    use ui::fonts::setup_custom_fonts;
 
-fn main() -> eframe::Result<()> {
     // ウィンドウ設定
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default().with_inner_size([WINDOW_WIDTH, WINDOW_HEIGHT]),
@@ -28,7 +71,16 @@ fn main() -> eframe::Result<()> {
         options,
         Box::new(|cc| {
             setup_custom_fonts(&cc.egui_ctx);
-            Ok(Box::new(WeightedScoreTracker::new(cc)))
+            Ok(Box::new(WeightedScoreTracker::new(cc, launch)))
         }),
     )
+    .map_err(|e| e.to_string())
+}
+
+#[cfg(not(feature = "gui"))]
+fn run_gui(_launch: LaunchOptions) -> Result<(), String> {
+    Err(format!(
+        "画面なし（gui 機能を無効にして）ビルドされているため、コマンドのみ使用できます。\n{}",
+        launch::USAGE
+    ))
 }
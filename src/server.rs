@@ -0,0 +1,467 @@
+use std::io::Read;
+use std::time::{Duration, Instant};
+
+use percent_encoding::percent_decode_str;
+use serde::{Deserialize, Serialize};
+use tiny_http::{Header, Request, Response, Server};
+
+use crate::application::{AppError, ConflictResolution, DataStore, TrackerService};
+use crate::cli::{AddedScore, ItemStats, category_listing, find_item, to_json};
+use crate::constants::{LOCK_REFRESH_SECS, SERVER_MAX_BODY_BYTES};
+use crate::domain::ItemData;
+use crate::infrastructure::DataLock;
+
+/// 待ち受けを中断してロックを更新する間隔
+const RECV_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Host・Origin に指定できるホスト名（このマシン自身を指すもののみ）
+const LOOPBACK_HOSTS: [&str; 3] = ["127.0.0.1", "localhost", "[::1]"];
+
+/// HTTP の応答（状態コードと JSON の本文）
+#[derive(Debug, PartialEq)]
+pub struct Reply {
+    pub status: u16,
+    pub body: String,
+}
+
+impl Reply {
+    fn json<T: Serialize + ?Sized>(status: u16, value: &T) -> Self {
+        match to_json(value) {
+            Ok(body) => Self { status, body },
+            Err(err) => Self::error(500, &err.to_string()),
+        }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Self::json(status, &ErrorBody { error: message })
+    }
+
+    /// エラーの種類に応じた状態コードで応答する
+    fn from_app_error(err: &AppError) -> Self {
+        let status = match err {
+            AppError::Input(_) => 400,
+            AppError::Domain(_) => 422,
+            AppError::Conflict(_) => 409,
+            AppError::Persistence(_) => 503,
+        };
+        Self::error(status, &err.to_string())
+    }
+}
+
+#[derive(Serialize)]
+struct ErrorBody<'a> {
+    error: &'a str,
+}
+
+/// 送り主の確認に使うリクエストヘッダー
+#[derive(Debug, Default)]
+pub struct RequestHeaders {
+    /// 接続先として指定されたホスト名（ポート付き）
+    pub host: Option<String>,
+    /// ブラウザが付ける要求元のサイト
+    pub origin: Option<String>,
+    /// 本文の形式
+    pub content_type: Option<String>,
+}
+
+impl RequestHeaders {
+    fn of(request: &Request) -> Self {
+        let find = |name: &'static str| {
+            request
+                .headers()
+                .iter()
+                .find(|header| header.field.equiv(name))
+                .map(|header| header.value.as_str().to_string())
+        };
+        Self {
+            host: find("Host"),
+            origin: find("Origin"),
+            content_type: find("Content-Type"),
+        }
+    }
+}
+
+/// スコア追加のリクエスト本文（スコア以外は省略可。日時は画面と同じ形式、空なら現在時刻）
+#[derive(Deserialize)]
+struct NewScore {
+    score: ScoreValue,
+    #[serde(default)]
+    timestamp: String,
+    #[serde(default)]
+    note: String,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+/// スコアは数値・文字列のどちらでも受け付け、画面の入力と同じ検証にかける
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ScoreValue {
+    Number(f64),
+    Text(String),
+}
+
+impl ScoreValue {
+    fn input(&self) -> String {
+        match self {
+            ScoreValue::Number(value) => value.to_string(),
+            ScoreValue::Text(text) => text.clone(),
+        }
+    }
+}
+
+/// 127.0.0.1 で HTTP API を待ち受ける（終了するまで戻らない）
+///
+/// - `GET /categories`: カテゴリと項目の一覧
+/// - `GET /categories/{カテゴリ}/items/{項目}/scores`: スコア履歴
+/// - `POST /categories/{カテゴリ}/items/{項目}/scores`: スコアの記録
+/// - `GET /categories/{カテゴリ}/items/{項目}/stats`: 加重平均などの統計値
+pub fn serve<S: DataStore>(
+    mut service: TrackerService<S>,
+    mut lock: Option<DataLock>,
+    port: u16,
+) -> Result<(), AppError> {
+    let server = Server::http(("127.0.0.1", port)).map_err(|e| {
+        AppError::Persistence(format!("ポート {} で待ち受けできません: {}", port, e))
+    })?;
+    eprintln!(
+        "http://127.0.0.1:{}/ で待ち受けています（Ctrl+C で終了）。",
+        port
+    );
+
+    let mut last_lock_refresh = Instant::now();
+    loop {
+        if let Some(held) = &lock
+            && last_lock_refresh.elapsed() >= Duration::from_secs(LOCK_REFRESH_SECS)
+        {
+            match held.refresh() {
+                Ok(()) => {}
+                // ロックを失った後は参照だけ受け付ける
+                Err(AppError::Conflict(reason)) => {
+                    eprintln!("{}", reason);
+                    service.make_read_only(reason);
+                    lock = None;
+                }
+                Err(err) => eprintln!("{}", err),
+            }
+            last_lock_refresh = Instant::now();
+        }
+
+        let mut request = match server.recv_timeout(RECV_TIMEOUT) {
+            Ok(Some(request)) => request,
+            Ok(None) => continue,
+            Err(e) => {
+                return Err(AppError::Persistence(format!(
+                    "リクエストを受け付けられません: {}",
+                    e
+                )));
+            }
+        };
+
+        let headers = RequestHeaders::of(&request);
+        let reply = match check_headers(request.method().as_str(), &headers)
+            .and_then(|()| read_body(&mut request))
+        {
+            Ok(body) => handle(
+                &mut service,
+                request.method().as_str(),
+                request.url(),
+                &body,
+            ),
+            Err(reply) => reply,
+        };
+        respond(request, reply);
+        if let Some(warning) = service.take_backup_warning() {
+            eprintln!("{}", warning);
+        }
+    }
+}
+
+/// ブラウザ経由の要求を拒否する
+///
+/// ほかのサイトのページから送られる要求（Origin が別のサイト）や、DNS リバインディングで
+/// 別の名前から届く要求（Host がこのマシンを指さない）は受け付けない。POST は JSON の本文のみ受け付け、
+/// ブラウザが事前確認なしに送れる text/plain などのフォーム送信を防ぐ。
+pub fn check_headers(method: &str, headers: &RequestHeaders) -> Result<(), Reply> {
+    if !headers.host.as_deref().is_some_and(is_loopback_host) {
+        return Err(Reply::error(
+            403,
+            "Host が 127.0.0.1 または localhost のリクエストのみ受け付けます。",
+        ));
+    }
+    if let Some(origin) = &headers.origin {
+        let loopback = origin.strip_prefix("http://").is_some_and(is_loopback_host);
+        if !loopback {
+            return Err(Reply::error(
+                403,
+                "ほかのサイトからのリクエストは受け付けません。",
+            ));
+        }
+    }
+    if method == "POST" {
+        let media_type = headers
+            .content_type
+            .as_deref()
+            .and_then(|value| value.split(';').next())
+            .unwrap_or_default()
+            .trim();
+        if !media_type.eq_ignore_ascii_case("application/json") {
+            return Err(Reply::error(
+                415,
+                "本文は Content-Type: application/json で送ってください。",
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// "ホスト名[:ポート]" がこのマシン自身を指すかどうか
+fn is_loopback_host(authority: &str) -> bool {
+    let host = match authority.find(']') {
+        Some(end) => &authority[..=end],
+        None => authority.split(':').next().unwrap_or_default(),
+    };
+    LOOPBACK_HOSTS
+        .iter()
+        .any(|loopback| host.eq_ignore_ascii_case(loopback))
+}
+
+fn read_body(request: &mut Request) -> Result<String, Reply> {
+    let mut body = Vec::new();
+    request
+        .as_reader()
+        .take(SERVER_MAX_BODY_BYTES as u64 + 1)
+        .read_to_end(&mut body)
+        .map_err(|e| Reply::error(400, &format!("本文を読み込めません: {}", e)))?;
+    if body.len() > SERVER_MAX_BODY_BYTES {
+        return Err(Reply::error(413, "本文が大きすぎます。"));
+    }
+    String::from_utf8(body).map_err(|_| Reply::error(400, "本文は UTF-8 で送ってください。"))
+}
+
+fn respond(request: Request, reply: Reply) {
+    let content_type = Header::from_bytes("Content-Type", "application/json; charset=utf-8")
+        .expect("valid header");
+    let response = Response::from_string(reply.body)
+        .with_status_code(reply.status)
+        .with_header(content_type);
+    // 応答前に切断された場合は何もしない
+    let _ = request.respond(response);
+}
+
+/// 1件のリクエストを処理する
+pub fn handle<S: DataStore>(
+    service: &mut TrackerService<S>,
+    method: &str,
+    url: &str,
+    body: &str,
+) -> Reply {
+    if let Err(err) = sync(service) {
+        return Reply::from_app_error(&err);
+    }
+
+    let path = url.split('?').next().unwrap_or_default();
+    let segments: Vec<String> = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| percent_decode_str(segment).decode_utf8_lossy().into_owned())
+        .collect();
+    let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+
+    match (method, segments.as_slice()) {
+        ("GET", ["categories"]) => Reply::json(200, &category_listing(service.model())),
+        ("GET", ["categories", category, "items", item, "scores"]) => {
+            match locate(service, category, item) {
+                Ok(item) => Reply::json(200, &item.scores),
+                Err(reply) => reply,
+            }
+        }
+        ("POST", ["categories", category, "items", item, "scores"]) => {
+            add_score(service, category, item, body)
+        }
+        ("GET", ["categories", category, "items", item, "stats"]) => {
+            match locate(service, category, item) {
+                Ok(item_data) => Reply::json(200, &ItemStats::of(category, item_data)),
+                Err(reply) => reply,
+            }
+        }
+        (_, ["categories"] | ["categories", _, "items", _, "scores" | "stats"]) => {
+            Reply::error(405, "このメソッドは使用できません。")
+        }
+        _ => Reply::error(404, "見つかりません。"),
+    }
+}
+
+/// ほかのプログラムによる変更を読み込み直す
+///
+/// 画面がないため、競合した場合はどちらの変更も残す統合で解決する。
+fn sync<S: DataStore>(service: &mut TrackerService<S>) -> Result<(), AppError> {
+    service.check_external_changes()?;
+    if service.conflict().is_some() {
+        service.resolve_conflict(ConflictResolution::Merge)?;
+    }
+    Ok(())
+}
+
+fn locate<'a, S: DataStore>(
+    service: &'a TrackerService<S>,
+    category: &str,
+    item: &str,
+) -> Result<&'a ItemData, Reply> {
+    find_item(service, category, item).map_err(|err| Reply::error(404, &err.to_string()))
+}
+
+fn add_score<S: DataStore>(
+    service: &mut TrackerService<S>,
+    category: &str,
+    item: &str,
+    body: &str,
+) -> Reply {
+    if let Err(reply) = locate(service, category, item) {
+        return reply;
+    }
+    let new_score: NewScore = match serde_json::from_str(body) {
+        Ok(new_score) => new_score,
+        Err(e) => return Reply::error(400, &format!("本文の JSON を解釈できません: {}", e)),
+    };
+
+    let added = service
+        .add_score_to_item(
+            (category, item),
+            &new_score.score.input(),
+            &new_score.timestamp,
+            new_score.note,
+            &new_score.tags.join(","),
+        )
+        .and_then(|entry| sync(service).map(|()| entry));
+    match added {
+        Ok(entry) => Reply::json(
+            201,
+            &AddedScore {
+                category,
+                item,
+                entry: &entry,
+            },
+        ),
+        Err(err) => Reply::from_app_error(&err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::JsonFileStore;
+    use std::path::PathBuf;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    /// カテゴリ「勉強」に項目「英語」を作ったデータファイルを開く
+    fn seeded_service(name: &str) -> (TrackerService<JsonFileStore>, PathBuf) {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time went backwards")
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("{name}-{unique}"));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut service =
+            TrackerService::try_open(JsonFileStore::new(dir.join("data.json"))).unwrap();
+        service.add_category("勉強".to_string()).unwrap();
+        let cat = service.model().data.find_category_id("勉強").unwrap();
+        service
+            .add_item(cat, "英語".to_string(), "0.9", Default::default())
+            .unwrap();
+        (service, dir)
+    }
+
+    const SCORES_URL: &str = "/categories/%E5%8B%89%E5%BC%B7/items/%E8%8B%B1%E8%AA%9E/scores";
+
+    fn json(reply: &Reply) -> serde_json::Value {
+        serde_json::from_str(&reply.body).unwrap()
+    }
+
+    #[test]
+    fn scores_are_recorded_and_read_through_the_api() {
+        // POST したスコアが保存され、一覧・履歴・統計値として JSON で返ることを確認する。
+        let (mut service, dir) = seeded_service("server-add");
+
+        let body = r#"{"score": 80, "timestamp": "2024-01-02 03:04", "note": "小テスト", "tags": ["a", "b"]}"#;
+        let reply = handle(&mut service, "POST", SCORES_URL, body);
+        assert_eq!(reply.status, 201, "{}", reply.body);
+        assert_eq!(json(&reply)["score"], 80.0);
+        handle(&mut service, "POST", SCORES_URL, r#"{"score": "90"}"#);
+
+        let reopened =
+            &mut TrackerService::try_open(JsonFileStore::new(dir.join("data.json"))).unwrap();
+        let history = handle(reopened, "GET", SCORES_URL, "");
+        assert_eq!(history.status, 200);
+        assert_eq!(json(&history)[0]["note"], "小テスト");
+        assert_eq!(json(&history)[0]["tags"], serde_json::json!(["a", "b"]));
+
+        let stats_url = SCORES_URL.replace("/scores", "/stats");
+        let stats = handle(reopened, "GET", &stats_url, "");
+        assert_eq!(json(&stats)["count"], 2);
+
+        let listing = handle(reopened, "GET", "/categories", "");
+        assert_eq!(json(&listing)[0]["items"][0]["name"], "英語");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    fn headers(host: &str, origin: Option<&str>, content_type: Option<&str>) -> RequestHeaders {
+        RequestHeaders {
+            host: Some(host.to_string()),
+            origin: origin.map(str::to_string),
+            content_type: content_type.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn requests_from_other_sites_are_rejected() {
+        // 別のサイトの Origin・このマシンを指さない Host・JSON 以外の POST は拒否し、ローカルからの要求は通すことを確認する。
+        let json = Some("application/json; charset=utf-8");
+        assert!(check_headers("POST", &headers("127.0.0.1:8765", None, json)).is_ok());
+        assert!(check_headers("GET", &headers("localhost:8765", None, None)).is_ok());
+        let local_origin = Some("http://localhost:3000");
+        assert!(check_headers("POST", &headers("127.0.0.1:8765", local_origin, json)).is_ok());
+
+        let plain = check_headers("POST", &headers("127.0.0.1:8765", None, Some("text/plain")));
+        assert_eq!(plain.unwrap_err().status, 415);
+        let missing = check_headers("POST", &headers("127.0.0.1:8765", None, None));
+        assert_eq!(missing.unwrap_err().status, 415);
+
+        let foreign = Some("https://evil.example");
+        let cross_site = check_headers("POST", &headers("127.0.0.1:8765", foreign, json));
+        assert_eq!(cross_site.unwrap_err().status, 403);
+        let lookalike = Some("http://localhost.evil.example");
+        assert!(check_headers("GET", &headers("127.0.0.1:8765", lookalike, None)).is_err());
+
+        let rebound = check_headers("GET", &headers("evil.example:8765", None, None));
+        assert_eq!(rebound.unwrap_err().status, 403);
+        assert!(check_headers("GET", &RequestHeaders::default()).is_err());
+    }
+
+    #[test]
+    fn errors_are_reported_with_status_codes() {
+        // 入力エラー・存在しない項目・未定義の経路・使えないメソッドがそれぞれの状態コードになることを確認する。
+        let (mut service, dir) = seeded_service("server-errors");
+
+        let invalid = handle(&mut service, "POST", SCORES_URL, r#"{"score": 1.5}"#);
+        assert_eq!(invalid.status, 400);
+        assert!(json(&invalid)["error"].is_string());
+        assert_eq!(handle(&mut service, "POST", SCORES_URL, "{").status, 400);
+
+        let missing = "/categories/%E5%8B%89%E5%BC%B7/items/none/scores";
+        assert_eq!(handle(&mut service, "GET", missing, "").status, 404);
+        assert_eq!(handle(&mut service, "GET", "/unknown", "").status, 404);
+        assert_eq!(handle(&mut service, "DELETE", SCORES_URL, "").status, 405);
+
+        let item = service.find_item("勉強", "英語").unwrap();
+        assert!(
+            service
+                .model()
+                .get_item(item.0, item.1)
+                .unwrap()
+                .scores
+                .is_empty()
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,28 @@
+use crate::action::Action;
+use eframe::egui;
+
+/// 読み取り専用バナー（ほかのインスタンスが使用中のため変更を保存しない）
+pub fn show_read_only(ctx: &egui::Context, reason: &str, enabled: bool) -> Option<Action> {
+    let mut action = None;
+
+    egui::TopBottomPanel::top("read_only_banner").show(ctx, |ui| {
+        if !enabled {
+            ui.disable();
+        }
+
+        ui.horizontal_wrapped(|ui| {
+            ui.label(
+                egui::RichText::new("🔒 読み取り専用")
+                    .strong()
+                    .color(egui::Color32::from_rgb(230, 160, 40)),
+            );
+            ui.label(reason);
+            ui.label("変更は保存されません（ほかのウィンドウでの変更は自動で読み込みます）。");
+            if ui.button("再試行").clicked() {
+                action = Some(Action::RetryLock);
+            }
+        });
+    });
+
+    action
+}
@@ -0,0 +1,84 @@
+use crate::action::Action;
+use crate::constants::CATEGORY_AGGREGATE_PRECISION;
+use crate::domain::{CategoryData, CategoryId};
+use crate::logic::summarize_category;
+use crate::utils::comma_display::CommaDisplay;
+use eframe::egui;
+
+/// カテゴリ概要（項目ごとの加重平均と重要度、総合スコア）
+pub struct CategorySummaryView<'a> {
+    cat_id: CategoryId,
+    category: &'a CategoryData,
+}
+
+impl<'a> CategorySummaryView<'a> {
+    pub fn new(cat_id: CategoryId, category: &'a CategoryData) -> Self {
+        Self { cat_id, category }
+    }
+
+    pub fn show(&self, ui: &mut egui::Ui) -> Option<Action> {
+        let mut action = None;
+        let summary = summarize_category(self.category);
+
+        // ヘッダー
+        ui.horizontal(|ui| {
+            ui.label(egui::RichText::new(&self.category.name).size(16.0).strong());
+            let aggregate = summary.aggregate.map_or("-".to_string(), |value| {
+                value.to_comma_fmt(CATEGORY_AGGREGATE_PRECISION)
+            });
+            ui.label(
+                egui::RichText::new(format!("総合スコア: {}", aggregate))
+                    .size(16.0)
+                    .strong(),
+            );
+        });
+        ui.label(
+            egui::RichText::new(
+                "各項目の加重平均をスコアの範囲で 0〜100 に換算し（低いほど良い項目は反転）、重要度で加重平均した値です（スコアのない項目は除外）",
+            )
+            .size(10.0)
+            .color(egui::Color32::GRAY),
+        );
+        ui.separator();
+
+        if summary.items.is_empty() {
+            ui.label("項目はありません");
+            return None;
+        }
+
+        egui::Grid::new("category_summary_grid")
+            .num_columns(6)
+            .striped(true)
+            .spacing([20.0, 6.0])
+            .show(ui, |ui| {
+                ui.label(egui::RichText::new("項目").strong());
+                ui.label(egui::RichText::new("加重平均").strong());
+                ui.label(egui::RichText::new("換算値").strong());
+                ui.label(egui::RichText::new("重要度").strong());
+                ui.label(egui::RichText::new("データ数").strong());
+                ui.label("");
+                ui.end_row();
+
+                summary.items.iter().for_each(|item| {
+                    // 項目名クリックでその項目を開く
+                    if ui.link(&item.name).clicked() {
+                        action = Some(Action::SelectItem(self.cat_id, item.id));
+                    }
+                    ui.label(item.average.map_or("-".to_string(), |avg| {
+                        avg.to_comma_fmt(item.value_type.stats_precision())
+                    }));
+                    ui.label(item.normalized.map_or("-".to_string(), |value| {
+                        value.to_comma_fmt(CATEGORY_AGGREGATE_PRECISION)
+                    }));
+                    ui.label(item.importance.to_string());
+                    ui.label(item.count.to_string());
+                    if ui.button("設定").clicked() {
+                        action = Some(Action::ShowEditItemModal(self.cat_id, item.id));
+                    }
+                    ui.end_row();
+                });
+            });
+
+        action
+    }
+}
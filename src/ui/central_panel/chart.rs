@@ -1,36 +1,57 @@
-use crate::constants::BAR_BASE_COLOR;
-use crate::domain::ScoreEntry;
-use crate::logic::{PlotParams, calculate_plot_params, calculate_stats};
-use crate::utils::comma_display::CommaDisplay;
+use crate::domain::{ItemData, ScoreDirection, ScoreEntry, ValueType};
+use crate::logic::{
+    PlotParams, WeightingScheme, calculate_plot_params, calculate_stats, calculate_trend,
+    scheme_for,
+};
+use crate::ui::constants::{BAR_BASE_COLOR, PERSONAL_BEST_COLOR, TREND_LINE_COLOR};
+use crate::utils::weighted_statistics::WeightedRegression;
 use eframe::egui;
 use egui_plot::{Bar, BarChart, Corner, Legend, Plot, PlotUi};
 use std::iter::zip;
 
-pub struct WeightedScoreChart;
+pub struct WeightedScoreChart<'a> {
+    scores: &'a [ScoreEntry],
+    scheme: Box<dyn WeightingScheme>,
+    value_type: ValueType,
+    direction: ScoreDirection,
+    best_index: Option<usize>,
+}
 
-impl WeightedScoreChart {
-    pub fn new() -> Self {
-        Self
+impl<'a> WeightedScoreChart<'a> {
+    pub fn new(item_data: &'a ItemData) -> Self {
+        Self {
+            scores: &item_data.scores,
+            scheme: scheme_for(item_data),
+            value_type: item_data.value_type,
+            direction: item_data.direction,
+            best_index: item_data.personal_best_index(),
+        }
     }
 
     pub fn show(
         &self,
         ui: &mut egui::Ui,
-        scores: &[ScoreEntry],
-        decay_rate: f64,
         show_average_line: bool,
+        show_trend_line: bool,
         selected_index: &mut Option<usize>,
         scroll_req_index: &mut Option<usize>,
     ) {
+        let scores = self.scores;
+
         // 統計計算
-        let (avg, _, _, weights) = calculate_stats(scores, decay_rate);
-        let params = calculate_plot_params(scores, &weights);
+        let (avg, _, _, _) = calculate_stats(scores, self.scheme.as_ref());
+        let trend = show_trend_line
+            .then(|| calculate_trend(scores, self.scheme.as_ref(), self.direction))
+            .flatten()
+            .map(|trend| trend.per_session);
+        let params = calculate_plot_params(scores, self.scheme.as_ref(), self.value_type);
 
         // バーとクリック判定境界の作成
-        let (bars, boundaries) = self.create_bars(scores, &weights, *selected_index);
+        let (bars, boundaries) = self.create_bars(scores, *selected_index);
 
         // プロット、クリック処理
-        let clicked_idx = self.draw_plot(ui, bars, &boundaries, avg, &params, show_average_line);
+        let avg = show_average_line.then_some(avg);
+        let clicked_idx = self.draw_plot(ui, bars, &boundaries, &params, avg, trend);
 
         // クリック結果
         if let Some(idx) = clicked_idx {
@@ -42,23 +63,28 @@ impl WeightedScoreChart {
     fn create_bars(
         &self,
         scores: &[ScoreEntry],
-        weights: &[f64],
         selected_index: Option<usize>,
     ) -> (Vec<Bar>, Vec<f64>) {
+        let weights = self.scheme.weights(scores);
         let mut boundaries = Vec::new(); // クリック判定用のバー範囲記録
         let mut current_x = 0.0; // 棒グラフの合計横幅記録用
 
-        let base_color = BAR_BASE_COLOR; // バーカラー
-
         // let bar_base = params.bar_base;
         let bars = zip(scores.iter(), weights.iter())
             .enumerate()
             .map(|(i, (entry, &weight))| {
                 let width = weight; // 重みがそのまま横幅
-                let height = entry.score as f64;
+                let height = entry.score;
 
                 let center_x = current_x + (width / 2.0);
 
+                // 自己ベストは別色で強調
+                let base_color = if self.best_index == Some(i) {
+                    PERSONAL_BEST_COLOR
+                } else {
+                    BAR_BASE_COLOR
+                };
+
                 let is_selected = selected_index == Some(i);
                 let bar_color = if is_selected {
                     base_color // 選択時は濃く
@@ -66,9 +92,9 @@ impl WeightedScoreChart {
                     base_color.gamma_multiply(0.4) // 通常は薄く
                 };
 
-                let bar = Bar::new(center_x, height.max(0.0))
+                let bar = Bar::new(center_x, height)
                     .width(width)
-                    .name(format!("{}回目", i + 1))
+                    .name(format!("{}回目{}", i + 1, score_annotation(entry)))
                     .fill(bar_color)
                     .stroke(egui::Stroke::new(1.0, base_color));
 
@@ -87,9 +113,9 @@ impl WeightedScoreChart {
         ui: &mut egui::Ui,
         bars: Vec<Bar>,
         boundaries: &[f64],
-        avg: f64,
         params: &PlotParams,
-        show_average_line: bool,
+        avg: Option<f64>,
+        trend: Option<WeightedRegression>,
     ) -> Option<usize> {
         let plot_height = ui.available_height() * 0.6; // 画面の縦幅6割を使用
         let plot = Plot::new("score_plot")
@@ -105,11 +131,15 @@ impl WeightedScoreChart {
             .include_y(params.min_y);
 
         let total_width = bars.iter().map(|bar| bar.bar_width).sum();
+        let centers = bars.iter().map(|bar| bar.argument).collect::<Vec<_>>();
         plot.show(ui, |plot_ui| {
             self.show_bars(plot_ui, bars);
-            if show_average_line {
+            if let Some(avg) = avg {
                 self.show_average_line(plot_ui, avg, total_width);
             }
+            if let Some(trend) = &trend {
+                self.show_trend_line(plot_ui, trend, &centers);
+            }
             self.check_click(plot_ui, boundaries, total_width)
         })
         .inner
@@ -117,12 +147,13 @@ impl WeightedScoreChart {
 
     /// 棒グラフ描画
     fn show_bars(&self, plot_ui: &mut PlotUi, bars: Vec<Bar>) {
+        let value_type = self.value_type;
         plot_ui.bar_chart(
             BarChart::new("スコア", bars)
                 .highlight(false)
                 .color(BAR_BASE_COLOR) // 凡例のカラー設定
-                .element_formatter(Box::new(|bar, _| {
-                    format!("{}\nスコア: {}", bar.name, bar.value.to_comma_fmt(0))
+                .element_formatter(Box::new(move |bar, _| {
+                    format!("{}\nスコア: {}", bar.name, value_type.format(bar.value))
                 })),
         );
     }
@@ -139,6 +170,21 @@ impl WeightedScoreChart {
         );
     }
 
+    /// 傾向線描画（各回のバーの中央に回帰直線の値を取る）
+    fn show_trend_line(&self, plot_ui: &mut PlotUi, trend: &WeightedRegression, centers: &[f64]) {
+        let line_points = centers
+            .iter()
+            .enumerate()
+            .map(|(i, &x)| [x, trend.value_at(i as f64)])
+            .collect::<Vec<_>>();
+        plot_ui.line(
+            egui_plot::Line::new("傾向", egui_plot::PlotPoints::new(line_points))
+                .color(TREND_LINE_COLOR)
+                .highlight(false)
+                .allow_hover(false),
+        );
+    }
+
     /// クリック判定
     fn check_click(&self, plot_ui: &PlotUi, boundaries: &[f64], width: f64) -> Option<usize> {
         let clicked = plot_ui.response().clicked();
@@ -155,6 +201,18 @@ impl WeightedScoreChart {
     }
 }
 
+/// ツールチップに追記するメモ・タグ
+fn score_annotation(entry: &ScoreEntry) -> String {
+    let mut text = String::new();
+    if let Some(note) = &entry.note {
+        text.push_str(&format!("\nメモ: {}", note));
+    }
+    if !entry.tags.is_empty() {
+        text.push_str(&format!("\nタグ: {}", entry.tags.join(", ")));
+    }
+    text
+}
+
 /// x座標がどのバーに属するか判定
 fn find_clicked_bar(x: f64, boundaries: &[f64]) -> Option<usize> {
     // クリック場所が負の場合は範囲外確定
@@ -1,22 +1,56 @@
 use crate::action::Action;
-use crate::domain::ScoreEntry;
-use crate::utils::comma_display::CommaDisplay;
+use crate::domain::{ItemData, ScoreEntry, ValueType};
 use eframe::egui;
 use eframe::egui::UiKind;
 
 const COPY_SCORE_MENU_LABEL: &str = "📋 スコアをコピー";
+const EDIT_SCORE_MENU_LABEL: &str = "✏ スコアを修正";
+const EDIT_NOTE_MENU_LABEL: &str = "📝 メモ・タグを編集";
+const ALL_TAGS_LABEL: &str = "すべて";
+const RANKING_SORT_LABEL: &str = "🏆 成績順";
+const PERSONAL_BEST_MARK: &str = " 🏆";
 
-fn copied_text_if_requested(clicked: bool, score: i64) -> Option<String> {
-    clicked.then(|| score.to_string())
+fn copied_text_if_requested(clicked: bool, score: f64, value_type: ValueType) -> Option<String> {
+    clicked.then(|| value_type.format_plain(score))
+}
+
+/// タグフィルタに一致するかどうか（未指定なら常に一致）
+fn matches_tag_filter(entry: &ScoreEntry, tag_filter: Option<&str>) -> bool {
+    tag_filter.is_none_or(|tag| entry.has_tag(tag))
+}
+
+/// 表示順のインデックス列（成績順なら良い順、そうでなければ新しい順）
+fn display_order(total: usize, ranked_indices: &[usize], sort_by_rank: bool) -> Vec<usize> {
+    if sort_by_rank {
+        ranked_indices.to_vec()
+    } else {
+        (0..total).rev().collect()
+    }
+}
+
+/// 履歴行に表示するタグ文字列
+fn tags_label(tags: &[String]) -> String {
+    tags.iter()
+        .map(|tag| format!("#{}", tag))
+        .collect::<Vec<_>>()
+        .join(" ")
 }
 
 pub struct HistoryList<'a> {
     score_entries: &'a [ScoreEntry],
+    available_tags: Vec<String>,
+    value_type: ValueType,
+    ranked_indices: Vec<usize>,
 }
 
 impl<'a> HistoryList<'a> {
-    pub fn new(score_entries: &'a [ScoreEntry]) -> Self {
-        Self { score_entries }
+    pub fn new(item_data: &'a ItemData) -> Self {
+        Self {
+            score_entries: &item_data.scores,
+            available_tags: item_data.all_tags(),
+            value_type: item_data.value_type,
+            ranked_indices: item_data.ranked_indices(),
+        }
     }
 
     pub fn show(
@@ -24,11 +58,28 @@ impl<'a> HistoryList<'a> {
         ui: &mut egui::Ui,
         selected_index: &mut Option<usize>,
         scroll_req_index: &mut Option<usize>,
+        tag_filter: &mut Option<String>,
+        sort_by_rank: &mut bool,
     ) -> Option<Action> {
         let mut action = None;
 
+        // 存在しないタグでの絞り込みは解除
+        if tag_filter
+            .as_ref()
+            .is_some_and(|tag| !self.available_tags.contains(tag))
+        {
+            *tag_filter = None;
+        }
+
         ui.vertical(|ui| {
-            ui.label("履歴");
+            ui.horizontal(|ui| {
+                ui.label("履歴");
+                ui.toggle_value(sort_by_rank, RANKING_SORT_LABEL)
+                    .on_hover_text("評価の向きに従って良い順に並べ替えます");
+                if !self.available_tags.is_empty() {
+                    self.draw_tag_filter(ui, tag_filter);
+                }
+            });
             ui.separator();
 
             egui::ScrollArea::vertical()
@@ -41,52 +92,82 @@ impl<'a> HistoryList<'a> {
                         return;
                     }
 
-                    let total = self.score_entries.len();
-                    action = self
-                        .score_entries
-                        .iter()
-                        .rev()
-                        .enumerate()
-                        .filter_map(|(idx, entry)| {
-                            let score_index = total - 1 - idx;
-
-                            let is_selected = Some(score_index) == *selected_index;
-                            let should_scroll = Some(score_index) == *scroll_req_index;
-
-                            // 行オブジェクトを作って描画
-                            let action = HistoryRow::new(score_index, entry, is_selected).show(
-                                ui,
-                                selected_index,
-                                should_scroll,
-                            );
-
-                            // スクロール状態解除
-                            if should_scroll {
-                                *scroll_req_index = None;
-                            }
-
-                            action
-                        })
-                        .last();
+                    let best_index = self.ranked_indices.first().copied();
+                    action = display_order(
+                        self.score_entries.len(),
+                        &self.ranked_indices,
+                        *sort_by_rank,
+                    )
+                    .into_iter()
+                    .map(|score_index| (score_index, &self.score_entries[score_index]))
+                    .filter(|(_, entry)| matches_tag_filter(entry, tag_filter.as_deref()))
+                    .filter_map(|(score_index, entry)| {
+                        let is_selected = Some(score_index) == *selected_index;
+                        let should_scroll = Some(score_index) == *scroll_req_index;
+
+                        // 行オブジェクトを作って描画
+                        let is_best = Some(score_index) == best_index;
+                        let action = HistoryRow::new(
+                            score_index,
+                            entry,
+                            self.value_type,
+                            is_selected,
+                            is_best,
+                        )
+                        .show(ui, selected_index, should_scroll);
+
+                        // スクロール状態解除
+                        if should_scroll {
+                            *scroll_req_index = None;
+                        }
+
+                        action
+                    })
+                    .last();
                 });
         });
 
         action
     }
+
+    /// タグによる絞り込み選択
+    fn draw_tag_filter(&self, ui: &mut egui::Ui, tag_filter: &mut Option<String>) {
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            egui::ComboBox::from_id_salt("history_tag_filter")
+                .selected_text(tag_filter.as_deref().unwrap_or(ALL_TAGS_LABEL))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(tag_filter, None, ALL_TAGS_LABEL);
+                    self.available_tags.iter().for_each(|tag| {
+                        ui.selectable_value(tag_filter, Some(tag.clone()), tag);
+                    });
+                });
+            ui.label("タグ:");
+        });
+    }
 }
 
 struct HistoryRow<'a> {
     index: usize,
     entry: &'a ScoreEntry,
+    value_type: ValueType,
     is_selected: bool,
+    is_best: bool,
 }
 
 impl<'a> HistoryRow<'a> {
-    pub fn new(index: usize, entry: &'a ScoreEntry, is_selected: bool) -> Self {
+    pub fn new(
+        index: usize,
+        entry: &'a ScoreEntry,
+        value_type: ValueType,
+        is_selected: bool,
+        is_best: bool,
+    ) -> Self {
         Self {
             index,
             entry,
+            value_type,
             is_selected,
+            is_best,
         }
     }
 
@@ -108,27 +189,49 @@ impl<'a> HistoryRow<'a> {
             // 日付フォーマット
             let local_time = self.entry.timestamp.with_timezone(&chrono::Local);
             let date_str = local_time.format("%Y-%m-%d %H:%M").to_string();
-            let label_text = format!(
+            let mut label_text = format!(
                 "[{}] {}回目: {}",
                 date_str,
                 self.index + 1,
-                self.entry.score.to_comma()
+                self.value_type.format(self.entry.score)
             );
+            if self.is_best {
+                label_text.push_str(PERSONAL_BEST_MARK);
+            }
+            if self.entry.note.is_some() {
+                label_text.push_str(" 📝");
+            }
+            if !self.entry.tags.is_empty() {
+                label_text.push_str(&format!(" {}", tags_label(&self.entry.tags)));
+            }
 
             // ラベル
-            let response_label = ui.selectable_label(self.is_selected, label_text);
+            let mut response_label = ui.selectable_label(self.is_selected, label_text);
             if response_label.clicked() {
                 *selected_index = Some(self.index);
             }
+            if let Some(note) = &self.entry.note {
+                response_label = response_label.on_hover_text(note);
+            }
 
-            // 右クリックメニュー: スコアコピー
+            // 右クリックメニュー: スコアコピー、スコア修正、メモ・タグ編集
             let mut copied_text = None;
             response_label.context_menu(|ui| {
                 let clicked = ui.button(COPY_SCORE_MENU_LABEL).clicked();
-                copied_text = copied_text_if_requested(clicked, self.entry.score);
+                copied_text = copied_text_if_requested(clicked, self.entry.score, self.value_type);
                 if copied_text.is_some() {
                     ui.close_kind(UiKind::Menu);
                 }
+
+                if ui.button(EDIT_SCORE_MENU_LABEL).clicked() {
+                    action = Some(Action::ShowEditScoreModal(self.index));
+                    ui.close_kind(UiKind::Menu);
+                }
+
+                if ui.button(EDIT_NOTE_MENU_LABEL).clicked() {
+                    action = Some(Action::ShowEditScoreNoteModal(self.index));
+                    ui.close_kind(UiKind::Menu);
+                }
             });
             if let Some(text) = copied_text {
                 ui.ctx().copy_text(text);
@@ -151,20 +254,58 @@ mod tests {
     #[test]
     fn copied_text_is_generated_when_copy_menu_is_clicked() {
         // 右クリックメニューのコピー操作が選択されたときに、スコア文字列が生成されることを確認する。
-        let copied = copied_text_if_requested(true, 12345);
+        let copied = copied_text_if_requested(true, 12345.0, ValueType::Integer);
         assert_eq!(copied, Some("12345".to_string()));
+
+        let copied = copied_text_if_requested(true, 87.5, ValueType::Decimal(2));
+        assert_eq!(copied, Some("87.50".to_string()));
     }
 
     #[test]
     fn copied_text_is_none_when_copy_menu_is_not_clicked() {
         // 右クリックメニューのコピー操作が未選択なら、コピー文字列が生成されないことを確認する。
-        let copied = copied_text_if_requested(false, 12345);
+        let copied = copied_text_if_requested(false, 12345.0, ValueType::Integer);
         assert_eq!(copied, None);
     }
 
+    fn entry_with_tags(tags: &[&str]) -> ScoreEntry {
+        ScoreEntry {
+            score: 10.0,
+            timestamp: chrono::Utc::now(),
+            note: None,
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn tag_filter_matches_only_entries_with_tag() {
+        // タグ絞り込み時は該当タグを持つスコアのみ、未指定時はすべてが一致することを確認する。
+        let tagged = entry_with_tags(&["朝"]);
+        let untagged = entry_with_tags(&[]);
+
+        assert!(matches_tag_filter(&tagged, Some("朝")));
+        assert!(!matches_tag_filter(&untagged, Some("朝")));
+        assert!(matches_tag_filter(&untagged, None));
+    }
+
+    #[test]
+    fn tags_label_prefixes_each_tag_with_hash() {
+        // 履歴行のタグ表示が「#タグ」形式の空白区切りになることを確認する。
+        assert_eq!(tags_label(&["a".to_string(), "b".to_string()]), "#a #b");
+        assert_eq!(tags_label(&[]), "");
+    }
+
     #[test]
     fn copy_menu_label_is_expected_text() {
         // 履歴行の右クリックメニューに表示するラベル文言が意図した値であることを確認する。
         assert_eq!(COPY_SCORE_MENU_LABEL, "📋 スコアをコピー");
     }
+
+    #[test]
+    fn display_order_is_newest_first_or_ranked() {
+        // 通常は新しい順、成績順指定時は順位インデックスの順で並ぶことを確認する。
+        let ranked = vec![1, 2, 0];
+        assert_eq!(display_order(3, &ranked, false), vec![2, 1, 0]);
+        assert_eq!(display_order(3, &ranked, true), vec![1, 2, 0]);
+    }
 }
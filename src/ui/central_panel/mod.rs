@@ -1,25 +1,56 @@
+mod category_summary;
 mod chart;
 mod history;
 mod score_input;
 
 use crate::action::Action;
-use crate::domain::{ItemData, TrackerModel};
-use crate::logic::calculate_stats;
+use crate::domain::{CategoryId, ItemData, ItemId, TrackerModel, WeightingMode};
+use crate::logic::{ScoreTrend, calculate_stats, calculate_trend, scheme_for};
+use crate::ui::central_panel::category_summary::CategorySummaryView;
 use crate::ui::central_panel::chart::WeightedScoreChart;
 use crate::ui::central_panel::history::HistoryList;
 use crate::ui::central_panel::score_input::ScoreInput;
 use crate::utils::comma_display::CommaDisplay;
+use crate::utils::weighted_statistics::WeightedRegression;
 use eframe::egui::{self};
 
 const INPUT_SETTINGS_GAP: f32 = 16.0;
 
-fn item_settings_action(cat_name: &str, item_name: &str, clicked: bool) -> Option<Action> {
-    clicked.then(|| Action::ShowEditItemModal(cat_name.to_string(), item_name.to_string()))
+fn item_settings_action(cat: CategoryId, item: ItemId, clicked: bool) -> Option<Action> {
+    clicked.then_some(Action::ShowEditItemModal(cat, item))
+}
+
+/// 傾向の表示（1回あたり・1週間あたりの傾きに標準誤差を添える）
+fn trend_text(trend: &ScoreTrend, precision: usize) -> String {
+    let slope_text = |regression: &WeightedRegression, unit: &str| {
+        let sign = if regression.slope > 0.0 { "+" } else { "" };
+        let std_error = regression
+            .slope_std_error
+            .map(|se| format!(" ± {}", se.to_comma_fmt(precision)))
+            .unwrap_or_default();
+        format!(
+            "{}{}{}/{}",
+            sign,
+            regression.slope.to_comma_fmt(precision),
+            std_error,
+            unit
+        )
+    };
+
+    let mut slopes = vec![slope_text(&trend.per_session, "回")];
+    if let Some(per_week) = &trend.per_week {
+        slopes.push(slope_text(per_week, "週"));
+    }
+    format!("傾向: {}（{}）", trend.direction.label(), slopes.join("、"))
 }
 
 pub struct CentralPanel {
     score_input_text: String,
+    score_timestamp_text: String,
     show_weighted_average: bool,
+    show_trend_line: bool,
+    tag_filter: Option<String>,
+    sort_by_rank: bool,
 
     selected_index: Option<usize>,
     scroll_req_index: Option<usize>,
@@ -29,7 +60,11 @@ impl CentralPanel {
     pub fn new() -> Self {
         Self {
             score_input_text: String::new(),
+            score_timestamp_text: String::new(),
             show_weighted_average: true,
+            show_trend_line: false,
+            tag_filter: None,
+            sort_by_rank: false,
 
             selected_index: None,   // 選択中インデックス
             scroll_req_index: None, // リストに対するスクロール処理用インデックス
@@ -49,9 +84,16 @@ impl CentralPanel {
                     ui.disable();
                 }
 
+                // カテゴリのみ選択: カテゴリ概要
+                if let (Some(cat_id), None) = (model.selection.category, model.selection.item)
+                    && let Ok(cat_data) = model.data.get_category(cat_id)
+                {
+                    return CategorySummaryView::new(cat_id, cat_data).show(ui);
+                }
+
                 // カテゴリ未選択
-                let (Some(cat_name), Some(item_name)) =
-                    (&model.selection.category, &model.selection.item)
+                let (Some(cat_id), Some(item_id)) =
+                    (model.selection.category, model.selection.item)
                 else {
                     ui.centered_and_justified(|ui| {
                         ui.label("左のリストから項目を選択するか、追加してください");
@@ -60,7 +102,7 @@ impl CentralPanel {
                 };
 
                 // データ取得
-                let Ok(item_data) = model.data.get_item(cat_name, item_name) else {
+                let Ok(item_data) = model.data.get_item(cat_id, item_id) else {
                     ui.label("項目データ読み込みエラー");
                     return None;
                 };
@@ -72,11 +114,10 @@ impl CentralPanel {
                 ui.separator();
 
                 // グラフ
-                WeightedScoreChart::new().show(
+                WeightedScoreChart::new(item_data).show(
                     ui,
-                    &item_data.scores,
-                    item_data.decay_rate,
                     self.show_weighted_average,
+                    self.show_trend_line,
                     &mut self.selected_index,
                     &mut self.scroll_req_index,
                 );
@@ -95,20 +136,25 @@ impl CentralPanel {
                                 // 左カラム: 入力
                                 let input_action = ui
                                     .vertical(|ui| {
-                                        let input_action =
-                                            ScoreInput::new().show(ui, &mut self.score_input_text);
+                                        let input_action = ScoreInput::new().show(
+                                            ui,
+                                            &mut self.score_input_text,
+                                            &mut self.score_timestamp_text,
+                                        );
                                         ui.add_space(INPUT_SETTINGS_GAP);
-                                        let settings_action = self
-                                            .draw_item_settings(ui, cat_name, item_name, item_data);
+                                        let settings_action =
+                                            self.draw_item_settings(ui, cat_id, item_id, item_data);
                                         input_action.or(settings_action)
                                     })
                                     .inner;
 
                                 // 右カラム: 履歴
-                                let history_action = HistoryList::new(&item_data.scores).show(
+                                let history_action = HistoryList::new(item_data).show(
                                     ui,
                                     &mut self.selected_index,
                                     &mut self.scroll_req_index,
+                                    &mut self.tag_filter,
+                                    &mut self.sort_by_rank,
                                 );
                                 input_action.or(history_action)
                             })
@@ -121,24 +167,37 @@ impl CentralPanel {
 
     /// ヘッダー（統計情報）の描画
     fn draw_header(&self, ui: &mut egui::Ui, item_data: &ItemData) {
-        let (avg, std, count, _) = calculate_stats(&item_data.scores, item_data.decay_rate);
+        let scheme = scheme_for(item_data);
+        let (avg, std, count, _) = calculate_stats(&item_data.scores, scheme.as_ref());
+        let trend = calculate_trend(&item_data.scores, scheme.as_ref(), item_data.direction);
+        let precision = item_data.value_type.stats_precision();
 
         ui.horizontal(|ui| {
             ui.label(
-                egui::RichText::new(format!("現在の加重平均: {}", avg.to_comma_fmt(2)))
+                egui::RichText::new(format!("現在の加重平均: {}", avg.to_comma_fmt(precision)))
                     .size(16.0)
                     .strong(),
             );
-            ui.label(format!("加重標準偏差: {}", std.to_comma_fmt(2)));
+            ui.label(format!("加重標準偏差: {}", std.to_comma_fmt(precision)));
+            if let Some(best) = item_data.personal_best_index() {
+                ui.label(format!(
+                    "自己ベスト（{}）: {}",
+                    item_data.direction.best_label(),
+                    item_data.value_type.format(item_data.scores[best].score)
+                ));
+            }
             ui.label(format!("データ数: {}", count));
+            if let Some(trend) = &trend {
+                ui.label(trend_text(trend, precision));
+            }
         });
     }
 
     fn draw_item_settings(
         &mut self,
         ui: &mut egui::Ui,
-        cat_name: &str,
-        item_name: &str,
+        cat_id: CategoryId,
+        item_id: ItemId,
         item_data: &ItemData,
     ) -> Option<Action> {
         let mut action = None;
@@ -150,7 +209,7 @@ impl CentralPanel {
                     ui.label(egui::RichText::new("項目設定").strong());
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                         let clicked = ui.button("設定を開く").clicked();
-                        action = item_settings_action(cat_name, item_name, clicked);
+                        action = item_settings_action(cat_id, item_id, clicked);
                     });
                 });
 
@@ -160,13 +219,32 @@ impl CentralPanel {
                     .num_columns(2)
                     .spacing([12.0, 4.0])
                     .show(ui, |ui| {
-                        ui.label("減衰率");
-                        ui.label(item_data.decay_rate.to_comma_fmt(2));
+                        ui.label("重み付け");
+                        ui.label(item_data.weighting.label());
+                        ui.end_row();
+
+                        // 減衰率は指数減衰のときのみ使われる
+                        if item_data.weighting == WeightingMode::Exponential {
+                            ui.label("減衰率");
+                            ui.label(item_data.decay_rate.to_comma_fmt(2));
+                            ui.end_row();
+                        }
+
+                        ui.label("値の種類");
+                        ui.label(item_data.value_type.label());
+                        ui.end_row();
+
+                        ui.label("評価の向き");
+                        ui.label(item_data.direction.label());
                         ui.end_row();
 
                         ui.label("加重平均");
                         ui.checkbox(&mut self.show_weighted_average, "グラフ表示");
                         ui.end_row();
+
+                        ui.label("傾向線");
+                        ui.checkbox(&mut self.show_trend_line, "グラフ表示");
+                        ui.end_row();
                     });
             });
 
@@ -175,6 +253,7 @@ impl CentralPanel {
 
     pub fn clear_input(&mut self) {
         self.score_input_text.clear();
+        self.score_timestamp_text.clear();
     }
 }
 
@@ -185,17 +264,18 @@ mod tests {
     #[test]
     fn item_settings_button_dispatches_edit_item_action() {
         // 項目設定ボタンが押された場合に、項目編集モーダル起動アクションが生成されることを確認する。
-        let action = item_settings_action("CatA", "ItemA", true);
+        let (cat_id, item_id) = (CategoryId::generate(), ItemId::generate());
+        let action = item_settings_action(cat_id, item_id, true);
         assert!(matches!(
             action,
-            Some(Action::ShowEditItemModal(cat, item)) if cat == "CatA" && item == "ItemA"
+            Some(Action::ShowEditItemModal(cat, item)) if cat == cat_id && item == item_id
         ));
     }
 
     #[test]
     fn item_settings_button_no_click_yields_no_action() {
         // 項目設定ボタンが押されていない場合はアクションが生成されないことを確認する。
-        let action = item_settings_action("CatA", "ItemA", false);
+        let action = item_settings_action(CategoryId::generate(), ItemId::generate(), false);
         assert!(action.is_none());
     }
 
@@ -206,6 +286,25 @@ mod tests {
         assert!(panel.show_weighted_average);
     }
 
+    #[test]
+    fn trend_text_shows_direction_and_slopes_with_std_error() {
+        // 傾向の表示に判定・1回あたりと1週間あたりの符号付きの傾き・標準誤差が含まれることを確認する。
+        let regression = |slope, slope_std_error| WeightedRegression {
+            slope,
+            intercept: 0.0,
+            slope_std_error,
+        };
+        let trend = ScoreTrend {
+            per_session: regression(1.5, Some(0.25)),
+            per_week: Some(regression(-2.0, None)),
+            direction: crate::logic::TrendDirection::Improving,
+        };
+        assert_eq!(
+            trend_text(&trend, 2),
+            "傾向: 改善傾向（+1.50 ± 0.25/回、-2.00/週）"
+        );
+    }
+
     #[test]
     fn input_and_settings_gap_matches_design_value() {
         // スコア入力欄と項目設定の間隔がデザインで定義した値になっていることを確認する。
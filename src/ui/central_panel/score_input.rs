@@ -1,6 +1,7 @@
 use eframe::egui;
 
 use crate::action::Action;
+use crate::constants::TIMESTAMP_INPUT_HINT;
 
 pub struct ScoreInput;
 
@@ -9,7 +10,12 @@ impl ScoreInput {
         Self
     }
 
-    pub fn show(&mut self, ui: &mut egui::Ui, input_text: &mut String) -> Option<Action> {
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        input_text: &mut String,
+        timestamp_text: &mut String,
+    ) -> Option<Action> {
         let mut action = None;
 
         ui.vertical(|ui| {
@@ -22,12 +28,21 @@ impl ScoreInput {
 
                 if is_clicked_button || is_enter {
                     if !input_text.is_empty() {
-                        action = Some(Action::AddScore(input_text.clone()));
+                        action = Some(Action::AddScore(input_text.clone(), timestamp_text.clone()));
                     }
                     // エンターを押されるとフォーカスが切れるため、空白かどうか関係なくフォーカスし直し
                     res.request_focus();
                 }
             });
+
+            // 記録日時（過去の記録を入力する場合のみ指定）
+            ui.horizontal(|ui| {
+                ui.label("記録日時:");
+                ui.add(
+                    egui::TextEdit::singleline(timestamp_text)
+                        .hint_text(format!("{} (空欄で現在時刻)", TIMESTAMP_INPUT_HINT)),
+                );
+            });
         });
 
         action
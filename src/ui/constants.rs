@@ -0,0 +1,21 @@
+use eframe::egui;
+
+// --- ウィンドウ設定 ---
+pub const WINDOW_WIDTH: f32 = 1000.0;
+pub const WINDOW_HEIGHT: f32 = 600.0;
+
+// --- フォント設定 ---
+pub const FONT_SCALE: f32 = 1.2;
+// 優先順位順のフォントパス
+pub const FONT_PATHS: &[&str] = &[
+    "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc",
+    "C:\\Windows\\Fonts\\meiryo.ttc",
+];
+
+// --- グラフ ---
+pub const BAR_BASE_COLOR: egui::Color32 = egui::Color32::from_rgb(65, 105, 225);
+// 自己ベストのバーカラー
+pub const PERSONAL_BEST_COLOR: egui::Color32 = egui::Color32::from_rgb(218, 165, 32);
+// 傾向線のカラー
+pub const TREND_LINE_COLOR: egui::Color32 = egui::Color32::from_rgb(46, 139, 87);
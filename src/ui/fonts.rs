@@ -1,4 +1,4 @@
-use crate::constants::{FONT_PATHS, FONT_SCALE};
+use crate::ui::constants::{FONT_PATHS, FONT_SCALE};
 use eframe::egui;
 
 pub fn setup_custom_fonts(ctx: &egui::Context) {
@@ -1,5 +1,8 @@
+pub mod banner;
 pub mod central_panel;
+pub mod constants;
 pub mod fonts;
+pub mod ime;
 pub mod modals;
 pub mod side_panel;
 pub mod state;
@@ -1,6 +1,6 @@
 use super::{Modal, ModalResult};
 use crate::action::Action;
-use crate::utils::ime::ImeFocusExtension;
+use crate::ui::ime::ImeFocusExtension;
 use eframe::egui;
 
 pub struct AddCategoryModal {
@@ -1,23 +1,29 @@
+use super::value_type_input;
 use super::{Modal, ModalResult};
 use crate::action::Action;
 use crate::constants::{DEFAULT_DECAY_RATE, MAX_DECAY_RATE, MIN_DECAY_RATE};
-use crate::utils::ime::ImeFocusExtension;
+use crate::domain::{CategoryId, ValueType};
+use crate::ui::ime::ImeFocusExtension;
 use eframe::egui;
 
 // 必要なデータはすべてフィールドとして持つ！
 pub struct AddItemModal {
-    target_cat: String,
+    target_cat: CategoryId,
+    target_name: String,
     input_item: String,
     input_decay: String,
+    input_value_type: ValueType,
 }
 
 impl AddItemModal {
     // コンストラクタで初期値を受け取る
-    pub fn new(cat_name: String) -> Self {
+    pub fn new(cat_id: CategoryId, cat_name: String) -> Self {
         Self {
-            target_cat: cat_name.clone(),
+            target_cat: cat_id,
+            target_name: cat_name,
             input_item: String::new(),
             input_decay: DEFAULT_DECAY_RATE.to_string(),
+            input_value_type: ValueType::default(),
         }
     }
 }
@@ -32,7 +38,7 @@ impl Modal for AddItemModal {
             .resizable(false)
             .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
             .show(ctx, |ui| {
-                ui.label(format!("追加先カテゴリ: {}", self.target_cat));
+                ui.label(format!("追加先カテゴリ: {}", self.target_name));
 
                 ui.label("項目名:");
                 let res = ui.text_edit_singleline(&mut self.input_item);
@@ -44,14 +50,18 @@ impl Modal for AddItemModal {
                 ));
                 ui.text_edit_singleline(&mut self.input_decay);
 
+                ui.label("値の種類:");
+                value_type_input::show(ui, "add_item_value_type", &mut self.input_value_type);
+
                 ui.add_space(10.0);
 
                 ui.horizontal(|ui| {
                     if ui.button("追加").clicked() {
                         result = ModalResult::Dispatch(Action::AddItem(
-                            self.target_cat.to_string(),
+                            self.target_cat,
                             self.input_item.clone(),
                             self.input_decay.clone(),
+                            self.input_value_type,
                         ));
                     }
                     if ui.button("キャンセル").clicked() {
@@ -0,0 +1,46 @@
+use super::{Modal, ModalResult};
+use crate::action::Action;
+use crate::ui::ime::ImeFocusExtension;
+use eframe::egui;
+
+pub struct AddProfileModal {
+    input_name: String,
+}
+
+impl AddProfileModal {
+    pub fn new() -> Self {
+        Self {
+            input_name: String::new(),
+        }
+    }
+}
+
+impl Modal for AddProfileModal {
+    fn show(&mut self, ctx: &egui::Context) -> ModalResult {
+        let mut result = ModalResult::KeepOpen;
+
+        egui::Window::new("プロファイル作成")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.label("プロファイル名:");
+                let res = ui.text_edit_singleline(&mut self.input_name);
+                res.handle_ime_focus(ui);
+
+                ui.add_space(10.0);
+
+                ui.horizontal(|ui| {
+                    if ui.button("作成して切り替え").clicked() {
+                        result =
+                            ModalResult::Dispatch(Action::CreateProfile(self.input_name.clone()));
+                    }
+                    if ui.button("キャンセル").clicked() {
+                        result = ModalResult::Close;
+                    }
+                });
+            });
+
+        result
+    }
+}
@@ -1,5 +1,6 @@
 use super::{Modal, ModalResult};
 use crate::action::Action;
+use crate::domain::{CategoryId, ItemId};
 use eframe::egui;
 
 pub struct ConfirmationModal {
@@ -18,26 +19,26 @@ impl ConfirmationModal {
     }
 
     /// カテゴリ削除
-    pub fn new_delete_category(cat_name: String) -> Self {
+    pub fn new_delete_category(cat_id: CategoryId, cat_name: &str) -> Self {
         Self::new(
             "カテゴリ削除",
             format!(
                 "カテゴリ「{}」を削除しますか？\n含まれるすべての項目と履歴が失われます。",
                 cat_name
             ),
-            Action::ExecuteDeleteCategory(cat_name),
+            Action::ExecuteDeleteCategory(cat_id),
         )
     }
 
     /// 項目削除
-    pub fn new_delete_item(cat_name: String, item_name: String) -> Self {
+    pub fn new_delete_item(cat_id: CategoryId, item_id: ItemId, item_name: &str) -> Self {
         Self::new(
             "項目削除",
             format!(
                 "項目「{}」を削除しますか？\nこの項目の履歴データもすべて失われます。",
                 item_name
             ),
-            Action::ExecuteDeleteItem(cat_name, item_name),
+            Action::ExecuteDeleteItem(cat_id, item_id),
         )
     }
 
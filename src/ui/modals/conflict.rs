@@ -0,0 +1,72 @@
+use super::{Modal, ModalResult};
+use crate::action::Action;
+use crate::application::ConflictResolution;
+use eframe::egui;
+
+/// ほかのプログラムによるデータファイルの変更との競合（解決方法の選択）
+///
+/// 解決方法が選ばれるまで閉じられない。
+pub struct ConflictModal {
+    message: String,
+}
+
+impl ConflictModal {
+    pub fn new(message: String) -> Self {
+        Self { message }
+    }
+}
+
+impl Modal for ConflictModal {
+    fn show(&mut self, ctx: &egui::Context) -> ModalResult {
+        let mut result = ModalResult::KeepOpen;
+
+        egui::Window::new("データファイルの競合")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.label("ほかのプログラムがデータファイルを変更したため、保存を停止しています。");
+                ui.label(
+                    egui::RichText::new(&self.message)
+                        .size(11.0)
+                        .color(egui::Color32::GRAY),
+                );
+
+                ui.add_space(10.0);
+                ui.label(egui::RichText::new("どちらの内容を残すか選択してください").strong());
+                ui.add_space(5.0);
+
+                let choices = [
+                    (
+                        "統合する",
+                        "どちらかにしかないカテゴリ・項目・スコアをすべて残す（名前や設定はこちらを優先）",
+                        ConflictResolution::Merge,
+                    ),
+                    (
+                        "ファイルの内容を読み込む",
+                        "こちらの変更は破棄する（元に戻すで取り戻せる）",
+                        ConflictResolution::UseFile,
+                    ),
+                    (
+                        "こちらの内容で上書き",
+                        "ほかのプログラムによる変更は失われる",
+                        ConflictResolution::KeepMine,
+                    ),
+                ];
+                egui::Grid::new("conflict_choices")
+                    .num_columns(2)
+                    .spacing([10.0, 6.0])
+                    .show(ui, |ui| {
+                        choices.iter().for_each(|(label, hint, resolution)| {
+                            if ui.button(*label).clicked() {
+                                result = ModalResult::Dispatch(Action::ResolveConflict(*resolution));
+                            }
+                            ui.label(egui::RichText::new(*hint).size(11.0));
+                            ui.end_row();
+                        });
+                    });
+            });
+
+        result
+    }
+}
@@ -0,0 +1,61 @@
+use super::{Modal, ModalResult};
+use crate::action::Action;
+use crate::application::ExportScope;
+use crate::constants::CSV_EXPORT_FILENAME;
+use crate::ui::ime::ImeFocusExtension;
+use eframe::egui;
+
+/// CSV書き出し（保存先のパスを指定して書き出す）
+pub struct CsvExportModal {
+    scope: ExportScope,
+    target_label: String,
+    input_path: String,
+}
+
+impl CsvExportModal {
+    pub fn new(scope: ExportScope, target_label: String) -> Self {
+        Self {
+            scope,
+            target_label,
+            input_path: CSV_EXPORT_FILENAME.to_string(),
+        }
+    }
+}
+
+impl Modal for CsvExportModal {
+    fn show(&mut self, ctx: &egui::Context) -> ModalResult {
+        let mut result = ModalResult::KeepOpen;
+
+        egui::Window::new("CSVに書き出す")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.label(format!("対象: {}", self.target_label));
+
+                ui.label("保存先:");
+                let res = ui.text_edit_singleline(&mut self.input_path);
+                res.handle_ime_focus(ui);
+
+                ui.add_space(10.0);
+
+                ui.horizontal(|ui| {
+                    let can_export = !self.input_path.trim().is_empty();
+                    if ui
+                        .add_enabled(can_export, egui::Button::new("書き出す"))
+                        .clicked()
+                    {
+                        result = ModalResult::Dispatch(Action::ExportCsv(
+                            self.scope,
+                            self.input_path.trim().to_string(),
+                        ));
+                    }
+                    if ui.button("キャンセル").clicked() {
+                        result = ModalResult::Close;
+                    }
+                });
+            });
+
+        result
+    }
+}
@@ -0,0 +1,230 @@
+use super::{Modal, ModalResult};
+use crate::action::Action;
+use crate::application::{ColumnMapping, ImportPreview};
+use crate::ui::ime::ImeFocusExtension;
+use eframe::egui;
+
+const PREVIEW_LIST_HEIGHT: f32 = 240.0;
+const ERROR_LIST_HEIGHT: f32 = 120.0;
+
+/// 読み込んだ CSV の内容（列の割り当てと取り込み件数の確認）
+struct LoadedCsv {
+    headers: Vec<String>,
+    mapping: ColumnMapping,
+    preview: Result<ImportPreview, String>,
+}
+
+/// CSV取り込み（読み込み → 列の割り当てと件数の確認 → 取り込み）
+pub struct CsvImportModal {
+    input_path: String,
+    loaded: Option<LoadedCsv>,
+}
+
+impl CsvImportModal {
+    pub fn new(input_path: String) -> Self {
+        Self {
+            input_path,
+            loaded: None,
+        }
+    }
+
+    /// 読み込んだ見出しと割り当て、そのドライラン結果を表示する
+    pub fn with_preview(
+        mut self,
+        headers: Vec<String>,
+        mapping: ColumnMapping,
+        preview: Result<ImportPreview, String>,
+    ) -> Self {
+        self.loaded = Some(LoadedCsv {
+            headers,
+            mapping,
+            preview,
+        });
+        self
+    }
+}
+
+/// 割り当て欄の表示名
+fn column_label(headers: &[String], column: Option<usize>) -> String {
+    match column.and_then(|index| headers.get(index).map(|header| (index, header))) {
+        Some((index, header)) => format!("{}: {}", index + 1, header),
+        None => "（なし）".to_string(),
+    }
+}
+
+/// 取り込み件数のまとめ
+fn summary_label(preview: &ImportPreview) -> String {
+    format!(
+        "取り込み {} 件・重複 {} 件・エラー {} 件",
+        preview.imported(),
+        preview.duplicates(),
+        preview.errors.len()
+    )
+}
+
+impl Modal for CsvImportModal {
+    fn show(&mut self, ctx: &egui::Context) -> ModalResult {
+        let mut result = ModalResult::KeepOpen;
+
+        egui::Window::new("CSVから取り込む")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.label("ファイル:");
+                ui.horizontal(|ui| {
+                    let res = ui.text_edit_singleline(&mut self.input_path);
+                    res.handle_ime_focus(ui);
+
+                    let can_load = !self.input_path.trim().is_empty();
+                    if ui
+                        .add_enabled(can_load, egui::Button::new("読み込む"))
+                        .clicked()
+                    {
+                        result = ModalResult::Dispatch(Action::PreviewCsvImport(
+                            self.input_path.trim().to_string(),
+                            None,
+                        ));
+                    }
+                });
+
+                let mut can_import = false;
+
+                if let Some(loaded) = &mut self.loaded {
+                    ui.add_space(10.0);
+                    ui.label("列の割り当て (* は必須):");
+
+                    let mut changed = false;
+                    egui::Grid::new("csv_mapping_grid")
+                        .num_columns(2)
+                        .spacing([10.0, 6.0])
+                        .show(ui, |ui| {
+                            let headers = &loaded.headers;
+                            loaded.mapping.fields_mut().into_iter().for_each(
+                                |(label, required, column)| {
+                                    let mark = if required { " *" } else { "" };
+                                    ui.label(format!("{}{}:", label, mark));
+                                    egui::ComboBox::from_id_salt(("csv_mapping", label))
+                                        .selected_text(column_label(headers, *column))
+                                        .show_ui(ui, |ui| {
+                                            changed |= ui
+                                                .selectable_value(column, None, "（なし）")
+                                                .changed();
+                                            (0..headers.len()).for_each(|index| {
+                                                changed |= ui
+                                                    .selectable_value(
+                                                        column,
+                                                        Some(index),
+                                                        column_label(headers, Some(index)),
+                                                    )
+                                                    .changed();
+                                            });
+                                        });
+                                    ui.end_row();
+                                },
+                            );
+                        });
+
+                    // 割り当てを変えたら件数を数え直す
+                    if changed {
+                        result = ModalResult::Dispatch(Action::PreviewCsvImport(
+                            self.input_path.trim().to_string(),
+                            Some(loaded.mapping),
+                        ));
+                    }
+
+                    ui.add_space(10.0);
+
+                    match &loaded.preview {
+                        Err(msg) => {
+                            ui.colored_label(egui::Color32::RED, msg);
+                        }
+                        Ok(preview) => {
+                            can_import = preview.imported() > 0;
+                            ui.label(summary_label(preview));
+
+                            egui::ScrollArea::vertical()
+                                .id_salt("csv_preview_list")
+                                .max_height(PREVIEW_LIST_HEIGHT)
+                                .show(ui, |ui| {
+                                    egui::Grid::new("csv_preview_grid")
+                                        .num_columns(4)
+                                        .striped(true)
+                                        .spacing([16.0, 4.0])
+                                        .show(ui, |ui| {
+                                            ui.label(egui::RichText::new("カテゴリ").strong());
+                                            ui.label(egui::RichText::new("項目").strong());
+                                            ui.label(egui::RichText::new("取り込み").strong());
+                                            ui.label(egui::RichText::new("重複").strong());
+                                            ui.end_row();
+
+                                            preview.items.iter().for_each(|count| {
+                                                ui.label(&count.category);
+                                                if count.new_item {
+                                                    ui.label(format!("{} (新規)", count.item));
+                                                } else {
+                                                    ui.label(&count.item);
+                                                }
+                                                ui.label(count.imported.to_string());
+                                                ui.label(count.duplicates.to_string());
+                                                ui.end_row();
+                                            });
+                                        });
+                                });
+
+                            if !preview.errors.is_empty() {
+                                ui.collapsing(
+                                    format!("取り込めない行 ({} 件)", preview.errors.len()),
+                                    |ui| {
+                                        egui::ScrollArea::vertical()
+                                            .id_salt("csv_error_list")
+                                            .max_height(ERROR_LIST_HEIGHT)
+                                            .show(ui, |ui| {
+                                                preview.errors.iter().for_each(|error| {
+                                                    ui.label(error);
+                                                });
+                                            });
+                                    },
+                                );
+                            }
+                        }
+                    }
+                }
+
+                ui.add_space(10.0);
+
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(can_import, egui::Button::new("取り込む"))
+                        .clicked()
+                        && let Some(loaded) = &self.loaded
+                    {
+                        result = ModalResult::Dispatch(Action::ImportCsv(
+                            self.input_path.trim().to_string(),
+                            loaded.mapping,
+                        ));
+                    }
+                    if ui.button("キャンセル").clicked() {
+                        result = ModalResult::Close;
+                    }
+                });
+            });
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn column_label_shows_position_and_header() {
+        // 割り当てた列は列番号と見出しを、未割り当てや範囲外は（なし）を表示することを確認する。
+        let headers = vec!["date".to_string(), "score".to_string()];
+
+        assert_eq!(column_label(&headers, Some(1)), "2: score");
+        assert_eq!(column_label(&headers, None), "（なし）");
+        assert_eq!(column_label(&headers, Some(5)), "（なし）");
+    }
+}
@@ -1,16 +1,19 @@
 use super::{Modal, ModalResult};
-use crate::{action::Action, utils::ime::ImeFocusExtension};
+use crate::domain::CategoryId;
+use crate::{action::Action, ui::ime::ImeFocusExtension};
 use eframe::egui;
 
 pub struct EditCategoryModal {
-    target_cat: String,
+    target_cat: CategoryId,
+    target_name: String,
     input_cat: String,
 }
 
 impl EditCategoryModal {
-    pub fn new(cat_name: String) -> Self {
+    pub fn new(cat_id: CategoryId, cat_name: String) -> Self {
         Self {
-            target_cat: cat_name.clone(),
+            target_cat: cat_id,
+            target_name: cat_name.clone(),
             input_cat: cat_name,
         }
     }
@@ -25,7 +28,7 @@ impl Modal for EditCategoryModal {
             .resizable(false)
             .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
             .show(ctx, |ui| {
-                ui.label(format!("対象: {}", self.target_cat));
+                ui.label(format!("対象: {}", self.target_name));
 
                 ui.label("新しい名前:");
                 let res = ui.text_edit_singleline(&mut self.input_cat);
@@ -36,7 +39,7 @@ impl Modal for EditCategoryModal {
                 ui.horizontal(|ui| {
                     if ui.button("変更").clicked() {
                         result = ModalResult::Dispatch(Action::RenameCategory(
-                            self.target_cat.clone(),
+                            self.target_cat,
                             self.input_cat.clone(),
                         ));
                     }
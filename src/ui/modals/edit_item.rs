@@ -1,35 +1,62 @@
+use super::value_type_input;
 use super::{Modal, ModalResult};
 use crate::action::Action;
-use crate::constants::{MAX_DECAY_RATE, MIN_DECAY_RATE};
-use crate::utils::ime::ImeFocusExtension;
+use crate::application::ItemSettingsInput;
+use crate::constants::{
+    MAX_DECAY_RATE, MAX_HALF_LIFE_DAYS, MAX_IMPORTANCE, MAX_WINDOW_SIZE, MIN_DECAY_RATE,
+    MIN_HALF_LIFE_DAYS, MIN_IMPORTANCE, MIN_WINDOW_SIZE,
+};
+use crate::domain::{CategoryId, ItemId, ScoreDirection, WeightingKind};
+use crate::ui::ime::ImeFocusExtension;
 use eframe::egui;
 
+/// 範囲の注記付き入力欄
+fn range_input(ui: &mut egui::Ui, text: &mut String, range_hint: String) {
+    ui.vertical(|ui| {
+        ui.text_edit_singleline(text);
+        ui.label(
+            egui::RichText::new(range_hint)
+                .size(10.0)
+                .color(egui::Color32::GRAY),
+        );
+    });
+}
+
 pub struct EditItemModal {
-    target_cat: String,
-    target_item: String,
-    input_cat: String,
+    target_cat: CategoryId,
+    target_item: ItemId,
+    input_cat: CategoryId,
     input_item: String,
-    input_decay: String,
+    input_settings: ItemSettingsInput,
 
-    available_categories: Vec<String>,
+    // 移動先の候補（ID, 表示名）
+    available_categories: Vec<(CategoryId, String)>,
 }
 
 impl EditItemModal {
     pub fn new(
-        cat_name: String,
+        location: (CategoryId, ItemId),
         item_name: String,
-        current_decay: f64,
-        categories: Vec<String>,
+        current_settings: ItemSettingsInput,
+        categories: Vec<(CategoryId, String)>,
     ) -> Self {
+        let (cat_id, item_id) = location;
         Self {
-            target_cat: cat_name.clone(),
-            target_item: item_name.clone(),
-            input_cat: cat_name,
+            target_cat: cat_id,
+            target_item: item_id,
+            input_cat: cat_id,
             input_item: item_name,
-            input_decay: current_decay.to_string(),
+            input_settings: current_settings,
             available_categories: categories,
         }
     }
+
+    fn category_label(&self, cat_id: CategoryId) -> &str {
+        self.available_categories
+            .iter()
+            .find(|(id, _)| *id == cat_id)
+            .map_or("", |(_, name)| name.as_str())
+    }
 }
 
 impl Modal for EditItemModal {
@@ -47,11 +74,11 @@ impl Modal for EditItemModal {
                     .show(ui, |ui| {
                         ui.label("カテゴリ:");
                         egui::ComboBox::from_id_salt("cat_select")
-                            .selected_text(self.input_cat.clone())
+                            .selected_text(self.category_label(self.input_cat).to_string())
                             .show_ui(ui, |ui| {
                                 // 存在するカテゴリを一覧表示
-                                self.available_categories.iter().for_each(|cat| {
-                                    ui.selectable_value(&mut self.input_cat, cat.clone(), cat);
+                                self.available_categories.iter().for_each(|(id, name)| {
+                                    ui.selectable_value(&mut self.input_cat, *id, name);
                                 });
                             });
                         ui.end_row();
@@ -61,17 +88,111 @@ impl Modal for EditItemModal {
                         res.handle_ime_focus(ui);
                         ui.end_row();
 
-                        ui.label("減衰率:");
-                        ui.vertical(|ui| {
-                            ui.text_edit_singleline(&mut self.input_decay);
-                            ui.label(
-                                egui::RichText::new(format!(
-                                    "({:.2} - {:.2})",
-                                    MIN_DECAY_RATE, MAX_DECAY_RATE
-                                ))
-                                .size(10.0)
-                                .color(egui::Color32::GRAY),
-                            );
+                        ui.label("重み付け:");
+                        egui::ComboBox::from_id_salt("weighting_select")
+                            .selected_text(self.input_settings.weighting_kind.label())
+                            .show_ui(ui, |ui| {
+                                WeightingKind::ALL.iter().for_each(|kind| {
+                                    ui.selectable_value(
+                                        &mut self.input_settings.weighting_kind,
+                                        *kind,
+                                        kind.label(),
+                                    );
+                                });
+                            });
+                        ui.end_row();
+
+                        // 選択中の重み付け方式に応じて入力欄を切り替え
+                        match self.input_settings.weighting_kind {
+                            WeightingKind::Exponential => {
+                                ui.label("減衰率:");
+                                range_input(
+                                    ui,
+                                    &mut self.input_settings.decay,
+                                    format!("({:.2} - {:.2})", MIN_DECAY_RATE, MAX_DECAY_RATE),
+                                );
+                                ui.end_row();
+                            }
+                            WeightingKind::SlidingWindow => {
+                                ui.label("件数:");
+                                range_input(
+                                    ui,
+                                    &mut self.input_settings.window_size,
+                                    format!("({} - {})", MIN_WINDOW_SIZE, MAX_WINDOW_SIZE),
+                                );
+                                ui.end_row();
+                            }
+                            WeightingKind::TimeDecay => {
+                                ui.label("半減期（日）:");
+                                range_input(
+                                    ui,
+                                    &mut self.input_settings.half_life_days,
+                                    format!("({} - {})", MIN_HALF_LIFE_DAYS, MAX_HALF_LIFE_DAYS),
+                                );
+                                ui.end_row();
+                            }
+                            WeightingKind::LinearRamp | WeightingKind::Uniform => {}
+                        }
+
+                        ui.label("重要度:");
+                        range_input(
+                            ui,
+                            &mut self.input_settings.importance,
+                            format!(
+                                "({} - {}) カテゴリの総合スコアでの重み",
+                                MIN_IMPORTANCE, MAX_IMPORTANCE
+                            ),
+                        );
+                        ui.end_row();
+
+                        ui.label("値の種類:");
+                        value_type_input::show(
+                            ui,
+                            "edit_item_value_type",
+                            &mut self.input_settings.value_type,
+                        );
+                        ui.end_row();
+
+                        ui.label("最小値:");
+                        ui.add(
+                            egui::TextEdit::singleline(&mut self.input_settings.rules.min)
+                                .hint_text("指定なし"),
+                        );
+                        ui.end_row();
+
+                        ui.label("最大値:");
+                        ui.add(
+                            egui::TextEdit::singleline(&mut self.input_settings.rules.max)
+                                .hint_text("指定なし"),
+                        );
+                        ui.end_row();
+
+                        ui.label("刻み幅:");
+                        ui.add(
+                            egui::TextEdit::singleline(&mut self.input_settings.rules.step)
+                                .hint_text("指定なし"),
+                        );
+                        ui.end_row();
+
+                        ui.label("");
+                        ui.checkbox(
+                            &mut self.input_settings.rules.allow_negative,
+                            "マイナスの値を許可",
+                        );
+                        ui.end_row();
+
+                        ui.label("評価の向き:");
+                        ui.horizontal(|ui| {
+                            for direction in [
+                                ScoreDirection::HigherIsBetter,
+                                ScoreDirection::LowerIsBetter,
+                            ] {
+                                ui.radio_value(
+                                    &mut self.input_settings.direction,
+                                    direction,
+                                    direction.label(),
+                                );
+                            }
                         });
                         ui.end_row();
                     });
@@ -81,11 +202,11 @@ impl Modal for EditItemModal {
                 ui.horizontal(|ui| {
                     if ui.button("保存").clicked() {
                         result = ModalResult::Dispatch(Action::UpdateItem(
-                            self.target_cat.clone(),
-                            self.target_item.clone(),
-                            self.input_cat.clone(),
+                            self.target_cat,
+                            self.target_item,
+                            self.input_cat,
                             self.input_item.clone(),
-                            self.input_decay.clone(),
+                            Box::new(self.input_settings.clone()),
                         ));
                     }
                     if ui.button("キャンセル").clicked() {
@@ -0,0 +1,76 @@
+use super::{Modal, ModalResult};
+use crate::action::Action;
+use crate::constants::TIMESTAMP_INPUT_HINT;
+use eframe::egui;
+
+pub struct EditScoreModal {
+    target_index: usize,
+    input_score: String,
+    input_timestamp: String,
+}
+
+impl EditScoreModal {
+    pub fn new(index: usize, score: String, timestamp: String) -> Self {
+        Self {
+            target_index: index,
+            input_score: score,
+            input_timestamp: timestamp,
+        }
+    }
+}
+
+impl Modal for EditScoreModal {
+    fn show(&mut self, ctx: &egui::Context) -> ModalResult {
+        let mut result = ModalResult::KeepOpen;
+
+        egui::Window::new("スコア修正")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                egui::Grid::new("edit_score_grid")
+                    .num_columns(2)
+                    .spacing([10.0, 10.0])
+                    .show(ui, |ui| {
+                        ui.label("対象:");
+                        ui.label(format!("{}回目", self.target_index + 1));
+                        ui.end_row();
+
+                        ui.label("スコア:");
+                        ui.text_edit_singleline(&mut self.input_score);
+                        ui.end_row();
+
+                        ui.label("記録日時:");
+                        ui.vertical(|ui| {
+                            ui.text_edit_singleline(&mut self.input_timestamp);
+                            ui.label(
+                                egui::RichText::new(format!(
+                                    "({}、空欄なら変更しない)",
+                                    TIMESTAMP_INPUT_HINT
+                                ))
+                                .size(10.0)
+                                .color(egui::Color32::GRAY),
+                            );
+                        });
+                        ui.end_row();
+                    });
+
+                ui.add_space(15.0);
+
+                ui.horizontal(|ui| {
+                    if ui.button("保存").clicked() {
+                        result = ModalResult::Dispatch(Action::UpdateScore(
+                            self.target_index,
+                            self.input_score.clone(),
+                            self.input_timestamp.clone(),
+                        ));
+                    }
+                    if ui.button("キャンセル").clicked() {
+                        result = ModalResult::Close;
+                    }
+                });
+            });
+
+        result
+    }
+}
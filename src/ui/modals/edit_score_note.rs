@@ -0,0 +1,59 @@
+use super::{Modal, ModalResult};
+use crate::action::Action;
+use crate::ui::ime::ImeFocusExtension;
+use eframe::egui;
+
+pub struct EditScoreNoteModal {
+    target_index: usize,
+    input_note: String,
+    input_tags: String,
+}
+
+impl EditScoreNoteModal {
+    pub fn new(index: usize, note: String, tags: String) -> Self {
+        Self {
+            target_index: index,
+            input_note: note,
+            input_tags: tags,
+        }
+    }
+}
+
+impl Modal for EditScoreNoteModal {
+    fn show(&mut self, ctx: &egui::Context) -> ModalResult {
+        let mut result = ModalResult::KeepOpen;
+
+        egui::Window::new("メモ・タグ編集")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.label(format!("対象: {}回目のスコア", self.target_index + 1));
+
+                ui.label("メモ:");
+                let res = ui.text_edit_multiline(&mut self.input_note);
+                res.handle_ime_focus(ui);
+
+                ui.label("タグ (カンマ区切り):");
+                let res = ui.text_edit_singleline(&mut self.input_tags);
+                res.handle_ime_focus(ui);
+
+                ui.add_space(10.0);
+
+                ui.horizontal(|ui| {
+                    if ui.button("保存").clicked() {
+                        result = ModalResult::Dispatch(Action::UpdateScoreNote(
+                            self.target_index,
+                            self.input_note.clone(),
+                            self.input_tags.clone(),
+                        ));
+                    }
+                    if ui.button("キャンセル").clicked() {
+                        result = ModalResult::Close;
+                    }
+                });
+            });
+
+        result
+    }
+}
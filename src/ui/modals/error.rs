@@ -2,12 +2,24 @@ use crate::ui::modals::{Modal, ModalResult};
 use eframe::egui;
 
 pub struct ErrorModal {
+    title: &'static str,
     error_msg: String,
 }
 
 impl ErrorModal {
     pub fn new(error_msg: String) -> Self {
-        Self { error_msg }
+        Self {
+            title: "エラー",
+            error_msg,
+        }
+    }
+
+    /// 完了報告などのお知らせとして表示する
+    pub fn notice(msg: String) -> Self {
+        Self {
+            title: "お知らせ",
+            error_msg: msg,
+        }
     }
 }
 
@@ -16,7 +28,7 @@ impl Modal for ErrorModal {
         let mut result = ModalResult::KeepOpen;
         let mut open = true;
 
-        egui::Window::new("エラー")
+        egui::Window::new(self.title)
             .collapsible(false)
             .resizable(false)
             .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
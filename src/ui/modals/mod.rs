@@ -1,9 +1,18 @@
 pub mod add_category;
 pub mod add_item;
+pub mod add_profile;
 pub mod confirm;
+pub mod conflict;
+pub mod csv_export;
+pub mod csv_import;
 pub mod edit_category;
 pub mod edit_item;
+pub mod edit_score;
+pub mod edit_score_note;
 pub mod error;
+pub mod restore_backup;
+pub mod safe_mode;
+mod value_type_input;
 
 use crate::action::Action;
 use crate::ui::modals::error::ErrorModal;
@@ -43,6 +52,15 @@ impl ModalLayer {
             }
         }
 
+        // お知らせモーダル
+        if let Some(msg) = &state.notice_message {
+            let mut notice_modal = ErrorModal::notice(msg.to_string());
+
+            if let ModalResult::Close = notice_modal.show(ctx) {
+                state.notice_message = None;
+            }
+        }
+
         // 通常モーダル
         if let Some(modal) = &mut self.active_modal {
             match modal.show(ctx) {
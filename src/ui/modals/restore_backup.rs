@@ -0,0 +1,111 @@
+use chrono::Local;
+
+use super::{Modal, ModalResult};
+use crate::action::Action;
+use crate::application::BackupInfo;
+use eframe::egui;
+
+const BACKUP_LIST_HEIGHT: f32 = 300.0;
+
+/// バックアップから復元（一覧から選んで復元する）
+pub struct RestoreBackupModal {
+    backups: Vec<BackupInfo>,
+}
+
+impl RestoreBackupModal {
+    pub fn new(backups: Vec<BackupInfo>) -> Self {
+        Self { backups }
+    }
+}
+
+/// 件数欄の表示（読み込めないバックアップは復元不可として表示）
+fn counts_label(counts: Option<(usize, usize)>) -> (String, String) {
+    match counts {
+        Some((categories, items)) => (categories.to_string(), items.to_string()),
+        None => ("読み込み不可".to_string(), "-".to_string()),
+    }
+}
+
+impl Modal for RestoreBackupModal {
+    fn show(&mut self, ctx: &egui::Context) -> ModalResult {
+        let mut result = ModalResult::KeepOpen;
+
+        egui::Window::new("バックアップから復元")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                if self.backups.is_empty() {
+                    ui.label("バックアップはまだありません");
+                } else {
+                    ui.label(
+                        "復元するバックアップを選択してください（現在の内容は置き換わります）",
+                    );
+                    ui.add_space(5.0);
+
+                    egui::ScrollArea::vertical()
+                        .max_height(BACKUP_LIST_HEIGHT)
+                        .show(ui, |ui| {
+                            egui::Grid::new("backup_list_grid")
+                                .num_columns(5)
+                                .striped(true)
+                                .spacing([16.0, 6.0])
+                                .show(ui, |ui| {
+                                    ui.label(egui::RichText::new("種類").strong());
+                                    ui.label(egui::RichText::new("保存日時").strong());
+                                    ui.label(egui::RichText::new("カテゴリ数").strong());
+                                    ui.label(egui::RichText::new("項目数").strong());
+                                    ui.label("");
+                                    ui.end_row();
+
+                                    self.backups.iter().for_each(|backup| {
+                                        let (categories, items) = counts_label(backup.counts);
+                                        ui.label(backup.kind.label());
+                                        ui.label(
+                                            backup
+                                                .saved_at
+                                                .with_timezone(&Local)
+                                                .format("%Y-%m-%d %H:%M:%S")
+                                                .to_string(),
+                                        );
+                                        ui.label(categories);
+                                        ui.label(items);
+                                        let restore = egui::Button::new("復元");
+                                        if ui
+                                            .add_enabled(backup.counts.is_some(), restore)
+                                            .clicked()
+                                        {
+                                            result = ModalResult::Dispatch(Action::RestoreBackup(
+                                                backup.id.clone(),
+                                            ));
+                                        }
+                                        ui.end_row();
+                                    });
+                                });
+                        });
+                }
+
+                ui.add_space(10.0);
+                if ui.button("キャンセル").clicked() {
+                    result = ModalResult::Close;
+                }
+            });
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_label_marks_unreadable_backup() {
+        // 件数が取得できたバックアップは件数を、読み込めないバックアップは読み込み不可を表示することを確認する。
+        assert_eq!(
+            counts_label(Some((2, 5))),
+            ("2".to_string(), "5".to_string())
+        );
+        assert_eq!(counts_label(None).0, "読み込み不可");
+    }
+}
@@ -0,0 +1,69 @@
+use super::{Modal, ModalResult};
+use crate::action::Action;
+use eframe::egui;
+
+/// セーフモード（データファイルを読み込めなかった場合の復元方法の選択）
+///
+/// 復元方法が選ばれるまで閉じられない。
+pub struct SafeModeModal {
+    message: String,
+    input_path: String,
+    confirm_fresh: bool,
+}
+
+impl SafeModeModal {
+    pub fn new(message: String, current_path: String) -> Self {
+        Self {
+            message,
+            input_path: current_path,
+            confirm_fresh: false,
+        }
+    }
+}
+
+impl Modal for SafeModeModal {
+    fn show(&mut self, ctx: &egui::Context) -> ModalResult {
+        let mut result = ModalResult::KeepOpen;
+
+        egui::Window::new("セーフモード")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.label("データファイルを読み込めなかったため、保存を停止しています。");
+                ui.label(
+                    egui::RichText::new(&self.message)
+                        .size(11.0)
+                        .color(egui::Color32::GRAY),
+                );
+
+                ui.add_space(10.0);
+                ui.label(egui::RichText::new("復元方法を選択してください").strong());
+                ui.add_space(5.0);
+
+                if ui.button("バックアップから復元...").clicked() {
+                    result = ModalResult::Dispatch(Action::ShowRestoreBackupModal);
+                }
+
+                ui.add_space(5.0);
+                ui.horizontal(|ui| {
+                    ui.text_edit_singleline(&mut self.input_path);
+                    if ui.button("別のファイルを開く").clicked() {
+                        result =
+                            ModalResult::Dispatch(Action::OpenDataFile(self.input_path.clone()));
+                    }
+                });
+
+                ui.add_space(5.0);
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut self.confirm_fresh, "空のデータで上書きする");
+                    let start = egui::Button::new("新しく始める");
+                    if ui.add_enabled(self.confirm_fresh, start).clicked() {
+                        result = ModalResult::Dispatch(Action::StartFresh);
+                    }
+                });
+            });
+
+        result
+    }
+}
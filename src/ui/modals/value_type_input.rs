@@ -0,0 +1,28 @@
+use crate::constants::MAX_DECIMAL_PRECISION;
+use crate::domain::ValueType;
+use eframe::egui;
+
+// 小数に切り替えた際の初期桁数
+const DEFAULT_DECIMAL_PRECISION: u8 = 1;
+
+/// 値の種類（整数 / 小数と桁数）の入力欄
+pub fn show(ui: &mut egui::Ui, id_salt: &str, value_type: &mut ValueType) {
+    ui.horizontal(|ui| {
+        let is_decimal = matches!(value_type, ValueType::Decimal(_));
+        egui::ComboBox::from_id_salt(id_salt)
+            .selected_text(if is_decimal { "小数" } else { "整数" })
+            .show_ui(ui, |ui| {
+                if ui.selectable_label(!is_decimal, "整数").clicked() {
+                    *value_type = ValueType::Integer;
+                }
+                if ui.selectable_label(is_decimal, "小数").clicked() && !is_decimal {
+                    *value_type = ValueType::Decimal(DEFAULT_DECIMAL_PRECISION);
+                }
+            });
+
+        if let ValueType::Decimal(digits) = value_type {
+            ui.add(egui::DragValue::new(digits).range(1..=MAX_DECIMAL_PRECISION));
+            ui.label("桁");
+        }
+    });
+}
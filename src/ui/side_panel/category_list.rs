@@ -1,24 +1,40 @@
 use crate::action::Action;
-use crate::domain::{AppData, CategoryData, SelectionState};
+use crate::application::ExportScope;
+use crate::constants::CATEGORY_AGGREGATE_PRECISION;
+use crate::domain::{AppData, CategoryData, CategoryId, ItemData, ItemId, SelectionState};
+use crate::logic::summarize_category;
+use crate::utils::comma_display::CommaDisplay;
 use eframe::egui::{self, UiKind};
 
 fn category_header_menu_action(
-    cat_name: &str,
+    cat_id: CategoryId,
     add_item_clicked: bool,
     edit_clicked: bool,
     delete_clicked: bool,
 ) -> Option<Action> {
     if add_item_clicked {
-        Some(Action::ShowAddItemModal(cat_name.to_string()))
+        Some(Action::ShowAddItemModal(cat_id))
     } else if edit_clicked {
-        Some(Action::ShowEditCategoryModal(cat_name.to_string()))
+        Some(Action::ShowEditCategoryModal(cat_id))
     } else if delete_clicked {
-        Some(Action::ShowDeleteCategoryConfirm(cat_name.to_string()))
+        Some(Action::ShowDeleteCategoryConfirm(cat_id))
     } else {
         None
     }
 }
 
+/// カテゴリヘッダーの表示名（集計値があれば併記）
+fn category_header_label(cat_name: &str, aggregate: Option<f64>) -> String {
+    match aggregate {
+        Some(value) => format!(
+            "{}  ({})",
+            cat_name,
+            value.to_comma_fmt(CATEGORY_AGGREGATE_PRECISION)
+        ),
+        None => cat_name.to_string(),
+    }
+}
+
 /// カテゴリリスト描画のエントリーポイント
 pub fn show(ui: &mut egui::Ui, data: &AppData, selection: &SelectionState) -> Option<Action> {
     let mut action = None;
@@ -30,13 +46,13 @@ pub fn show(ui: &mut egui::Ui, data: &AppData, selection: &SelectionState) -> Op
 
             // カテゴリを日付順（新しい順）にソート
             let mut categories: Vec<_> = data.categories.iter().collect();
-            categories.sort_by(|a, b| b.1.created_at.cmp(&a.1.created_at));
+            categories.sort_by_key(|c| std::cmp::Reverse(c.1.created_at));
 
             // 各カテゴリを描画
             action = categories
                 .iter()
-                .filter_map(|(cat_name, cat_data)| {
-                    draw_single_category(ui, cat_name, cat_data, selection)
+                .filter_map(|(cat_id, cat_data)| {
+                    draw_single_category(ui, **cat_id, cat_data, selection)
                 })
                 .last();
         });
@@ -47,21 +63,34 @@ pub fn show(ui: &mut egui::Ui, data: &AppData, selection: &SelectionState) -> Op
 /// 1つのカテゴリ（折りたたみヘッダー）の描画
 fn draw_single_category(
     ui: &mut egui::Ui,
-    cat_name: &str,
+    cat_id: CategoryId,
     cat_data: &CategoryData,
     selection: &SelectionState,
 ) -> Option<Action> {
     let mut action = None;
 
-    let header_response = egui::CollapsingHeader::new(cat_name)
-        .id_salt(cat_name)
+    // カテゴリのみが選択中（概要表示中）なら強調
+    let is_selected = selection.category == Some(cat_id) && selection.item.is_none();
+    let aggregate = summarize_category(cat_data).aggregate;
+    let mut header_text = egui::RichText::new(category_header_label(&cat_data.name, aggregate));
+    if is_selected {
+        header_text = header_text.strong();
+    }
+
+    let header_response = egui::CollapsingHeader::new(header_text)
+        .id_salt(cat_id)
         .default_open(true)
         .show(ui, |ui| {
-            if let Some(act) = draw_category_contents(ui, cat_name, cat_data, selection) {
+            if let Some(act) = draw_category_contents(ui, cat_id, cat_data, selection) {
                 action = Some(act);
             }
         });
 
+    // 左クリック: カテゴリ概要を表示
+    if header_response.header_response.clicked() {
+        action = Some(Action::SelectCategory(cat_id));
+    }
+
     // カテゴリに対する右クリックメニュー
     header_response.header_response.context_menu(|ui| {
         let add_item_clicked = ui.button("＋ このカテゴリに項目追加").clicked();
@@ -70,11 +99,17 @@ fn draw_single_category(
         let delete_clicked = ui.button("🗑 このカテゴリを削除").clicked();
 
         let resolved =
-            category_header_menu_action(cat_name, add_item_clicked, edit_clicked, delete_clicked);
+            category_header_menu_action(cat_id, add_item_clicked, edit_clicked, delete_clicked);
         if resolved.is_some() {
             action = resolved;
             ui.close_kind(UiKind::Menu);
         }
+
+        ui.separator();
+        if ui.button("📄 CSVに書き出す...").clicked() {
+            action = Some(Action::ShowCsvExportModal(ExportScope::Category(cat_id)));
+            ui.close_kind(UiKind::Menu);
+        }
     });
 
     action
@@ -83,25 +118,25 @@ fn draw_single_category(
 /// カテゴリの中身描画
 fn draw_category_contents(
     ui: &mut egui::Ui,
-    cat_name: &str,
+    cat_id: CategoryId,
     cat_data: &CategoryData,
     selection: &SelectionState,
 ) -> Option<Action> {
     // 項目を日付順にソート
     let mut items = cat_data.items.iter().collect::<Vec<_>>();
-    items.sort_by(|a, b| b.1.updated_at.cmp(&a.1.updated_at));
+    items.sort_by_key(|i| std::cmp::Reverse(i.1.updated_at));
 
     // 各項目を描画
     let mut action = items
         .iter()
-        .filter_map(|(item_name, _)| draw_single_item(ui, cat_name, item_name, selection))
+        .filter_map(|(item_id, item)| draw_single_item(ui, (cat_id, **item_id), item, selection))
         .last();
 
     ui.add_space(5.0);
 
     // 項目追加ボタン
     if ui.button("＋ 項目").clicked() {
-        action = Some(Action::ShowAddItemModal(cat_name.to_string()));
+        action = Some(Action::ShowAddItemModal(cat_id));
     }
 
     ui.add_space(5.0);
@@ -112,41 +147,40 @@ fn draw_category_contents(
 /// 1項目描画
 fn draw_single_item(
     ui: &mut egui::Ui,
-    cat_name: &str,
-    item_name: &str,
+    location: (CategoryId, ItemId),
+    item: &ItemData,
     selection: &SelectionState,
 ) -> Option<Action> {
     let mut action = None;
+    let (cat_id, item_id) = location;
 
-    let is_selected = selection.category.as_deref() == Some(cat_name)
-        && selection.item.as_deref() == Some(item_name);
+    let is_selected = selection.category == Some(cat_id) && selection.item == Some(item_id);
 
-    let response = ui.selectable_label(is_selected, item_name);
+    let response = ui.selectable_label(is_selected, &item.name);
 
     // 左クリック: 選択
     if response.clicked() {
-        action = Some(Action::SelectItem(
-            cat_name.to_string(),
-            item_name.to_string(),
-        ));
+        action = Some(Action::SelectItem(cat_id, item_id));
     }
 
     // 右クリック: 削除メニュー
     response.context_menu(|ui| {
         // 編集メニュー (一括変更)
         if ui.button("✏ 項目を編集...").clicked() {
-            action = Some(Action::ShowEditItemModal(
-                cat_name.to_string(),
-                item_name.to_string(),
-            ));
+            action = Some(Action::ShowEditItemModal(cat_id, item_id));
             ui.close_kind(egui::UiKind::Menu);
         }
 
         if ui.button("🗑 この項目を削除").clicked() {
-            action = Some(Action::ShowDeleteItemConfirm(
-                cat_name.to_string(),
-                item_name.to_string(),
-            ));
+            action = Some(Action::ShowDeleteItemConfirm(cat_id, item_id));
+            ui.close_kind(UiKind::Menu);
+        }
+
+        ui.separator();
+        if ui.button("📄 CSVに書き出す...").clicked() {
+            action = Some(Action::ShowCsvExportModal(ExportScope::Item(
+                cat_id, item_id,
+            )));
             ui.close_kind(UiKind::Menu);
         }
     });
@@ -161,30 +195,43 @@ mod tests {
     #[test]
     fn category_menu_add_item_opens_add_item_modal() {
         // カテゴリヘッダの右クリックで項目追加を選んだ場合に、項目追加モーダル起動アクションになることを確認する。
-        let action = category_header_menu_action("CatA", true, false, false);
-        assert!(matches!(action, Some(Action::ShowAddItemModal(name)) if name == "CatA"));
+        let cat_id = CategoryId::generate();
+        let action = category_header_menu_action(cat_id, true, false, false);
+        assert!(matches!(action, Some(Action::ShowAddItemModal(id)) if id == cat_id));
     }
 
     #[test]
     fn category_menu_edit_and_delete_actions_are_mapped_correctly() {
         // カテゴリヘッダ右クリックの編集・削除メニューがそれぞれ対応するアクションに変換されることを確認する。
-        let edit = category_header_menu_action("CatA", false, true, false);
-        let delete = category_header_menu_action("CatA", false, false, true);
+        let cat_id = CategoryId::generate();
+        let edit = category_header_menu_action(cat_id, false, true, false);
+        let delete = category_header_menu_action(cat_id, false, false, true);
 
         assert!(matches!(
             edit,
-            Some(Action::ShowEditCategoryModal(name)) if name == "CatA"
+            Some(Action::ShowEditCategoryModal(id)) if id == cat_id
         ));
         assert!(matches!(
             delete,
-            Some(Action::ShowDeleteCategoryConfirm(name)) if name == "CatA"
+            Some(Action::ShowDeleteCategoryConfirm(id)) if id == cat_id
         ));
     }
 
     #[test]
     fn category_menu_prioritizes_add_item_when_multiple_flags_are_true() {
         // 複数フラグが同時に真の場合でも、項目追加メニューを最優先することを確認する。
-        let action = category_header_menu_action("CatA", true, true, true);
-        assert!(matches!(action, Some(Action::ShowAddItemModal(name)) if name == "CatA"));
+        let cat_id = CategoryId::generate();
+        let action = category_header_menu_action(cat_id, true, true, true);
+        assert!(matches!(action, Some(Action::ShowAddItemModal(id)) if id == cat_id));
+    }
+
+    #[test]
+    fn category_header_label_includes_aggregate_when_available() {
+        // 集計値がある場合はカテゴリ名に併記され、ない場合はカテゴリ名のみになることを確認する。
+        assert_eq!(
+            category_header_label("CatA", Some(1234.5)),
+            "CatA  (1,234.50)"
+        );
+        assert_eq!(category_header_label("CatA", None), "CatA");
     }
 }
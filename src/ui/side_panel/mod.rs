@@ -1,6 +1,7 @@
 pub mod category_list;
 
 use crate::action::Action;
+use crate::application::ExportScope;
 use crate::domain::TrackerModel;
 use eframe::egui;
 
@@ -15,6 +16,8 @@ impl SidePanel {
         &mut self,
         ctx: &egui::Context,
         model: &TrackerModel,
+        profiles: (&str, &[String]),
+        history: (bool, bool),
         enabled: bool,
     ) -> Option<Action> {
         let mut action = None;
@@ -29,10 +32,13 @@ impl SidePanel {
 
                 // 上下に要素を先に配置
                 egui::TopBottomPanel::top("header_panel").show_inside(ui, |ui| {
+                    if let Some(a) = self.show_profile_select(ui, profiles) {
+                        action = Some(a);
+                    }
                     ui.heading("カテゴリ一覧");
                 });
                 egui::TopBottomPanel::bottom("footer_panel").show_inside(ui, |ui| {
-                    if let Some(a) = self.show_footer(ui) {
+                    if let Some(a) = self.show_footer(ui, history) {
                         action = Some(a);
                     }
                 });
@@ -48,9 +54,71 @@ impl SidePanel {
         action
     }
 
-    /// フッター描画
-    fn show_footer(&self, ui: &mut egui::Ui) -> Option<Action> {
+    /// プロファイル切り替え（profiles: 使用中のプロファイル名, プロファイル名の一覧）
+    fn show_profile_select(
+        &self,
+        ui: &mut egui::Ui,
+        profiles: (&str, &[String]),
+    ) -> Option<Action> {
         let mut action = None;
+        let (current, names) = profiles;
+
+        ui.horizontal(|ui| {
+            ui.label("プロファイル:");
+            egui::ComboBox::from_id_salt("profile_select")
+                .selected_text(current)
+                .show_ui(ui, |ui| {
+                    names.iter().for_each(|name| {
+                        if ui.selectable_label(name == current, name).clicked() && name != current {
+                            action = Some(Action::SwitchProfile(name.clone()));
+                        }
+                    });
+                    ui.separator();
+                    if ui.button("＋ 新しいプロファイル...").clicked() {
+                        action = Some(Action::ShowAddProfileModal);
+                    }
+                });
+        });
+
+        action
+    }
+
+    /// フッター描画（history: 元に戻せるか, やり直せるか）
+    fn show_footer(&self, ui: &mut egui::Ui, history: (bool, bool)) -> Option<Action> {
+        let mut action = None;
+        let (can_undo, can_redo) = history;
+
+        ui.add_space(3.0);
+
+        ui.columns(2, |columns| {
+            let undo = egui::Button::new("↶ 元に戻す");
+            if columns[0]
+                .add_enabled(can_undo, undo)
+                .on_hover_text("Ctrl+Z")
+                .clicked()
+            {
+                action = Some(Action::Undo);
+            }
+            let redo = egui::Button::new("↷ やり直し");
+            if columns[1]
+                .add_enabled(can_redo, redo)
+                .on_hover_text("Ctrl+Y")
+                .clicked()
+            {
+                action = Some(Action::Redo);
+            }
+        });
+        if ui.small_button("バックアップから復元...").clicked() {
+            action = Some(Action::ShowRestoreBackupModal);
+        }
+        ui.horizontal(|ui| {
+            if ui.small_button("CSV取り込み...").clicked() {
+                action = Some(Action::ShowCsvImportModal);
+            }
+            if ui.small_button("CSV書き出し...").clicked() {
+                action = Some(Action::ShowCsvExportModal(ExportScope::All));
+            }
+        });
 
         ui.add_space(3.0);
 
@@ -2,4 +2,6 @@
 pub struct UiState {
     /// エラーメッセージ
     pub error_message: Option<String>,
+    /// お知らせメッセージ（取り込み結果など）
+    pub notice_message: Option<String>,
 }
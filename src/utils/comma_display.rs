@@ -1,6 +1,7 @@
 use num_format::{Locale, ToFormattedString};
 
 // カンマ変換用拡張関数
+/// 数値をカンマ区切りで表示する拡張
 pub trait CommaDisplay {
     /// カンマ区切りの文字列を返す。
     /// 浮動小数点数の場合、デフォルトの精度が適用されるか、
@@ -1,3 +1,4 @@
+/// カンマ区切りの数値表示
 pub mod comma_display;
-pub mod ime;
+/// 重み付きの平均・分散・標準偏差・回帰直線
 pub mod weighted_statistics;
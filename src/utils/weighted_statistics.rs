@@ -1,3 +1,4 @@
+/// 加重平均（値と重みは同じ長さ）
 pub fn weighted_mean(values: &[f64], weights: &[f64]) -> f64 {
     assert_eq!(values.len(), weights.len());
 
@@ -5,6 +6,7 @@ pub fn weighted_mean(values: &[f64], weights: &[f64]) -> f64 {
     values.iter().zip(weights).map(|(x, w)| x * w).sum::<f64>() / weight_sum
 }
 
+/// 加重分散（重みの合計で割る母分散）
 pub fn weighted_variance(values: &[f64], weights: &[f64]) -> f64 {
     assert_eq!(values.len(), weights.len());
 
@@ -19,10 +21,80 @@ pub fn weighted_variance(values: &[f64], weights: &[f64]) -> f64 {
         / weight_sum
 }
 
+/// 加重標準偏差
 pub fn weighted_std(values: &[f64], weights: &[f64]) -> f64 {
     weighted_variance(values, weights).sqrt()
 }
 
+/// 重み付き最小二乗法で求めた回帰直線（y = intercept + slope * x）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WeightedRegression {
+    /// 傾き（x が 1 増えたときの y の変化量）
+    pub slope: f64,
+    /// 切片（x = 0 での y）
+    pub intercept: f64,
+    /// 傾きの標準誤差（有効件数が 2 以下なら None）
+    pub slope_std_error: Option<f64>,
+}
+
+impl WeightedRegression {
+    /// x での回帰直線の値
+    pub fn value_at(&self, x: f64) -> f64 {
+        self.intercept + self.slope * x
+    }
+}
+
+/// 重み付き最小二乗法で回帰直線を求める（x・y・重みは同じ長さ）
+///
+/// 重みの合計が 0 の場合や、重みのある点の x がすべて同じ場合は None を返す。
+/// 標準誤差は重みの大きさによらないよう、有効件数 (Σw)² / Σw² で自由度を見積もる。
+pub fn weighted_linear_regression(
+    xs: &[f64],
+    ys: &[f64],
+    weights: &[f64],
+) -> Option<WeightedRegression> {
+    assert_eq!(xs.len(), ys.len());
+    assert_eq!(xs.len(), weights.len());
+
+    let weight_sum: f64 = weights.iter().sum();
+    if weight_sum <= 0.0 {
+        return None;
+    }
+    let x_mean = weighted_mean(xs, weights);
+    let y_mean = weighted_mean(ys, weights);
+
+    let (sxx, sxy) = xs
+        .iter()
+        .zip(ys)
+        .zip(weights)
+        .fold((0.0, 0.0), |(sxx, sxy), ((x, y), w)| {
+            let dx = x - x_mean;
+            (sxx + w * dx * dx, sxy + w * dx * (y - y_mean))
+        });
+    if sxx <= 0.0 {
+        return None;
+    }
+    let slope = sxy / sxx;
+    let intercept = y_mean - slope * x_mean;
+
+    let residual_sum: f64 = xs
+        .iter()
+        .zip(ys)
+        .zip(weights)
+        .map(|((x, y), w)| w * (y - intercept - slope * x).powi(2))
+        .sum();
+    let squared_weight_sum: f64 = weights.iter().map(|w| w * w).sum();
+    let effective_count = weight_sum * weight_sum / squared_weight_sum;
+    let slope_std_error =
+        (effective_count > 2.0).then(|| (residual_sum / ((effective_count - 2.0) * sxx)).sqrt());
+
+    Some(WeightedRegression {
+        slope,
+        intercept,
+        slope_std_error,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -55,6 +127,43 @@ mod tests {
         assert_close(std, 0.8660254037844386);
     }
 
+    #[test]
+    fn weighted_regression_matches_ordinary_least_squares_for_equal_weights() {
+        // 重みが等しい場合は通常の最小二乗法と同じ傾き・切片・標準誤差になり、重みの大きさによらないことを確認する。
+        let xs = [0.0, 1.0, 2.0, 3.0];
+        let ys = [1.0, 3.0, 2.0, 5.0];
+        let regression = weighted_linear_regression(&xs, &ys, &[1.0; 4]).unwrap();
+        assert_close(regression.slope, 1.1);
+        assert_close(regression.intercept, 1.1);
+        // 残差平方和 2.7、Sxx 5 より sqrt(2.7 / (2 * 5))
+        assert_close(regression.slope_std_error.unwrap(), 0.27_f64.sqrt());
+        assert_close(regression.value_at(2.0), 3.3);
+
+        let scaled = weighted_linear_regression(&xs, &ys, &[0.5; 4]).unwrap();
+        assert_close(scaled.slope, regression.slope);
+        assert_close(
+            scaled.slope_std_error.unwrap(),
+            regression.slope_std_error.unwrap(),
+        );
+    }
+
+    #[test]
+    fn weighted_regression_follows_heavier_points_and_rejects_degenerate_input() {
+        // 重み 0 の点は無視され、x が1種類しかない場合や有効件数が 2 以下の場合の扱いを確認する。
+        let xs = [0.0, 1.0, 2.0];
+        let ys = [100.0, 2.0, 4.0];
+        let regression = weighted_linear_regression(&xs, &ys, &[0.0, 1.0, 1.0]).unwrap();
+        assert_close(regression.slope, 2.0);
+        assert_close(regression.intercept, 0.0);
+        assert_eq!(regression.slope_std_error, None);
+
+        assert_eq!(
+            weighted_linear_regression(&[1.0, 1.0], &[1.0, 2.0], &[1.0, 1.0]),
+            None
+        );
+        assert_eq!(weighted_linear_regression(&xs, &ys, &[0.0; 3]), None);
+    }
+
     #[test]
     #[should_panic]
     fn weighted_mean_panics_on_length_mismatch() {